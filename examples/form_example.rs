@@ -0,0 +1,47 @@
+extern crate rustubble;
+use crossterm::{
+    cursor::MoveTo,
+    execute,
+    terminal::{Clear, ClearType},
+};
+
+use rustubble::{
+    event::PromptResult,
+    form::{handle_form, Field, Form, Group, Value},
+};
+
+fn main() -> rustubble::Result<()> {
+    let mut form = Form::new(vec![
+        Group::new(vec![
+            Field::input("name", "Project name").required(),
+            Field::select("license", "License", &["MIT", "Apache-2.0", "GPL-3.0"]),
+        ])
+        .with_title("New project")
+        .with_description("Shift+Tab goes back to an earlier answer"),
+        Group::new(vec![
+            Field::multi_select("features", "Features", &["cli", "serde", "async"]),
+            Field::text("description", "Description", 3),
+            Field::confirm("git", "Initialize a git repository?"),
+        ])
+        .with_title("Details"),
+    ]);
+
+    execute!(std::io::stdout(), Clear(ClearType::All))?;
+    let result = handle_form(&mut form, 2, 1)?;
+    execute!(std::io::stdout(), Clear(ClearType::All), MoveTo(0, 0))?;
+
+    match result {
+        PromptResult::Submitted(values) => {
+            for (key, value) in values.iter() {
+                match value {
+                    Value::Choices(choices) => println!("{}: {}", key, choices.join(", ")),
+                    value => println!("{}: {:?}", key, value),
+                }
+            }
+        }
+        PromptResult::Cancelled => println!("Cancelled"),
+        // Ctrl+C exits like an interrupted shell command
+        PromptResult::Interrupted => std::process::exit(130),
+    }
+    Ok(())
+}
@@ -0,0 +1,31 @@
+extern crate rustubble;
+use rustubble::event::PromptResult;
+use rustubble::inline::{run_inline, InlineEnd};
+use rustubble::input::TextInput;
+use rustubble::menu_list::Menu;
+
+// Two prompts that stay inside the shell's scrollback, like a project generator.
+fn main() -> rustubble::Result<()> {
+    println!("Creating a new project");
+
+    let mut name_input = TextInput::new(Some("my-app"), 0, "", "Project name", None, ">");
+    let name = match run_inline(&mut name_input, InlineEnd::Summary("Project name".into()))? {
+        PromptResult::Submitted(name) => name,
+        PromptResult::Cancelled => return Ok(()),
+        PromptResult::Interrupted => std::process::exit(130),
+    };
+
+    let mut template_menu = Menu::new(
+        "Template".to_string(),
+        "Pick a starting point".to_string(),
+        vec![
+            "Binary".to_string(),
+            "Library".to_string(),
+            "Workspace".to_string(),
+        ],
+    );
+    let template = run_inline(&mut template_menu, InlineEnd::Summary("Template".into()))?;
+
+    println!("Done: {} ({:?})", name, template.ok());
+    Ok(())
+}
@@ -1,10 +1,6 @@
-use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
 use rustubble::list::{handle_list, Item, ItemList};
-use std::io;
-
-fn main() -> Result<(), io::Error> {
-    enable_raw_mode()?;
 
+fn main() -> rustubble::Result<()> {
     let mut list = ItemList::new(
         "Groceries".to_string(),
         vec![
@@ -56,9 +52,9 @@ fn main() -> Result<(), io::Error> {
     );
 
     let (x, y) = (5, 5);
-    let list = handle_list(&mut list, x, y);
+    let list = handle_list(&mut list, x, y)?;
 
     println!("Operation completed.{:?}", list);
 
-    disable_raw_mode()
+    Ok(())
 }
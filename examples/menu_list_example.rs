@@ -1,10 +1,6 @@
-use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
 use rustubble::menu_list::{handle_menu_list, Menu};
-use std::io;
-
-fn main() -> Result<(), io::Error> {
-    enable_raw_mode()?;
 
+fn main() -> rustubble::Result<()> {
     let mut new_menu = Menu::new(
         "Main Menu".to_string(),
         "Select an option:".to_string(),
@@ -18,8 +14,8 @@ fn main() -> Result<(), io::Error> {
 
     let (x, y) = (5, 5);
 
-    let selected_menu = handle_menu_list(&mut new_menu, x, y);
+    let selected_menu = handle_menu_list(&mut new_menu, x, y)?;
 
     println!("Selected Menu: {:?}", selected_menu);
-    disable_raw_mode()
+    Ok(())
 }
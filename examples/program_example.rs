@@ -0,0 +1,77 @@
+extern crate rustubble;
+use crossterm::event::{KeyCode, KeyModifiers};
+use crossterm::style::Color;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::Frame;
+use rustubble::input::TextInput;
+use rustubble::program::{Cmd, Model, Msg, Program};
+use rustubble::spinner::Spinner;
+use std::time::Duration;
+
+// A text input and a spinner sharing one screen.
+struct App {
+    input: TextInput,
+    spinner: Spinner,
+    submitted: Option<String>,
+}
+
+impl Model for App {
+    fn update(&mut self, msg: Msg) -> Cmd {
+        if let Msg::Key(key) = &msg {
+            match key.code {
+                KeyCode::Esc => return Cmd::quit(),
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    return Cmd::quit()
+                }
+                KeyCode::Enter => {
+                    self.submitted = Some(self.input.value().to_string());
+                    return Cmd::quit();
+                }
+                _ => {}
+            }
+        }
+
+        Cmd::batch([self.spinner.update(msg.clone()), self.input.update(msg)])
+    }
+
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .margin(2)
+            .constraints([Constraint::Length(2), Constraint::Min(6)])
+            .split(area);
+
+        self.spinner.view(frame, chunks[0]);
+        self.input.view(frame, chunks[1]);
+    }
+}
+
+fn main() -> rustubble::Result<()> {
+    let app = App {
+        input: TextInput::new(
+            Some("Type here..."),
+            2,
+            "",
+            "What are we waiting for?",
+            Some("Enter to submit, Esc to quit"),
+            ">",
+        ),
+        spinner: Spinner::new(
+            Color::Rgb {
+                r: 0,
+                g: 255,
+                b: 255,
+            },
+            "Loading... Please wait.".to_string(),
+            "Dots2",
+        )?,
+        submitted: None,
+    };
+
+    let app = Program::new(app)
+        .with_tick_rate(Duration::from_millis(80))
+        .run()?;
+
+    println!("Submitted: {:?}", app.submitted);
+    Ok(())
+}
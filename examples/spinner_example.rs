@@ -1,14 +1,11 @@
 extern crate rustubble;
-use std::io::stdout;
-
-use crossterm::execute;
 use crossterm::style::Color;
-use crossterm::terminal::{EnterAlternateScreen, LeaveAlternateScreen};
 use rustubble::spinner::handle_spinner;
 use rustubble::spinner::Spinner;
+use rustubble::tui::{SessionOptions, TerminalSession};
 
-fn main() {
-    execute!(stdout(), EnterAlternateScreen).unwrap();
+fn main() -> rustubble::Result<()> {
+    let session = TerminalSession::with_options(SessionOptions::new().alternate_screen(true))?;
 
     let spinner = Spinner::new(
         Color::Rgb {
@@ -18,11 +15,12 @@ fn main() {
         },
         "Loading... Please wait.".to_string(),
         "FingerDance",
-    );
+    )?;
 
     let (x, y) = (10, 10);
-    handle_spinner(&spinner, x, y);
+    handle_spinner(&spinner, x, y)?;
 
-    execute!(stdout(), LeaveAlternateScreen).unwrap();
+    session.restore()?;
     println!("Operation completed.");
+    Ok(())
 }
@@ -1,20 +1,15 @@
 extern crate rustubble;
-use crossterm::execute;
-use crossterm::terminal::{
-    disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
-};
 use rustubble::stopwatch::{handle_stopwatch, StopWatch};
-use std::io;
+use rustubble::tui::{SessionOptions, TerminalSession};
 
-fn main() {
-    execute!(io::stdout(), EnterAlternateScreen).unwrap();
-    enable_raw_mode().unwrap();
+fn main() -> rustubble::Result<()> {
+    let session = TerminalSession::with_options(SessionOptions::new().alternate_screen(true))?;
     let mut time = StopWatch::new();
 
     let (x, y) = (5, 5);
-    handle_stopwatch(&mut time, x, y);
+    let elapsed = handle_stopwatch(&mut time, x, y)?;
 
-    execute!(io::stdout(), LeaveAlternateScreen).unwrap();
-
-    disable_raw_mode().unwrap();
+    session.restore()?;
+    println!("Stopwatch: {:?}", elapsed);
+    Ok(())
 }
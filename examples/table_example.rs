@@ -1,17 +1,9 @@
 use std::vec;
 
-use crossterm::{
-    execute,
-    style::{Color, SetForegroundColor},
-    terminal::{disable_raw_mode, enable_raw_mode},
-};
-
 extern crate rustubble;
 use rustubble::table::{handle_table, Table};
 
-fn main() -> std::io::Result<()> {
-    enable_raw_mode()?; // Enable raw mode for direct terminal manipulation
-
+fn main() -> rustubble::Result<()> {
     let headers = vec![
         "Rank".to_string(),
         "City".to_string(),
@@ -68,11 +60,10 @@ fn main() -> std::io::Result<()> {
     let mut table = Table::new(headers, data, 0, 3, 5); // Selected row is 0, padding is 1
 
     let (x, y) = (5, 5);
-    handle_table(&mut table, x, y);
+    let selected = handle_table(&mut table, x, y)?;
 
-    // Clean up the terminal
-    disable_raw_mode()?;
     // Clean up before exiting
-    table.show_cursor();
+    table.show_cursor()?;
+    println!("Selected row: {:?}", selected);
     Ok(())
 }
@@ -1,9 +1,20 @@
 use crossterm::style::{Color, ResetColor, SetForegroundColor, StyledContent, Stylize};
 use crossterm::ExecutableCommand;
-use std::io::{stdout, Write};
+use ratatui::{buffer::Buffer, style::Color as UiColor};
+use std::{
+    env,
+    io::{stdout, Write},
+    sync::Mutex,
+    time::Duration,
+};
+
+use crate::Result;
 
 pub mod custom {
     use crossterm::style::Color;
+    use ratatui::style::Color as UiColor;
+
+    use super::AdaptiveColor;
 
     pub const PURPLE: Color = Color::Rgb {
         r: 122,
@@ -40,9 +51,364 @@ pub mod custom {
         g: 165,
         b: 0,
     };
+
+    /// [`PURPLE`], darkened so it stays readable on light backgrounds.
+    pub const ADAPTIVE_PURPLE: AdaptiveColor =
+        AdaptiveColor::new(UiColor::Rgb(88, 78, 196), UiColor::Rgb(122, 114, 229));
+    /// [`DARK_WHITE`], which turns into a warm gray on light backgrounds.
+    pub const ADAPTIVE_DARK_WHITE: AdaptiveColor =
+        AdaptiveColor::new(UiColor::Rgb(112, 101, 86), UiColor::Rgb(218, 208, 192));
+}
+
+/// How many colors the terminal can show, from none to 24-bit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColorProfile {
+    NoColor,
+    Ansi16,
+    Ansi256,
+    TrueColor,
+}
+
+// The 16 ANSI colors in palette order, with xterm's default RGB values
+const ANSI16: [(UiColor, Color, (u8, u8, u8)); 16] = [
+    (UiColor::Black, Color::Black, (0, 0, 0)),
+    (UiColor::Red, Color::DarkRed, (205, 0, 0)),
+    (UiColor::Green, Color::DarkGreen, (0, 205, 0)),
+    (UiColor::Yellow, Color::DarkYellow, (205, 205, 0)),
+    (UiColor::Blue, Color::DarkBlue, (0, 0, 238)),
+    (UiColor::Magenta, Color::DarkMagenta, (205, 0, 205)),
+    (UiColor::Cyan, Color::DarkCyan, (0, 205, 205)),
+    (UiColor::Gray, Color::Grey, (229, 229, 229)),
+    (UiColor::DarkGray, Color::DarkGrey, (127, 127, 127)),
+    (UiColor::LightRed, Color::Red, (255, 0, 0)),
+    (UiColor::LightGreen, Color::Green, (0, 255, 0)),
+    (UiColor::LightYellow, Color::Yellow, (255, 255, 0)),
+    (UiColor::LightBlue, Color::Blue, (92, 92, 255)),
+    (UiColor::LightMagenta, Color::Magenta, (255, 0, 255)),
+    (UiColor::LightCyan, Color::Cyan, (0, 255, 255)),
+    (UiColor::White, Color::White, (255, 255, 255)),
+];
+
+// Channel values of the 6x6x6 cube in the 256 color palette
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+static PROFILE: Mutex<Option<ColorProfile>> = Mutex::new(None);
+
+/// The profile colors are mapped to before they are written, detected from
+/// the environment on first use unless [`set_color_profile`] was called.
+pub fn color_profile() -> ColorProfile {
+    let mut profile = PROFILE.lock().unwrap_or_else(|err| err.into_inner());
+    *profile.get_or_insert_with(ColorProfile::detect)
+}
+
+/// Override the detected profile, e.g. from a `--color` command line flag.
+pub fn set_color_profile(profile: ColorProfile) {
+    *PROFILE.lock().unwrap_or_else(|err| err.into_inner()) = Some(profile);
+}
+
+impl ColorProfile {
+    /// Detect the profile from `NO_COLOR`, `COLORTERM` and `TERM`.
+    pub fn detect() -> Self {
+        Self::from_env(|name| env::var(name).ok())
+    }
+
+    fn from_env(var: impl Fn(&str) -> Option<String>) -> Self {
+        // https://no-color.org: any non-empty value disables color
+        if var("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+            return ColorProfile::NoColor;
+        }
+        let colorterm = var("COLORTERM").unwrap_or_default().to_lowercase();
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            return ColorProfile::TrueColor;
+        }
+        // Windows Terminal supports 24-bit color but sets no TERM
+        if var("WT_SESSION").is_some() {
+            return ColorProfile::TrueColor;
+        }
+        let term = var("TERM").unwrap_or_default().to_lowercase();
+        if term == "dumb" {
+            ColorProfile::NoColor
+        } else if term.contains("truecolor") || term.contains("24bit") || term.contains("direct") {
+            ColorProfile::TrueColor
+        } else if term.contains("256color") {
+            ColorProfile::Ansi256
+        } else {
+            ColorProfile::Ansi16
+        }
+    }
+
+    /// The closest color this profile can show.
+    pub fn adapt(self, color: UiColor) -> UiColor {
+        match (self, color) {
+            (_, UiColor::Reset) | (ColorProfile::TrueColor, _) => color,
+            (ColorProfile::NoColor, _) => UiColor::Reset,
+            (ColorProfile::Ansi256, UiColor::Rgb(r, g, b)) => {
+                UiColor::Indexed(nearest_256((r, g, b)))
+            }
+            (ColorProfile::Ansi256, _) => color,
+            (ColorProfile::Ansi16, UiColor::Rgb(..) | UiColor::Indexed(_)) => {
+                let rgb = ui_to_rgb(color).unwrap_or_default();
+                ANSI16[nearest_16(rgb)].0
+            }
+            (ColorProfile::Ansi16, _) => color,
+        }
+    }
+
+    /// [`ColorProfile::adapt`] for crossterm colors.
+    pub fn adapt_crossterm(self, color: Color) -> Color {
+        match self.adapt(color.into()) {
+            UiColor::Reset => Color::Reset,
+            UiColor::Rgb(r, g, b) => Color::Rgb { r, g, b },
+            UiColor::Indexed(i) if i >= 16 => Color::AnsiValue(i),
+            UiColor::Indexed(i) => ANSI16[i as usize].1,
+            named => ANSI16
+                .iter()
+                .find(|(ui, ..)| *ui == named)
+                .map_or(Color::Reset, |(_, color, _)| *color),
+        }
+    }
+
+    /// Map the colors of every cell in `buffer` to this profile.
+    pub fn adapt_buffer(self, buffer: &mut Buffer) {
+        if self == ColorProfile::TrueColor {
+            return;
+        }
+        for cell in &mut buffer.content {
+            cell.fg = self.adapt(cell.fg);
+            cell.bg = self.adapt(cell.bg);
+        }
+    }
+}
+
+/// Whether the terminal draws on a light or a dark background.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Background {
+    Light,
+    Dark,
+}
+
+/// A color with one value for light and one for dark terminal backgrounds,
+/// picked by [`AdaptiveColor::resolve`] when it is drawn.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AdaptiveColor {
+    pub light: UiColor,
+    pub dark: UiColor,
+}
+
+impl AdaptiveColor {
+    pub const fn new(light: UiColor, dark: UiColor) -> Self {
+        AdaptiveColor { light, dark }
+    }
+
+    /// The color for the terminal's [`background`].
+    pub fn resolve(self) -> UiColor {
+        self.for_background(background())
+    }
+
+    pub fn for_background(self, background: Background) -> UiColor {
+        match background {
+            Background::Light => self.light,
+            Background::Dark => self.dark,
+        }
+    }
+}
+
+/// The same color on either background.
+impl From<UiColor> for AdaptiveColor {
+    fn from(color: UiColor) -> Self {
+        AdaptiveColor::new(color, color)
+    }
+}
+
+static BACKGROUND: Mutex<Option<Background>> = Mutex::new(None);
+
+// How long a terminal gets to answer the OSC 11 query
+const QUERY_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// The background adaptive colors resolve against: the one detected when
+/// the first [`TerminalSession`](crate::tui::TerminalSession) started, or
+/// the one given to [`set_background`]. Until then it is dark. This never
+/// talks to the terminal, so it is safe to call while drawing.
+pub fn background() -> Background {
+    let background = BACKGROUND.lock().unwrap_or_else(|err| err.into_inner());
+    background.unwrap_or(Background::Dark)
+}
+
+// Ask the terminal for its background unless it is already known. Only
+// called where a session starts, before keys are read and outside a frame.
+pub(crate) fn detect_background() {
+    let mut background = BACKGROUND.lock().unwrap_or_else(|err| err.into_inner());
+    background.get_or_insert_with(Background::detect);
+}
+
+/// Override the detected background, e.g. from a `--light` command line
+/// flag, or supply it when drawing without a
+/// [`TerminalSession`](crate::tui::TerminalSession).
+pub fn set_background(background: Background) {
+    *BACKGROUND.lock().unwrap_or_else(|err| err.into_inner()) = Some(background);
+}
+
+impl Background {
+    /// Ask the terminal for its background color with OSC 11, then fall
+    /// back to `COLORFGBG`, then to a dark background.
+    pub fn detect() -> Self {
+        query_background(QUERY_TIMEOUT)
+            .or_else(|| Self::from_colorfgbg(&env::var("COLORFGBG").ok()?))
+            .unwrap_or(Background::Dark)
+    }
+
+    fn from_rgb((r, g, b): (f32, f32, f32)) -> Self {
+        let luminance = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+        if luminance > 0.5 {
+            Background::Light
+        } else {
+            Background::Dark
+        }
+    }
+
+    // rxvt style "fg;bg" or "fg;default;bg", where bg is a palette index
+    fn from_colorfgbg(value: &str) -> Option<Self> {
+        let bg: u8 = value.rsplit(';').next()?.trim().parse().ok()?;
+        Some(if matches!(bg, 7 | 9..=15) {
+            Background::Light
+        } else {
+            Background::Dark
+        })
+    }
+
+    // A reply such as "\x1b]11;rgb:ffff/ffff/dddd\x07", with one to four hex
+    // digits per channel
+    fn from_osc11(reply: &str) -> Option<Self> {
+        let (_, rgb) = reply.split_once("rgb:")?;
+        let rgb = rgb.trim_end_matches(['\x07', '\x1b', '\\']);
+        let mut channels = rgb.split('/').map(|channel| {
+            let value = u32::from_str_radix(channel, 16).ok()?;
+            let max = match channel.len() {
+                len @ 1..=4 => (1u32 << (4 * len)) - 1,
+                _ => return None,
+            };
+            Some(value as f32 / max as f32)
+        });
+        let rgb = (channels.next()??, channels.next()??, channels.next()??);
+        Some(Self::from_rgb(rgb))
+    }
+}
+
+#[cfg(unix)]
+fn query_background(timeout: Duration) -> Option<Background> {
+    use crossterm::terminal::{disable_raw_mode, enable_raw_mode, is_raw_mode_enabled};
+    use std::io::{stdin, IsTerminal};
+    use std::os::fd::AsRawFd;
+
+    if !stdin().is_terminal() || !stdout().is_terminal() {
+        return None;
+    }
+    // The reply arrives as input, so it must not wait for a newline or echo
+    let was_raw = is_raw_mode_enabled().ok()?;
+    if !was_raw {
+        enable_raw_mode().ok()?;
+    }
+    let reply = read_osc11_reply(stdin().as_raw_fd(), timeout);
+    if !was_raw {
+        disable_raw_mode().ok()?;
+    }
+    Background::from_osc11(&reply?)
+}
+
+#[cfg(not(unix))]
+fn query_background(_timeout: Duration) -> Option<Background> {
+    None
+}
+
+// Reads byte by byte from the file descriptor, since the buffered stdin
+// handle would hide bytes from `poll` and keep the ones after the reply
+#[cfg(unix)]
+fn read_osc11_reply(fd: std::os::fd::RawFd, timeout: Duration) -> Option<String> {
+    use std::time::Instant;
+
+    let mut stdout = stdout();
+    stdout.write_all(b"\x1b]11;?\x07").ok()?;
+    stdout.flush().ok()?;
+
+    let deadline = Instant::now() + timeout;
+    let mut reply = Vec::new();
+    // Terminals end the reply with BEL or with ST (ESC \)
+    while !(reply.ends_with(b"\x07") || reply.ends_with(b"\x1b\\")) {
+        let left = deadline.checked_duration_since(Instant::now())?;
+        let mut pollfd = libc::pollfd {
+            fd,
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        // SAFETY: `pollfd` is a single valid entry that outlives the call
+        let ready = unsafe { libc::poll(&mut pollfd, 1, left.as_millis() as libc::c_int) };
+        if ready <= 0 {
+            return None;
+        }
+        let mut byte = 0u8;
+        // SAFETY: reads at most one byte into `byte`
+        if unsafe { libc::read(fd, (&mut byte as *mut u8).cast(), 1) } != 1 {
+            return None;
+        }
+        reply.push(byte);
+    }
+    String::from_utf8(reply).ok()
+}
+
+/// The RGB value of a color, or `None` for [`UiColor::Reset`].
+fn ui_to_rgb(color: UiColor) -> Option<(u8, u8, u8)> {
+    match color {
+        UiColor::Reset => None,
+        UiColor::Rgb(r, g, b) => Some((r, g, b)),
+        UiColor::Indexed(i @ 0..=15) => Some(ANSI16[i as usize].2),
+        UiColor::Indexed(i @ 16..=231) => {
+            let i = i - 16;
+            let level = |n: u8| CUBE_LEVELS[n as usize];
+            Some((level(i / 36), level(i / 6 % 6), level(i % 6)))
+        }
+        UiColor::Indexed(i) => {
+            let gray = 8 + 10 * (i - 232);
+            Some((gray, gray, gray))
+        }
+        named => ANSI16
+            .iter()
+            .find(|(ui, ..)| *ui == named)
+            .map(|(.., rgb)| *rgb),
+    }
+}
+
+fn distance((r1, g1, b1): (u8, u8, u8), (r2, g2, b2): (u8, u8, u8)) -> u32 {
+    let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2) as u32;
+    d(r1, r2) + d(g1, g2) + d(b1, b2)
+}
+
+fn nearest_16(rgb: (u8, u8, u8)) -> usize {
+    (0..ANSI16.len())
+        .min_by_key(|&i| distance(rgb, ANSI16[i].2))
+        .unwrap_or_default()
+}
+
+// Only the cube and the gray ramp are considered, since terminals often
+// remap the first 16 entries
+fn nearest_256(rgb: (u8, u8, u8)) -> u8 {
+    let level = |c: u8| {
+        (0..CUBE_LEVELS.len())
+            .min_by_key(|&i| (CUBE_LEVELS[i] as i32 - c as i32).abs())
+            .unwrap_or_default() as u8
+    };
+    let (r, g, b) = (level(rgb.0), level(rgb.1), level(rgb.2));
+    let cube = 16 + 36 * r + 6 * g + b;
+
+    let average = (rgb.0 as u32 + rgb.1 as u32 + rgb.2 as u32) / 3;
+    let gray = 232 + ((average.saturating_sub(3) / 10).min(23)) as u8;
+
+    [cube, gray]
+        .into_iter()
+        .min_by_key(|&i| distance(rgb, ui_to_rgb(UiColor::Indexed(i)).unwrap_or_default()))
+        .unwrap_or(cube)
 }
 
-pub fn display_gradient_text(text: &str, start_color: Color, end_color: Color) {
+pub fn display_gradient_text(text: &str, start_color: Color, end_color: Color) -> Result<()> {
     // Split the text into lines
     let lines: Vec<&str> = text.lines().collect();
 
@@ -51,47 +417,40 @@ pub fn display_gradient_text(text: &str, start_color: Color, end_color: Color) {
         let gradient_ratio = i as f32 / lines.len() as f32;
         let blended_color = blend_color(start_color, end_color, gradient_ratio);
         let mut stdout = stdout();
-        stdout.execute(SetForegroundColor(blended_color)).unwrap();
-        writeln!(stdout, "{}", line).unwrap();
-        stdout.execute(ResetColor).unwrap();
+        stdout.execute(SetForegroundColor(
+            color_profile().adapt_crossterm(blended_color),
+        ))?;
+        writeln!(stdout, "{}", line)?;
+        stdout.execute(ResetColor)?;
     }
+    Ok(())
 }
 
-pub fn display_color_text(text: &str, color: Color) {
+pub fn display_color_text(text: &str, color: Color) -> Result<()> {
     let mut stdout = stdout();
-    stdout.execute(SetForegroundColor(color)).unwrap();
-    writeln!(stdout, "{}", text).unwrap();
-    stdout.execute(ResetColor).unwrap();
+    stdout.execute(SetForegroundColor(color_profile().adapt_crossterm(color)))?;
+    writeln!(stdout, "{}", text)?;
+    stdout.execute(ResetColor)?;
+    Ok(())
 }
 
 pub fn return_color_text(text: &str, color: Color) -> StyledContent<String> {
     String::from(text).with(color)
 }
 
+/// Mix `start` and `end`, `ratio` of the way from one to the other. Named and
+/// indexed colors are blended through their usual RGB values, and the result
+/// is mapped to the terminal's [`ColorProfile`] when it is written.
 pub fn blend_color(start: Color, end: Color, ratio: f32) -> Color {
-    if let (
-        Color::Rgb {
-            r: r1,
-            g: g1,
-            b: b1,
+    match (ui_to_rgb(start.into()), ui_to_rgb(end.into())) {
+        (Some((r1, g1, b1)), Some((r2, g2, b2))) => Color::Rgb {
+            r: blend_value(r1, r2, ratio),
+            g: blend_value(g1, g2, ratio),
+            b: blend_value(b1, b2, ratio),
         },
-        Color::Rgb {
-            r: r2,
-            g: g2,
-            b: b2,
-        },
-    ) = (start, end)
-    {
-        let blended_r = blend_value(r1, r2, ratio);
-        let blended_g = blend_value(g1, g2, ratio);
-        let blended_b = blend_value(b1, b2, ratio);
-        Color::Rgb {
-            r: blended_r,
-            g: blended_g,
-            b: blended_b,
-        }
-    } else {
-        Color::White
+        // The terminal's default color can't be mixed, so switch halfway
+        _ if ratio < 0.5 => start,
+        _ => end,
     }
 }
 
@@ -106,7 +465,87 @@ mod tests {
 
     #[test]
     fn test_display_color_text() {
-        display_color_text("Hello, World!", Color::Green);
+        display_color_text("Hello, World!", Color::Green).unwrap();
         // Add more tests for different scenarios
     }
+
+    #[test]
+    fn test_detect_profile() {
+        let detect = |vars: &[(&str, &str)]| {
+            ColorProfile::from_env(|name| {
+                vars.iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| value.to_string())
+            })
+        };
+        assert_eq!(
+            detect(&[("COLORTERM", "truecolor")]),
+            ColorProfile::TrueColor
+        );
+        assert_eq!(detect(&[("TERM", "xterm-256color")]), ColorProfile::Ansi256);
+        assert_eq!(detect(&[("TERM", "xterm")]), ColorProfile::Ansi16);
+        assert_eq!(detect(&[("TERM", "dumb")]), ColorProfile::NoColor);
+        assert_eq!(
+            detect(&[("NO_COLOR", "1"), ("COLORTERM", "truecolor")]),
+            ColorProfile::NoColor
+        );
+        assert_eq!(
+            detect(&[("NO_COLOR", ""), ("TERM", "xterm")]),
+            ColorProfile::Ansi16
+        );
+    }
+
+    #[test]
+    fn test_adapt_colors() {
+        let purple = UiColor::Rgb(122, 114, 229);
+        assert_eq!(ColorProfile::TrueColor.adapt(purple), purple);
+        assert_eq!(ColorProfile::Ansi256.adapt(purple), UiColor::Indexed(98));
+        assert_eq!(ColorProfile::Ansi16.adapt(purple), UiColor::LightBlue);
+        assert_eq!(ColorProfile::NoColor.adapt(purple), UiColor::Reset);
+        assert_eq!(
+            ColorProfile::Ansi256.adapt(UiColor::Rgb(128, 128, 128)),
+            UiColor::Indexed(244)
+        );
+        assert_eq!(
+            ColorProfile::Ansi16.adapt(UiColor::Indexed(196)),
+            UiColor::LightRed
+        );
+        assert_eq!(
+            ColorProfile::Ansi16.adapt_crossterm(Color::Rgb { r: 0, g: 0, b: 0 }),
+            Color::Black
+        );
+    }
+
+    #[test]
+    fn test_detect_background() {
+        let light = Background::from_osc11("\x1b]11;rgb:ffff/ffff/dddd\x07");
+        assert_eq!(light, Some(Background::Light));
+        let dark = Background::from_osc11("\x1b]11;rgb:28/2a/36\x1b\\");
+        assert_eq!(dark, Some(Background::Dark));
+        assert_eq!(Background::from_osc11("\x1b]11;?\x07"), None);
+
+        assert_eq!(Background::from_colorfgbg("0;15"), Some(Background::Light));
+        assert_eq!(
+            Background::from_colorfgbg("15;default;0"),
+            Some(Background::Dark)
+        );
+        assert_eq!(Background::from_colorfgbg("default"), None);
+
+        let color = AdaptiveColor::new(UiColor::Black, UiColor::White);
+        assert_eq!(color.for_background(Background::Light), UiColor::Black);
+        assert_eq!(color.for_background(Background::Dark), UiColor::White);
+    }
+
+    #[test]
+    fn test_blend_named_colors() {
+        assert_eq!(
+            blend_color(Color::Black, Color::White, 0.5),
+            Color::Rgb {
+                r: 127,
+                g: 127,
+                b: 127
+            }
+        );
+        assert_eq!(blend_color(Color::Reset, Color::Red, 0.2), Color::Reset);
+    }
 }
@@ -0,0 +1,338 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::unicode;
+
+/// Editable lines of text with a cursor, shared by
+/// [`TextInput`](crate::input::TextInput) and [`TextArea`](crate::text_area::TextArea).
+///
+/// The cursor moves by grapheme cluster, so an accented letter typed as `e`
+/// plus a combining accent or an emoji made of several code points is
+/// stepped over and deleted as one character. Screen positions are measured
+/// in display columns, where East Asian wide characters take two.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TextBuffer {
+    lines: Vec<String>,
+    row: usize,
+    // Counted in grapheme clusters
+    column: usize,
+}
+
+impl Default for TextBuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TextBuffer {
+    /// An empty buffer with a single line.
+    pub fn new() -> Self {
+        Self {
+            lines: vec![String::new()],
+            row: 0,
+            column: 0,
+        }
+    }
+
+    /// A buffer holding `text`, split into lines at `\n`, `\r\n` or `\r`,
+    /// with the cursor at the end.
+    pub fn from_text(text: &str) -> Self {
+        let mut buffer = Self::new();
+        buffer.insert_str(text);
+        buffer
+    }
+
+    /// The text with lines joined by `\n`.
+    pub fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+
+    /// The line at `row`, or an empty string past the last line.
+    pub fn line(&self, row: usize) -> &str {
+        self.lines.get(row).map_or("", String::as_str)
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.len() == 1 && self.lines[0].is_empty()
+    }
+
+    /// The cursor as `(row, column)`, with the column counted in grapheme
+    /// clusters.
+    pub fn cursor(&self) -> (usize, usize) {
+        (self.row, self.column)
+    }
+
+    /// Move the cursor, clamped to the text.
+    pub fn set_cursor(&mut self, row: usize, column: usize) {
+        self.row = row.min(self.lines.len() - 1);
+        self.column = column.min(grapheme_count(&self.lines[self.row]));
+    }
+
+    /// Display width of the cursor's line up to the cursor, which is where
+    /// the cursor is drawn relative to the start of the line.
+    pub fn cursor_width(&self) -> usize {
+        let line = &self.lines[self.row];
+        unicode::width(&line[..byte_offset(line, self.column)])
+    }
+
+    /// The column on `row` drawn at display column `width`. A position in
+    /// the middle of a wide character falls before it.
+    pub fn column_at_width(&self, row: usize, width: usize) -> usize {
+        let mut start = 0;
+        for (column, grapheme) in self.line(row).graphemes(true).enumerate() {
+            start += unicode::width(grapheme);
+            if start > width {
+                return column;
+            }
+        }
+        grapheme_count(self.line(row))
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Insert `c` before the cursor, starting a new line for `\n`.
+    pub fn insert_char(&mut self, c: char) {
+        if c == '\n' {
+            self.insert_newline();
+        } else {
+            self.insert_in_line(c.encode_utf8(&mut [0; 4]));
+        }
+    }
+
+    /// Insert `text` before the cursor, which ends up after it. Line breaks
+    /// may be `\n`, `\r\n` or a lone `\r`, which many terminals paste.
+    ///
+    /// The whole text goes in as one edit, so pasting thousands of lines
+    /// costs about as much as copying them.
+    pub fn insert_str(&mut self, text: &str) {
+        let mut pasted = split_lines(text);
+        let first = pasted.next().unwrap_or_default();
+        let rest: Vec<&str> = pasted.collect();
+        if rest.is_empty() {
+            self.insert_in_line(first);
+            return;
+        }
+
+        let line = &mut self.lines[self.row];
+        let tail = line.split_off(byte_offset(line, self.column));
+        line.push_str(first);
+        let last = rest.len() - 1;
+        self.column = grapheme_count(rest[last]);
+        let mut added: Vec<String> = rest.into_iter().map(String::from).collect();
+        added[last].push_str(&tail);
+        let at = self.row + 1;
+        self.row += added.len();
+        self.lines.splice(at..at, added);
+    }
+
+    /// Split the line at the cursor.
+    pub fn insert_newline(&mut self) {
+        let line = &mut self.lines[self.row];
+        let rest = line.split_off(byte_offset(line, self.column));
+        self.lines.insert(self.row + 1, rest);
+        self.row += 1;
+        self.column = 0;
+    }
+
+    // `text` must not contain a newline
+    fn insert_in_line(&mut self, text: &str) {
+        let line = &mut self.lines[self.row];
+        let start = byte_offset(line, self.column);
+        line.insert_str(start, text);
+        // A combining mark joins the character before it rather than adding one
+        self.column = grapheme_count(&line[..start + text.len()]);
+    }
+
+    /// Delete the character before the cursor, joining the line to the
+    /// previous one at its start. Returns whether anything was deleted.
+    pub fn delete_backward(&mut self) -> bool {
+        if self.column > 0 {
+            let line = &mut self.lines[self.row];
+            let start = byte_offset(line, self.column - 1);
+            line.replace_range(start..byte_offset(line, self.column), "");
+            self.column -= 1;
+        } else if self.row > 0 {
+            let line = self.lines.remove(self.row);
+            self.row -= 1;
+            self.column = grapheme_count(&self.lines[self.row]);
+            self.lines[self.row].push_str(&line);
+        } else {
+            return false;
+        }
+        true
+    }
+
+    /// Delete the character under the cursor, joining the next line at the
+    /// end of a line. Returns whether anything was deleted.
+    pub fn delete_forward(&mut self) -> bool {
+        let line = &mut self.lines[self.row];
+        if self.column < grapheme_count(line) {
+            let start = byte_offset(line, self.column);
+            line.replace_range(start..byte_offset(line, self.column + 1), "");
+        } else if self.row + 1 < self.lines.len() {
+            let next = self.lines.remove(self.row + 1);
+            self.lines[self.row].push_str(&next);
+        } else {
+            return false;
+        }
+        true
+    }
+
+    /// Move one character left, to the end of the previous line at the
+    /// start of a line.
+    pub fn move_left(&mut self) {
+        if self.column > 0 {
+            self.column -= 1;
+        } else if self.row > 0 {
+            self.row -= 1;
+            self.column = grapheme_count(&self.lines[self.row]);
+        }
+    }
+
+    /// Move one character right, to the start of the next line at the end
+    /// of a line.
+    pub fn move_right(&mut self) {
+        if self.column < grapheme_count(&self.lines[self.row]) {
+            self.column += 1;
+        } else if self.row + 1 < self.lines.len() {
+            self.row += 1;
+            self.column = 0;
+        }
+    }
+
+    /// Move to the previous line, keeping the cursor's screen column where
+    /// the line is long enough.
+    pub fn move_up(&mut self) {
+        if self.row > 0 {
+            let width = self.cursor_width();
+            self.row -= 1;
+            self.column = self.column_at_width(self.row, width);
+        }
+    }
+
+    /// Move to the next line, keeping the cursor's screen column where the
+    /// line is long enough.
+    pub fn move_down(&mut self) {
+        if self.row + 1 < self.lines.len() {
+            let width = self.cursor_width();
+            self.row += 1;
+            self.column = self.column_at_width(self.row, width);
+        }
+    }
+
+    pub fn move_line_start(&mut self) {
+        self.column = 0;
+    }
+
+    pub fn move_line_end(&mut self) {
+        self.column = grapheme_count(&self.lines[self.row]);
+    }
+}
+
+fn split_lines(text: &str) -> impl Iterator<Item = &str> {
+    let mut rest = Some(text);
+    std::iter::from_fn(move || {
+        let text = rest?;
+        match text.find(['\r', '\n']) {
+            Some(end) => {
+                let skip = if text[end..].starts_with("\r\n") {
+                    2
+                } else {
+                    1
+                };
+                rest = Some(&text[end + skip..]);
+                Some(&text[..end])
+            }
+            None => {
+                rest = None;
+                Some(text)
+            }
+        }
+    })
+}
+
+fn grapheme_count(text: &str) -> usize {
+    text.graphemes(true).count()
+}
+
+// Byte index in `line` where the grapheme at `column` starts
+fn byte_offset(line: &str, column: usize) -> usize {
+    line.grapheme_indices(true)
+        .nth(column)
+        .map_or(line.len(), |(offset, _)| offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edits_by_grapheme_cluster() {
+        let mut buffer = TextBuffer::new();
+        buffer.insert_str("cafe");
+        buffer.insert_char('\u{301}');
+        assert_eq!(buffer.cursor(), (0, 4));
+        buffer.insert_char('👍');
+        buffer.move_left();
+        buffer.move_left();
+        buffer.insert_char('!');
+        assert_eq!(buffer.text(), "caf!e\u{301}👍");
+        assert_eq!(buffer.cursor_width(), 4);
+
+        buffer.move_line_end();
+        assert!(buffer.delete_backward());
+        assert!(buffer.delete_backward());
+        assert_eq!(buffer.text(), "caf!");
+        buffer.move_line_start();
+        assert!(buffer.delete_forward());
+        assert_eq!(buffer.text(), "af!");
+        assert!(!TextBuffer::new().delete_backward());
+    }
+
+    #[test]
+    fn measures_wide_characters() {
+        let mut buffer = TextBuffer::from_text("ab\n日本語");
+        assert_eq!(buffer.cursor(), (1, 3));
+        assert_eq!(buffer.cursor_width(), 6);
+        assert_eq!(buffer.column_at_width(1, 3), 1);
+        assert_eq!(buffer.column_at_width(1, 9), 3);
+
+        buffer.set_cursor(0, 2);
+        buffer.move_down();
+        assert_eq!(buffer.cursor(), (1, 1));
+        buffer.move_right();
+        buffer.move_up();
+        assert_eq!(buffer.cursor(), (0, 2));
+    }
+
+    #[test]
+    fn joins_and_splits_lines() {
+        let mut buffer = TextBuffer::from_text("one\r\ntwo");
+        assert_eq!(buffer.lines(), ["one", "two"]);
+        buffer.set_cursor(0, 1);
+        buffer.insert_str("X\rY\n\nZ");
+        assert_eq!(buffer.lines(), ["oX", "Y", "", "Zne", "two"]);
+        assert_eq!(buffer.cursor(), (3, 1));
+        let mut buffer = TextBuffer::from_text("one\ntwo");
+        buffer.set_cursor(1, 0);
+        buffer.delete_backward();
+        assert_eq!(buffer.text(), "onetwo");
+        assert_eq!(buffer.cursor(), (0, 3));
+        buffer.insert_newline();
+        buffer.move_left();
+        buffer.delete_forward();
+        assert_eq!(buffer.text(), "onetwo");
+        buffer.move_right();
+        buffer.move_right();
+        assert_eq!(buffer.cursor(), (0, 5));
+    }
+}
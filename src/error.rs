@@ -0,0 +1,41 @@
+use std::{fmt, io};
+
+/// Errors returned by rustubble's rendering and prompt functions.
+#[derive(Debug)]
+pub enum Error {
+    /// Reading from or writing to the terminal failed, e.g. because stdout is
+    /// a closed pipe or there is no TTY attached.
+    Io(io::Error),
+    /// [`Spinner::new`](crate::spinner::Spinner::new) was given a style name
+    /// that is not in the spinner table.
+    UnknownSpinnerStyle(String),
+    /// A key binding or other configuration could not be parsed.
+    Config(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(err) => write!(f, "terminal I/O failed: {}", err),
+            Error::UnknownSpinnerStyle(style) => write!(f, "unknown spinner style `{}`", style),
+            Error::Config(message) => write!(f, "invalid configuration: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(err) => Some(err),
+            Error::UnknownSpinnerStyle(_) | Error::Config(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
@@ -0,0 +1,66 @@
+use crossterm::event::Event;
+
+/// What a component did with an event passed to [`HandleEvent::handle_event`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EventOutcome {
+    /// The event changed the component or was otherwise used by it.
+    Consumed,
+    /// The component has no use for the event; route it elsewhere.
+    Ignored,
+    /// The user confirmed the component's value.
+    Submitted,
+    /// The user backed out of the component.
+    Cancelled,
+    /// The user pressed Ctrl+C.
+    Interrupted,
+}
+
+impl EventOutcome {
+    /// Whether the component used the event, i.e. anything but `Ignored`.
+    pub fn is_handled(&self) -> bool {
+        !matches!(self, EventOutcome::Ignored)
+    }
+}
+
+/// A component that can be driven one event at a time from the caller's own
+/// event loop.
+pub trait HandleEvent {
+    fn handle_event(&mut self, event: &Event) -> EventOutcome;
+}
+
+/// How a blocking `handle_*` prompt ended.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PromptResult<T> {
+    /// The user confirmed a value.
+    Submitted(T),
+    /// The user backed out with Esc or `q`.
+    Cancelled,
+    /// The user pressed Ctrl+C; CLIs conventionally exit with status 130.
+    Interrupted,
+}
+
+impl<T> PromptResult<T> {
+    /// The submitted value, discarding how the prompt was left otherwise.
+    pub fn ok(self) -> Option<T> {
+        match self {
+            PromptResult::Submitted(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn is_submitted(&self) -> bool {
+        matches!(self, PromptResult::Submitted(_))
+    }
+
+    pub fn is_interrupted(&self) -> bool {
+        matches!(self, PromptResult::Interrupted)
+    }
+
+    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> PromptResult<U> {
+        match self {
+            PromptResult::Submitted(value) => PromptResult::Submitted(f(value)),
+            PromptResult::Cancelled => PromptResult::Cancelled,
+            PromptResult::Interrupted => PromptResult::Interrupted,
+        }
+    }
+}
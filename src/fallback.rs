@@ -0,0 +1,191 @@
+use std::{
+    io::{self, BufRead, IsTerminal, Write},
+    time::Duration,
+};
+
+use crate::Result;
+
+/// How often components that show progress print a status line when the
+/// output is not a terminal.
+pub const STATUS_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Whether prompts can take over the terminal, which needs both stdin and
+/// stdout to be one.
+///
+/// When they aren't, as in CI, pipes or `docker run` without `-t`, the
+/// `handle_*` functions fall back to plain prompts: they print a question,
+/// read a line of stdin and never write escape codes.
+pub fn is_interactive() -> bool {
+    io::stdin().is_terminal() && io::stdout().is_terminal()
+}
+
+/// Write `prompt` and read one line, without its line ending. Returns `None`
+/// once the input has ended.
+pub fn read_line(
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+    prompt: &str,
+) -> Result<Option<String>> {
+    write!(output, "{}", prompt)?;
+    output.flush()?;
+    let mut line = String::new();
+    if input.read_line(&mut line)? == 0 {
+        // Whatever comes next starts on a line of its own
+        writeln!(output)?;
+        return Ok(None);
+    }
+    let len = line.trim_end_matches(['\r', '\n']).len();
+    line.truncate(len);
+    Ok(Some(line))
+}
+
+/// List `choices` under `title`, numbered from 1, and read the number of
+/// one, asking again until it is valid. Returns the index of the choice, or
+/// `None` once the input has ended or right away when there are no choices.
+pub fn choose<S: AsRef<str>>(
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+    title: &str,
+    choices: &[S],
+) -> Result<Option<usize>> {
+    if choices.is_empty() {
+        return Ok(None);
+    }
+    list_choices(output, title, choices)?;
+    let prompt = format!("Enter a number (1-{}): ", choices.len());
+    loop {
+        let Some(line) = read_line(input, output, &prompt)? else {
+            return Ok(None);
+        };
+        match parse_choice(&line, choices.len()) {
+            Some(index) => return Ok(Some(index)),
+            None => writeln!(output, "Not one of the choices: {}", line.trim())?,
+        }
+    }
+}
+
+/// Like [`choose`], but reads any number of choices separated by commas or
+/// spaces. An empty line picks none, as does an empty list of choices
+/// without asking.
+pub fn choose_many<S: AsRef<str>>(
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+    title: &str,
+    choices: &[S],
+) -> Result<Option<Vec<usize>>> {
+    if choices.is_empty() {
+        return Ok(Some(Vec::new()));
+    }
+    list_choices(output, title, choices)?;
+    let prompt = format!("Enter numbers separated by commas (1-{}): ", choices.len());
+    loop {
+        let Some(line) = read_line(input, output, &prompt)? else {
+            return Ok(None);
+        };
+        let picked: Option<Vec<usize>> = line
+            .split([',', ' '])
+            .filter(|part| !part.trim().is_empty())
+            .map(|part| parse_choice(part, choices.len()))
+            .collect();
+        match picked {
+            Some(mut picked) => {
+                picked.sort_unstable();
+                picked.dedup();
+                return Ok(Some(picked));
+            }
+            None => writeln!(output, "Not all of those are choices: {}", line.trim())?,
+        }
+    }
+}
+
+/// Ask a yes or no question, taking `default` for an empty answer. Returns
+/// `None` once the input has ended.
+pub fn confirm(
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+    question: &str,
+    default: bool,
+) -> Result<Option<bool>> {
+    let hint = if default { "Y/n" } else { "y/N" };
+    let prompt = format!("{} [{}] ", question, hint);
+    loop {
+        let Some(line) = read_line(input, output, &prompt)? else {
+            return Ok(None);
+        };
+        match line.trim().to_lowercase().as_str() {
+            "" => return Ok(Some(default)),
+            "y" | "yes" => return Ok(Some(true)),
+            "n" | "no" => return Ok(Some(false)),
+            _ => writeln!(output, "Please answer y or n")?,
+        }
+    }
+}
+
+fn list_choices<S: AsRef<str>>(output: &mut impl Write, title: &str, choices: &[S]) -> Result<()> {
+    if !title.is_empty() {
+        writeln!(output, "{}", title)?;
+    }
+    for (i, choice) in choices.iter().enumerate() {
+        writeln!(output, "{:>3}) {}", i + 1, choice.as_ref())?;
+    }
+    Ok(())
+}
+
+// The index of a 1-based choice number
+fn parse_choice(text: &str, count: usize) -> Option<usize> {
+    match text.trim().parse::<usize>() {
+        Ok(n) if (1..=count).contains(&n) => Some(n - 1),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_lines_and_choices() {
+        let mut output = Vec::new();
+        let mut input = "Ada\r\n".as_bytes();
+        let line = read_line(&mut input, &mut output, "Name: ").unwrap();
+        assert_eq!(line.as_deref(), Some("Ada"));
+        assert_eq!(read_line(&mut input, &mut output, "Name: ").unwrap(), None);
+
+        let mut output = Vec::new();
+        let mut input = "4\nblue\n2\n".as_bytes();
+        let choice = choose(&mut input, &mut output, "Color", &["red", "green", "blue"]);
+        assert_eq!(choice.unwrap(), Some(1));
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.starts_with("Color\n  1) red\n  2) green\n  3) blue\n"));
+        assert_eq!(output.matches("Not one of the choices").count(), 2);
+
+        let mut output = Vec::new();
+        let choice = choose::<&str>(&mut "1\n".as_bytes(), &mut output, "Color", &[]);
+        assert_eq!(choice.unwrap(), None);
+        assert!(output.is_empty());
+    }
+
+    #[test]
+    fn reads_several_choices_and_confirmations() {
+        let mut output = Vec::new();
+        let mut input = "3, 1 3\n".as_bytes();
+        let picked = choose_many(&mut input, &mut output, "", &["a", "b", "c"]).unwrap();
+        assert_eq!(picked, Some(vec![0, 2]));
+        let picked = choose_many::<&str>(&mut "1\n".as_bytes(), &mut output, "", &[]).unwrap();
+        assert_eq!(picked, Some(vec![]));
+
+        let mut input = "maybe\n\nno\n".as_bytes();
+        assert_eq!(
+            confirm(&mut input, &mut output, "Sure?", true).unwrap(),
+            Some(true)
+        );
+        assert_eq!(
+            confirm(&mut input, &mut output, "Sure?", true).unwrap(),
+            Some(false)
+        );
+        assert_eq!(
+            confirm(&mut input, &mut output, "Sure?", true).unwrap(),
+            None
+        );
+    }
+}
@@ -0,0 +1,218 @@
+use std::any::Any;
+
+use crossterm::event::Event;
+use ratatui::{layout::Rect, Frame};
+
+use crate::{
+    event::{EventOutcome, HandleEvent},
+    keymap::{Action, Component, KeyMap},
+};
+
+/// A component that can share the screen with others in a [`FocusRing`].
+///
+/// Blurred components render dimmed with the theme's `muted` color and leave
+/// the cursor to the focused one.
+pub trait Focusable: HandleEvent + Any {
+    fn is_focused(&self) -> bool;
+
+    fn set_focused(&mut self, focused: bool);
+
+    /// Draw the component into `area`, styled by whether it has focus.
+    fn view(&mut self, frame: &mut Frame, area: Rect);
+}
+
+/// Several components on one screen, of which only the focused one receives
+/// events. Tab and Shift+Tab move focus forward and back, wrapping around.
+///
+/// ```no_run
+/// # use rustubble::{focus::FocusRing, input::TextInput, list::ItemList};
+/// # let list = ItemList::new("Theme".to_string(), Vec::new());
+/// # let input = TextInput::new(None, 2, "", "Name", None, ">");
+/// let mut ring = FocusRing::new().with(list).with(input);
+/// // In the event loop:
+/// // ring.handle_event(&event);
+/// // ring.view(frame, &[list_area, input_area]);
+/// let name = ring.get::<TextInput>(1).unwrap().value();
+/// ```
+pub struct FocusRing {
+    components: Vec<Box<dyn Focusable>>,
+    focused: usize,
+    keymap: KeyMap,
+}
+
+impl Default for FocusRing {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FocusRing {
+    pub fn new() -> Self {
+        Self {
+            components: Vec::new(),
+            focused: 0,
+            keymap: KeyMap::default_for(Component::Focus),
+        }
+    }
+
+    /// Add a component after the existing ones.
+    pub fn with(mut self, component: impl Focusable) -> Self {
+        self.push(component);
+        self
+    }
+
+    /// Add a component after the existing ones and return its index. The
+    /// first component starts out focused and the others blurred.
+    pub fn push(&mut self, component: impl Focusable) -> usize {
+        let mut component = Box::new(component);
+        component.set_focused(self.components.is_empty());
+        self.components.push(component);
+        self.components.len() - 1
+    }
+
+    /// Use other keys than Tab and Shift+Tab to move focus.
+    pub fn with_keymap(mut self, keymap: KeyMap) -> Self {
+        self.set_keymap(keymap);
+        self
+    }
+
+    pub fn set_keymap(&mut self, keymap: KeyMap) {
+        self.keymap = keymap;
+    }
+
+    pub fn len(&self) -> usize {
+        self.components.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty()
+    }
+
+    /// Index of the focused component.
+    pub fn focused(&self) -> usize {
+        self.focused
+    }
+
+    /// Focus the component at `index`, if there is one.
+    pub fn focus(&mut self, index: usize) {
+        if index >= self.components.len() {
+            return;
+        }
+        self.focused = index;
+        for (i, component) in self.components.iter_mut().enumerate() {
+            component.set_focused(i == index);
+        }
+    }
+
+    pub fn focus_next(&mut self) {
+        if !self.is_empty() {
+            self.focus((self.focused + 1) % self.len());
+        }
+    }
+
+    pub fn focus_previous(&mut self) {
+        if !self.is_empty() {
+            self.focus((self.focused + self.len() - 1) % self.len());
+        }
+    }
+
+    /// The component at `index`, if it is a `T`.
+    pub fn get<T: Focusable>(&self, index: usize) -> Option<&T> {
+        let component: &dyn Any = &**self.components.get(index)?;
+        component.downcast_ref()
+    }
+
+    pub fn get_mut<T: Focusable>(&mut self, index: usize) -> Option<&mut T> {
+        let component: &mut dyn Any = &mut **self.components.get_mut(index)?;
+        component.downcast_mut()
+    }
+
+    /// Draw each component into the area at the same index. Components
+    /// without an area are not drawn.
+    pub fn view(&mut self, frame: &mut Frame, areas: &[Rect]) {
+        for (component, area) in self.components.iter_mut().zip(areas) {
+            component.view(frame, *area);
+        }
+    }
+}
+
+impl HandleEvent for FocusRing {
+    fn handle_event(&mut self, event: &Event) -> EventOutcome {
+        // Every component lays itself out again, not just the focused one
+        if let Event::Resize(..) = event {
+            for component in &mut self.components {
+                component.handle_event(event);
+            }
+            return EventOutcome::Consumed;
+        }
+        if let Event::Key(key) = event {
+            match self.keymap.action(key) {
+                Some(Action::FocusNext) => {
+                    self.focus_next();
+                    return EventOutcome::Consumed;
+                }
+                Some(Action::FocusPrevious) => {
+                    self.focus_previous();
+                    return EventOutcome::Consumed;
+                }
+                _ => {}
+            }
+        }
+        match self.components.get_mut(self.focused) {
+            Some(component) => component.handle_event(event),
+            None => EventOutcome::Ignored,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{input::TextInput, list::ItemList, theme::Theme};
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use ratatui::{backend::TestBackend, Terminal};
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> Event {
+        Event::Key(KeyEvent::new(code, modifiers))
+    }
+
+    fn input() -> TextInput {
+        TextInput::new(None, 0, "", "Name", None, ">")
+    }
+
+    #[test]
+    fn cycles_focus_and_routes_keys() {
+        let list = ItemList::new("Theme".to_string(), Vec::new());
+        let mut ring = FocusRing::new().with(input()).with(list).with(input());
+        assert!(ring.get::<TextInput>(0).unwrap().is_focused());
+        assert!(!ring.get::<ItemList>(1).unwrap().is_focused());
+        assert!(ring.get::<ItemList>(0).is_none());
+
+        ring.handle_event(&key(KeyCode::Char('a'), KeyModifiers::NONE));
+        ring.handle_event(&key(KeyCode::BackTab, KeyModifiers::SHIFT));
+        assert_eq!(ring.focused(), 2);
+        ring.handle_event(&key(KeyCode::Char('b'), KeyModifiers::NONE));
+        ring.handle_event(&key(KeyCode::Tab, KeyModifiers::NONE));
+        assert_eq!(ring.focused(), 0);
+
+        assert_eq!(ring.get::<TextInput>(0).unwrap().value(), "a");
+        assert_eq!(ring.get::<TextInput>(2).unwrap().value(), "b");
+        assert!(!ring.get::<TextInput>(2).unwrap().is_focused());
+    }
+
+    #[test]
+    fn renders_blurred_components_dimmed() {
+        let mut ring = FocusRing::new().with(input()).with(input());
+        let mut terminal = Terminal::new(TestBackend::new(10, 6)).unwrap();
+        terminal
+            .draw(|frame| {
+                let areas = [Rect::new(0, 0, 10, 3), Rect::new(0, 3, 10, 3)];
+                ring.view(frame, &areas);
+            })
+            .unwrap();
+        let theme = Theme::default();
+        let buffer = terminal.backend().buffer();
+        assert_eq!(buffer.get(0, 2).fg, theme.text.resolve());
+        assert_eq!(buffer.get(0, 5).fg, theme.muted.resolve());
+    }
+}
@@ -0,0 +1,1156 @@
+use std::{
+    collections::BTreeMap,
+    io::{self, stdout, BufRead, Write},
+};
+
+use crossterm::event::{read, Event, KeyCode, KeyModifiers};
+use ratatui::{
+    backend::CrosstermBackend,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame, Terminal, TerminalOptions, Viewport,
+};
+
+use crate::{
+    event::{EventOutcome, HandleEvent, PromptResult},
+    fallback,
+    focus::Focusable,
+    help::HelpComponent,
+    inline::InlinePrompt,
+    input::TextInput,
+    keymap::{Action, Component, KeyMap},
+    program::{Cmd, Model, Msg},
+    text_area::TextArea,
+    theme::Theme,
+    tui::{self, SessionOptions, TerminalSession},
+    Result,
+};
+
+/// The answer to one field of a [`Form`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Value {
+    /// What was typed into an input or text field.
+    Text(String),
+    /// The option picked in a select field.
+    Choice(String),
+    /// The options picked in a multi-select field, in display order.
+    Choices(Vec<String>),
+    /// The answer to a confirm field.
+    Bool(bool),
+}
+
+impl Value {
+    /// Whether the field was left blank: empty text or no options picked.
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Value::Text(text) | Value::Choice(text) => text.trim().is_empty(),
+            Value::Choices(choices) => choices.is_empty(),
+            Value::Bool(_) => false,
+        }
+    }
+}
+
+/// The answers of a submitted [`Form`], by field key.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FormValues(BTreeMap<String, Value>);
+
+impl FormValues {
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.0.get(key)
+    }
+
+    /// The answer to an input, text or select field.
+    pub fn text(&self, key: &str) -> Option<&str> {
+        match self.get(key)? {
+            Value::Text(text) | Value::Choice(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    /// The answer to a multi-select field.
+    pub fn choices(&self, key: &str) -> Option<&[String]> {
+        match self.get(key)? {
+            Value::Choices(choices) => Some(choices),
+            _ => None,
+        }
+    }
+
+    /// The answer to a confirm field.
+    pub fn bool(&self, key: &str) -> Option<bool> {
+        match self.get(key)? {
+            Value::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Value)> {
+        self.0.iter().map(|(key, value)| (key.as_str(), value))
+    }
+}
+
+type Validator = Box<dyn Fn(&Value) -> std::result::Result<(), String>>;
+
+/// One question of a [`Form`].
+pub struct Field {
+    key: String,
+    kind: FieldKind,
+    validators: Vec<Validator>,
+    error: Option<String>,
+}
+
+enum FieldKind {
+    Input(Box<TextInput>),
+    Text(TextArea),
+    Select(Select),
+    Confirm(Confirm),
+}
+
+impl Field {
+    /// A single line of text.
+    pub fn input(key: &str, title: &str) -> Self {
+        Self::from_input(key, TextInput::new(None, 0, "", title, None, ">"))
+    }
+
+    /// A field backed by an input configured by the caller.
+    pub fn from_input(key: &str, input: TextInput) -> Self {
+        Self::new(key, FieldKind::Input(Box::new(input)))
+    }
+
+    /// Several lines of text, `visible_lines` at a time.
+    pub fn text(key: &str, title: &str, visible_lines: usize) -> Self {
+        Self::from_text_area(key, TextArea::new(title, None, visible_lines))
+    }
+
+    pub fn from_text_area(key: &str, text_area: TextArea) -> Self {
+        Self::new(key, FieldKind::Text(text_area))
+    }
+
+    /// Pick one of `options`.
+    pub fn select(key: &str, title: &str, options: &[&str]) -> Self {
+        Self::new(key, FieldKind::Select(Select::new(title, options, false)))
+    }
+
+    /// Pick any number of `options`, toggled with space.
+    pub fn multi_select(key: &str, title: &str, options: &[&str]) -> Self {
+        Self::new(key, FieldKind::Select(Select::new(title, options, true)))
+    }
+
+    /// A yes or no question, answered with the arrow keys or `y` and `n`.
+    pub fn confirm(key: &str, title: &str) -> Self {
+        Self::new(key, FieldKind::Confirm(Confirm::new(title)))
+    }
+
+    fn new(key: &str, kind: FieldKind) -> Self {
+        Self {
+            key: key.to_string(),
+            kind,
+            validators: Vec::new(),
+            error: None,
+        }
+    }
+
+    /// Check the answer before moving on. The message is shown below the
+    /// field until the answer passes.
+    pub fn with_validator<F>(mut self, validator: F) -> Self
+    where
+        F: Fn(&Value) -> std::result::Result<(), String> + 'static,
+    {
+        self.validators.push(Box::new(validator));
+        self
+    }
+
+    /// Refuse to move on while the field is blank.
+    pub fn required(self) -> Self {
+        self.with_validator(|value| match value.is_empty() {
+            true => Err("This field is required".to_string()),
+            false => Ok(()),
+        })
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub fn value(&self) -> Value {
+        match &self.kind {
+            FieldKind::Input(input) => Value::Text(input.value().to_string()),
+            FieldKind::Text(text_area) => Value::Text(text_area.value()),
+            FieldKind::Select(select) => select.value(),
+            FieldKind::Confirm(confirm) => Value::Bool(confirm.value),
+        }
+    }
+
+    // The question as listed on the summary page
+    fn title(&self) -> &str {
+        match &self.kind {
+            FieldKind::Input(input) => input.label(),
+            FieldKind::Text(text_area) => text_area.label(),
+            FieldKind::Select(select) => &select.title,
+            FieldKind::Confirm(confirm) => &confirm.title,
+        }
+    }
+
+    // The answer on one line for the summary page, masked the way the input
+    // shows it
+    fn summary(&self) -> String {
+        match (&self.kind, self.value()) {
+            (FieldKind::Input(input), Value::Text(text)) => input.summary(&text),
+            (_, Value::Text(text)) => match text.split_once('\n') {
+                Some((first, _)) => format!("{} …", first),
+                None => text,
+            },
+            (_, Value::Choice(choice)) => choice,
+            (_, Value::Choices(choices)) => choices.join(", "),
+            (_, Value::Bool(value)) => match value {
+                true => "Yes".to_string(),
+                false => "No".to_string(),
+            },
+        }
+    }
+
+    /// The message of the last failed validation, if it still applies.
+    pub fn error(&self) -> Option<&str> {
+        match &self.kind {
+            FieldKind::Input(input) if self.error.is_none() => input.error(),
+            _ => self.error.as_deref(),
+        }
+    }
+
+    // Runs the validators and remembers the first failure. An input's own
+    // validators show their message in place of its helper text.
+    fn validate(&mut self) -> bool {
+        let value = self.value();
+        self.error = self
+            .validators
+            .iter()
+            .find_map(|validator| validator(&value).err());
+        let component_valid = match &mut self.kind {
+            FieldKind::Input(input) => input.validate(),
+            _ => true,
+        };
+        self.error.is_none() && component_valid
+    }
+
+    // Asks for the answer on plain lines, returning false once input has ended
+    fn prompt_plain(&mut self, input: &mut impl BufRead, output: &mut impl Write) -> Result<bool> {
+        match &mut self.kind {
+            FieldKind::Input(text_input) => text_input.read_plain(input, output),
+            FieldKind::Text(text_area) => Ok(text_area.prompt_plain(input, output)?.is_submitted()),
+            FieldKind::Select(select) if select.multiple => {
+                let Some(picked) =
+                    fallback::choose_many(input, output, &select.title, &select.options)?
+                else {
+                    return Ok(false);
+                };
+                for (i, option) in select.picked.iter_mut().enumerate() {
+                    *option = picked.contains(&i);
+                }
+                Ok(true)
+            }
+            FieldKind::Select(select) => {
+                let Some(cursor) = fallback::choose(input, output, &select.title, &select.options)?
+                else {
+                    return Ok(false);
+                };
+                select.cursor = cursor;
+                Ok(true)
+            }
+            FieldKind::Confirm(confirm) => {
+                let Some(value) = fallback::confirm(input, output, &confirm.title, confirm.value)?
+                else {
+                    return Ok(false);
+                };
+                confirm.value = value;
+                Ok(true)
+            }
+        }
+    }
+
+    // Whether the field shows a suggestion that Tab would accept
+    fn offers_suggestion(&self) -> bool {
+        matches!(&self.kind, FieldKind::Input(input) if input.suggestion().is_some())
+    }
+
+    fn component(&mut self) -> &mut dyn Focusable {
+        match &mut self.kind {
+            FieldKind::Input(input) => input.as_mut(),
+            FieldKind::Text(text_area) => text_area,
+            FieldKind::Select(select) => select,
+            FieldKind::Confirm(confirm) => confirm,
+        }
+    }
+
+    fn set_theme(&mut self, theme: Theme) {
+        match &mut self.kind {
+            FieldKind::Input(input) => input.set_theme(theme),
+            FieldKind::Text(text_area) => text_area.set_theme(theme),
+            FieldKind::Select(select) => select.theme = theme,
+            FieldKind::Confirm(confirm) => confirm.theme = theme,
+        }
+    }
+
+    /// Rows the field needs, including the line for its error.
+    fn height(&self) -> u16 {
+        let height = match &self.kind {
+            FieldKind::Input(input) => input.height(),
+            FieldKind::Text(text_area) => text_area.height(),
+            FieldKind::Select(select) => 1 + select.options.len() as u16,
+            FieldKind::Confirm(_) => 2,
+        };
+        height + 1
+    }
+
+    fn view(&mut self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let height = self.height();
+        let field_area = Rect {
+            height: area.height.min(height - 1),
+            ..area
+        };
+        self.component().view(frame, field_area);
+
+        if let Some(error) = &self.error {
+            let error_area = Rect {
+                y: field_area.bottom(),
+                height: 1,
+                ..area
+            }
+            .intersection(area);
+            let line = Line::styled(
+                format!("✗ {}", error),
+                Style::default().fg(theme.error.resolve()),
+            );
+            frame.render_widget(Paragraph::new(line), error_area);
+        }
+    }
+}
+
+// A list of options, like a Menu without its own title bar and help
+struct Select {
+    title: String,
+    options: Vec<String>,
+    picked: Vec<bool>,
+    cursor: usize,
+    multiple: bool,
+    keymap: KeyMap,
+    theme: Theme,
+    focused: bool,
+}
+
+impl Select {
+    fn new(title: &str, options: &[&str], multiple: bool) -> Self {
+        // Cancel is left to the form, which also handles Esc
+        let mut keymap = KeyMap::default_for(Component::Menu);
+        if multiple {
+            keymap.bind(KeyCode::Char(' '), Action::ToggleSelection);
+            keymap.bind(KeyCode::Char('x'), Action::ToggleSelection);
+        }
+        Self {
+            title: title.to_string(),
+            options: options.iter().map(|option| option.to_string()).collect(),
+            picked: vec![false; options.len()],
+            cursor: 0,
+            multiple,
+            keymap,
+            theme: Theme::default(),
+            focused: true,
+        }
+    }
+
+    fn value(&self) -> Value {
+        if !self.multiple {
+            return Value::Choice(self.options.get(self.cursor).cloned().unwrap_or_default());
+        }
+        let picked = self.options.iter().zip(&self.picked);
+        Value::Choices(
+            picked
+                .filter(|(_, picked)| **picked)
+                .map(|(option, _)| option.clone())
+                .collect(),
+        )
+    }
+}
+
+impl HandleEvent for Select {
+    fn handle_event(&mut self, event: &Event) -> EventOutcome {
+        let Event::Key(key) = event else {
+            return EventOutcome::Ignored;
+        };
+        let last = self.options.len().saturating_sub(1);
+        match self.keymap.action(key) {
+            Some(Action::Interrupt) => return EventOutcome::Interrupted,
+            Some(Action::Submit) => return EventOutcome::Submitted,
+            Some(Action::Up) => self.cursor = self.cursor.saturating_sub(1),
+            Some(Action::Down) => self.cursor = (self.cursor + 1).min(last),
+            Some(Action::Top) => self.cursor = 0,
+            Some(Action::Bottom) => self.cursor = last,
+            Some(Action::ToggleSelection) if self.multiple => {
+                if let Some(picked) = self.picked.get_mut(self.cursor) {
+                    *picked = !*picked;
+                }
+            }
+            _ => return EventOutcome::Ignored,
+        }
+        EventOutcome::Consumed
+    }
+}
+
+impl Focusable for Select {
+    fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        let highlight = match self.focused {
+            true => self.theme.primary,
+            false => self.theme.muted,
+        };
+        let mut lines = vec![Line::styled(
+            self.title.as_str(),
+            Style::default().add_modifier(Modifier::BOLD),
+        )];
+        for (i, option) in self.options.iter().enumerate() {
+            let pointer = if i == self.cursor { "> " } else { "  " };
+            let check = match (self.multiple, self.picked[i]) {
+                (false, _) => "",
+                (true, true) => "[x] ",
+                (true, false) => "[ ] ",
+            };
+            let style = match i == self.cursor {
+                true => Style::default().fg(highlight.resolve()),
+                false => Style::default().fg(self.theme.text.resolve()),
+            };
+            lines.push(Line::styled(
+                format!("{}{}{}", pointer, check, option),
+                style,
+            ));
+        }
+        frame.render_widget(Paragraph::new(lines), area);
+    }
+}
+
+struct Confirm {
+    title: String,
+    value: bool,
+    keymap: KeyMap,
+    theme: Theme,
+    focused: bool,
+}
+
+impl Confirm {
+    fn new(title: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            value: true,
+            keymap: KeyMap::default_for(Component::Form),
+            theme: Theme::default(),
+            focused: true,
+        }
+    }
+}
+
+impl HandleEvent for Confirm {
+    fn handle_event(&mut self, event: &Event) -> EventOutcome {
+        let Event::Key(key) = event else {
+            return EventOutcome::Ignored;
+        };
+        match self.keymap.action(key) {
+            Some(Action::Interrupt) => return EventOutcome::Interrupted,
+            Some(Action::Submit) => return EventOutcome::Submitted,
+            Some(Action::Left) => self.value = true,
+            Some(Action::Right) => self.value = false,
+            _ => match key.code {
+                KeyCode::Char('y' | 'Y') if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.value = true
+                }
+                KeyCode::Char('n' | 'N') if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.value = false
+                }
+                _ => return EventOutcome::Ignored,
+            },
+        }
+        EventOutcome::Consumed
+    }
+}
+
+impl Focusable for Confirm {
+    fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        let chosen = match self.focused {
+            true => self.theme.selection,
+            false => self.theme.track,
+        };
+        let button = |label: &'static str, active: bool| {
+            let style = match active {
+                true => Style::default().bg(chosen.resolve()),
+                false => Style::default().fg(self.theme.muted.resolve()),
+            };
+            Span::styled(label, style)
+        };
+        let lines = vec![
+            Line::styled(
+                self.title.as_str(),
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Line::from(vec![
+                button(" Yes ", self.value),
+                Span::raw("  "),
+                button(" No ", !self.value),
+            ]),
+        ];
+        frame.render_widget(Paragraph::new(lines), area);
+    }
+}
+
+/// A page of a [`Form`], shown on its own.
+pub struct Group {
+    title: Option<String>,
+    description: Option<String>,
+    fields: Vec<Field>,
+}
+
+impl Group {
+    pub fn new(fields: Vec<Field>) -> Self {
+        Self {
+            title: None,
+            description: None,
+            fields,
+        }
+    }
+
+    pub fn with_title(mut self, title: &str) -> Self {
+        self.title = Some(title.to_string());
+        self
+    }
+
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+        self
+    }
+
+    fn header_height(&self) -> u16 {
+        let rows = self.title.is_some() as u16 + self.description.is_some() as u16;
+        if rows > 0 {
+            rows + 1
+        } else {
+            0
+        }
+    }
+
+    fn height(&self) -> u16 {
+        self.header_height() + self.fields.iter().map(Field::height).sum::<u16>()
+    }
+}
+
+/// Several fields asked one after another and returned together, split
+/// into groups that are shown one page at a time.
+///
+/// Enter and Tab move to the next field once the current one passes its
+/// validators, and Shift+Tab goes back to change an earlier answer. After
+/// the last field a summary page lists every answer; Enter submits them and
+/// Shift+Tab goes back to the fields.
+pub struct Form {
+    groups: Vec<Group>,
+    group: usize,
+    field: usize,
+    keymap: KeyMap,
+    theme: Theme,
+    summary: bool,
+    reviewing: bool,
+}
+
+impl Form {
+    pub fn new(groups: Vec<Group>) -> Self {
+        let mut form = Self {
+            groups,
+            group: 0,
+            field: 0,
+            keymap: KeyMap::default_for(Component::Form),
+            theme: Theme::default(),
+            summary: true,
+            reviewing: false,
+        };
+        form.focus(0, 0);
+        form
+    }
+
+    pub fn with_keymap(mut self, keymap: KeyMap) -> Self {
+        self.set_keymap(keymap);
+        self
+    }
+
+    /// Use other keys to move between fields and answer confirm fields.
+    pub fn set_keymap(&mut self, keymap: KeyMap) {
+        for field in self.groups.iter_mut().flat_map(|group| &mut group.fields) {
+            if let FieldKind::Confirm(confirm) = &mut field.kind {
+                confirm.keymap = keymap.clone();
+            }
+        }
+        self.keymap = keymap;
+    }
+
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.set_theme(theme);
+        self
+    }
+
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+        for field in self.groups.iter_mut().flat_map(|group| &mut group.fields) {
+            field.set_theme(theme);
+        }
+    }
+
+    /// Show the summary page before submitting, or submit from the last
+    /// field without it.
+    pub fn with_summary(mut self, summary: bool) -> Self {
+        self.set_summary(summary);
+        self
+    }
+
+    pub fn set_summary(&mut self, summary: bool) {
+        self.summary = summary;
+    }
+
+    /// Whether the summary page is shown, waiting for the answers to be
+    /// submitted.
+    pub fn is_reviewing(&self) -> bool {
+        self.reviewing
+    }
+
+    /// Indexes of the current group and of the current field within it.
+    pub fn position(&self) -> (usize, usize) {
+        (self.group, self.field)
+    }
+
+    /// The answers so far, by field key.
+    pub fn values(&self) -> FormValues {
+        let fields = self.groups.iter().flat_map(|group| &group.fields);
+        FormValues(
+            fields
+                .map(|field| (field.key.clone(), field.value()))
+                .collect(),
+        )
+    }
+
+    /// Rows the tallest group or the summary page needs, including the
+    /// help bar.
+    pub fn height(&self) -> u16 {
+        let summary = match self.summary {
+            true => {
+                2 + self
+                    .groups
+                    .iter()
+                    .map(|group| group.fields.len())
+                    .sum::<usize>() as u16
+            }
+            false => 0,
+        };
+        let groups = self.groups.iter().map(Group::height).max().unwrap_or(0);
+        groups.max(summary) + 1
+    }
+
+    fn focus(&mut self, group: usize, field: usize) {
+        self.group = group;
+        self.field = field;
+        for (g, fields) in self.groups.iter_mut().enumerate() {
+            for (f, current) in fields.fields.iter_mut().enumerate() {
+                current.component().set_focused(g == group && f == field);
+            }
+        }
+    }
+
+    /// Move to the next field, or report that the form is complete.
+    fn next(&mut self) -> EventOutcome {
+        let Some(current) = self.current() else {
+            return EventOutcome::Submitted;
+        };
+        if !current.validate() {
+            return EventOutcome::Consumed;
+        }
+        if self.field + 1 < self.groups[self.group].fields.len() {
+            self.focus(self.group, self.field + 1);
+            return EventOutcome::Consumed;
+        }
+        match (self.group + 1..self.groups.len()).find(|&g| !self.groups[g].fields.is_empty()) {
+            Some(group) => self.focus(group, 0),
+            None => return self.submit(),
+        }
+        EventOutcome::Consumed
+    }
+
+    /// Move back to the previous field, across groups, or from the summary
+    /// page to the last field.
+    fn back(&mut self) {
+        if self.reviewing {
+            self.reviewing = false;
+        } else if self.field > 0 {
+            self.focus(self.group, self.field - 1);
+        } else if let Some(group) = (0..self.group)
+            .rev()
+            .find(|&g| !self.groups[g].fields.is_empty())
+        {
+            self.focus(group, self.groups[group].fields.len() - 1);
+        }
+    }
+
+    // Validates every field and shows the first one that fails
+    fn submit(&mut self) -> EventOutcome {
+        let mut invalid = None;
+        for (g, group) in self.groups.iter_mut().enumerate() {
+            for (f, field) in group.fields.iter_mut().enumerate() {
+                if !field.validate() && invalid.is_none() {
+                    invalid = Some((g, f));
+                }
+            }
+        }
+        match invalid {
+            Some((group, field)) => {
+                self.focus(group, field);
+                EventOutcome::Consumed
+            }
+            None if self.summary => {
+                self.reviewing = true;
+                EventOutcome::Consumed
+            }
+            None => EventOutcome::Submitted,
+        }
+    }
+
+    // Keys on the summary page, where the answers can only be read
+    fn handle_review_event(&mut self, event: &Event) -> EventOutcome {
+        let Event::Key(key) = event else {
+            return EventOutcome::Ignored;
+        };
+        match self.keymap.action(key) {
+            Some(Action::Submit) => EventOutcome::Submitted,
+            Some(Action::FocusPrevious) => {
+                self.back();
+                EventOutcome::Consumed
+            }
+            Some(Action::Cancel) => EventOutcome::Cancelled,
+            Some(Action::Interrupt) => EventOutcome::Interrupted,
+            _ => EventOutcome::Ignored,
+        }
+    }
+
+    /// Ask every field on plain lines of `input`, for when there is no
+    /// terminal. A field is asked again, with its error, until it passes
+    /// its validators; the form is cancelled if the input ends first. With
+    /// the summary, the answers are listed and every field is asked again
+    /// unless they are confirmed.
+    fn prompt_plain(
+        &mut self,
+        input: &mut impl BufRead,
+        output: &mut impl Write,
+    ) -> Result<PromptResult<FormValues>> {
+        loop {
+            for group in &mut self.groups {
+                for header in group.title.iter().chain(&group.description) {
+                    writeln!(output, "{}", header)?;
+                }
+                for field in &mut group.fields {
+                    loop {
+                        if !field.prompt_plain(input, output)? {
+                            return Ok(PromptResult::Cancelled);
+                        }
+                        match field.validate() {
+                            true => break,
+                            false => writeln!(output, "✗ {}", field.error().unwrap_or_default())?,
+                        }
+                    }
+                }
+            }
+            if !self.summary {
+                break;
+            }
+            writeln!(output, "Summary")?;
+            for field in self.groups.iter().flat_map(|group| &group.fields) {
+                writeln!(output, "  {}: {}", field.title(), field.summary())?;
+            }
+            match fallback::confirm(input, output, "Submit?", true)? {
+                Some(true) => break,
+                Some(false) => {}
+                None => return Ok(PromptResult::Cancelled),
+            }
+        }
+        Ok(PromptResult::Submitted(self.values()))
+    }
+
+    fn current(&mut self) -> Option<&mut Field> {
+        self.groups.get_mut(self.group)?.fields.get_mut(self.field)
+    }
+
+    fn help_component(&self) -> HelpComponent {
+        let last = match self.reviewing {
+            true => Action::Submit,
+            false => Action::FocusNext,
+        };
+        HelpComponent::from_keymap(
+            &self.keymap,
+            &[
+                Action::Interrupt,
+                Action::Cancel,
+                Action::FocusPrevious,
+                last,
+            ],
+        )
+        .with_theme(self.theme)
+    }
+
+    // Every answer under the heading of the summary page
+    fn view_summary(&self, frame: &mut Frame, area: Rect) {
+        let theme = self.theme;
+        let mut lines = vec![
+            Line::styled(
+                "Summary",
+                Style::default()
+                    .fg(theme.primary.resolve())
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Line::default(),
+        ];
+        for field in self.groups.iter().flat_map(|group| &group.fields) {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("{}: ", field.title()),
+                    Style::default().fg(theme.text.resolve()),
+                ),
+                Span::styled(
+                    field.summary(),
+                    Style::default().fg(theme.secondary.resolve()),
+                ),
+            ]));
+        }
+        let summary_area = Rect {
+            height: area.height.saturating_sub(1),
+            ..area
+        };
+        frame.render_widget(Paragraph::new(lines), summary_area);
+    }
+
+    /// Draw the current group into `area`, for use inside a [`Program`](crate::program::Program).
+    pub fn view(&mut self, frame: &mut Frame, area: Rect) {
+        let help = self.help_component();
+        let help_area = Rect::new(area.x, area.bottom().saturating_sub(1), area.width, 1);
+        if self.reviewing {
+            self.view_summary(frame, area);
+            frame.render_widget(help, help_area.intersection(area));
+            return;
+        }
+        let pages = self.groups.len();
+        let theme = self.theme;
+        let Some(group) = self.groups.get_mut(self.group) else {
+            return;
+        };
+
+        let mut y = area.y;
+        let mut header = Vec::new();
+        if let Some(title) = &group.title {
+            let mut spans = vec![Span::styled(
+                title.as_str(),
+                Style::default()
+                    .fg(theme.primary.resolve())
+                    .add_modifier(Modifier::BOLD),
+            )];
+            if pages > 1 {
+                spans.push(Span::styled(
+                    format!(" ({}/{})", self.group + 1, pages),
+                    Style::default().fg(theme.muted.resolve()),
+                ));
+            }
+            header.push(Line::from(spans));
+        }
+        if let Some(description) = &group.description {
+            header.push(Line::styled(
+                description.as_str(),
+                Style::default().fg(theme.muted.resolve()),
+            ));
+        }
+        let header_area = Rect {
+            height: group.header_height(),
+            ..area
+        }
+        .intersection(area);
+        frame.render_widget(Paragraph::new(header), header_area);
+        y += header_area.height;
+
+        for field in &mut group.fields {
+            let field_area = Rect::new(area.x, y, area.width, field.height()).intersection(area);
+            field.view(frame, field_area, &theme);
+            y += field_area.height;
+        }
+
+        frame.render_widget(help, help_area.intersection(area));
+    }
+}
+
+impl HandleEvent for Form {
+    fn handle_event(&mut self, event: &Event) -> EventOutcome {
+        if self.reviewing {
+            return self.handle_review_event(event);
+        }
+        // An input showing a suggestion gets the first say on Tab, so it
+        // can accept it
+        let offers_suggestion = self
+            .current()
+            .is_some_and(|field| field.offers_suggestion());
+        if let Event::Key(key) = event {
+            match self.keymap.action(key) {
+                Some(Action::FocusNext) if !offers_suggestion => return self.next(),
+                Some(Action::FocusPrevious) => {
+                    self.back();
+                    return EventOutcome::Consumed;
+                }
+                _ => {}
+            }
+        }
+        let outcome = match self.current() {
+            Some(field) => field.component().handle_event(event),
+            None => EventOutcome::Ignored,
+        };
+        match outcome {
+            EventOutcome::Submitted => self.next(),
+            EventOutcome::Ignored => match event {
+                Event::Key(key) => match self.keymap.action(key) {
+                    Some(Action::Submit | Action::FocusNext) => self.next(),
+                    Some(Action::Cancel) => EventOutcome::Cancelled,
+                    Some(Action::Interrupt) => EventOutcome::Interrupted,
+                    _ => EventOutcome::Ignored,
+                },
+                _ => EventOutcome::Ignored,
+            },
+            outcome => outcome,
+        }
+    }
+}
+
+impl<M> Model<M> for Form {
+    fn update(&mut self, msg: Msg<M>) -> Cmd<M> {
+        if let Some(event) = msg.to_event() {
+            self.handle_event(&event);
+        }
+        Cmd::none()
+    }
+
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        Form::view(self, frame, area);
+    }
+}
+
+/// Ask every field of `form` starting at `(x, y)` and return the answers.
+pub fn handle_form(form: &mut Form, x: u16, y: u16) -> Result<PromptResult<FormValues>> {
+    if !fallback::is_interactive() {
+        return form.prompt_plain(&mut io::stdin().lock(), &mut io::stdout());
+    }
+    let _session = TerminalSession::with_options(SessionOptions::new().bracketed_paste(true))?;
+    let fixed_terminal = |form: &Form| -> Result<_> {
+        let area = tui::area_at(x, y, form.height())?;
+        let viewport = Viewport::Fixed(area);
+        Ok(Terminal::with_options(
+            CrosstermBackend::new(stdout()),
+            TerminalOptions { viewport },
+        )?)
+    };
+    let mut terminal = fixed_terminal(form)?;
+    loop {
+        terminal.draw(|frame| {
+            form.view(frame, frame.size());
+            tui::adapt_colors(frame);
+        })?;
+        let event = read()?;
+        // A fixed viewport keeps its area, so start over with one that fits
+        if let Event::Resize(..) = event {
+            tui::clear_below(y)?;
+            terminal = fixed_terminal(form)?;
+        }
+        match form.handle_event(&event) {
+            EventOutcome::Submitted => return Ok(PromptResult::Submitted(form.values())),
+            EventOutcome::Cancelled => return Ok(PromptResult::Cancelled),
+            EventOutcome::Interrupted => return Ok(PromptResult::Interrupted),
+            EventOutcome::Consumed | EventOutcome::Ignored => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyEvent;
+
+    fn press(form: &mut Form, code: KeyCode) -> EventOutcome {
+        form.handle_event(&Event::Key(KeyEvent::new(code, KeyModifiers::NONE)))
+    }
+
+    fn type_text(form: &mut Form, text: &str) {
+        for c in text.chars() {
+            press(form, KeyCode::Char(c));
+        }
+    }
+
+    fn form() -> Form {
+        Form::new(vec![
+            Group::new(vec![
+                Field::input("name", "Name").required(),
+                Field::select("color", "Color", &["red", "green"]),
+            ])
+            .with_title("Profile"),
+            Group::new(vec![
+                Field::multi_select("toppings", "Toppings", &["cheese", "ham", "olives"]),
+                Field::confirm("save", "Save?"),
+            ]),
+        ])
+    }
+
+    #[test]
+    fn walks_through_groups_and_returns_values() {
+        let mut form = form();
+        type_text(&mut form, "Ada");
+        assert_eq!(press(&mut form, KeyCode::Enter), EventOutcome::Consumed);
+        press(&mut form, KeyCode::Down);
+        press(&mut form, KeyCode::Enter);
+        assert_eq!(form.position(), (1, 0));
+
+        press(&mut form, KeyCode::Char(' '));
+        press(&mut form, KeyCode::Down);
+        press(&mut form, KeyCode::Down);
+        press(&mut form, KeyCode::Char(' '));
+        press(&mut form, KeyCode::Tab);
+        press(&mut form, KeyCode::Char('n'));
+        assert_eq!(press(&mut form, KeyCode::Enter), EventOutcome::Consumed);
+        assert!(form.is_reviewing());
+        assert_eq!(press(&mut form, KeyCode::Enter), EventOutcome::Submitted);
+
+        let values = form.values();
+        assert_eq!(values.text("name"), Some("Ada"));
+        assert_eq!(values.text("color"), Some("green"));
+        let toppings = ["cheese".to_string(), "olives".to_string()];
+        assert_eq!(values.choices("toppings"), Some(&toppings[..]));
+        assert_eq!(values.bool("save"), Some(false));
+    }
+
+    #[test]
+    fn validates_before_moving_on() {
+        let mut form = form();
+        press(&mut form, KeyCode::Enter);
+        assert_eq!(form.position(), (0, 0));
+        assert_eq!(
+            form.groups[0].fields[0].error(),
+            Some("This field is required")
+        );
+
+        type_text(&mut form, "Ada");
+        press(&mut form, KeyCode::Tab);
+        assert_eq!(form.position(), (0, 1));
+        assert_eq!(form.groups[0].fields[0].error(), None);
+    }
+
+    #[test]
+    fn goes_back_to_earlier_answers() {
+        let mut form = form();
+        type_text(&mut form, "Ada");
+        press(&mut form, KeyCode::Tab);
+        press(&mut form, KeyCode::Tab);
+        assert_eq!(form.position(), (1, 0));
+
+        let back = KeyEvent::new(KeyCode::BackTab, KeyModifiers::SHIFT);
+        form.handle_event(&Event::Key(back));
+        form.handle_event(&Event::Key(back));
+        assert_eq!(form.position(), (0, 0));
+        press(&mut form, KeyCode::Backspace);
+        assert_eq!(form.values().text("name"), Some("Ad"));
+        assert_eq!(press(&mut form, KeyCode::Esc), EventOutcome::Cancelled);
+    }
+
+    #[test]
+    fn tab_accepts_a_suggestion_before_moving_on() {
+        let branch =
+            TextInput::new(None, 0, "", "Branch", None, ">").with_suggestions(["main", "develop"]);
+        let mut form = Form::new(vec![Group::new(vec![
+            Field::from_input("branch", branch),
+            Field::confirm("push", "Push?"),
+        ])]);
+        type_text(&mut form, "de");
+        assert_eq!(press(&mut form, KeyCode::Tab), EventOutcome::Consumed);
+        assert_eq!(form.position(), (0, 0));
+        assert_eq!(form.values().text("branch"), Some("develop"));
+
+        // With nothing left to suggest, Tab moves on
+        press(&mut form, KeyCode::Tab);
+        assert_eq!(form.position(), (0, 1));
+    }
+
+    #[test]
+    fn reviews_answers_on_a_summary_page() {
+        use ratatui::{backend::TestBackend, Terminal};
+
+        let mut form = form();
+        type_text(&mut form, "Ada");
+        press(&mut form, KeyCode::Enter);
+        press(&mut form, KeyCode::Enter);
+        press(&mut form, KeyCode::Char(' '));
+        press(&mut form, KeyCode::Enter);
+        assert_eq!(press(&mut form, KeyCode::Enter), EventOutcome::Consumed);
+        assert!(form.is_reviewing());
+
+        let mut terminal = Terminal::new(TestBackend::new(30, form.height())).unwrap();
+        terminal
+            .draw(|frame| form.view(frame, frame.size()))
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        let row = |y| -> String { (0..30).map(|x| buffer.get(x, y).symbol()).collect() };
+        assert_eq!(row(0).trim_end(), "Summary");
+        assert_eq!(row(2).trim_end(), "Name: Ada");
+        assert_eq!(row(3).trim_end(), "Color: red");
+        assert_eq!(row(4).trim_end(), "Toppings: cheese");
+        assert_eq!(row(5).trim_end(), "Save?: Yes");
+
+        // Shift+Tab goes back to the last field to change it
+        let back = KeyEvent::new(KeyCode::BackTab, KeyModifiers::SHIFT);
+        form.handle_event(&Event::Key(back));
+        assert!(!form.is_reviewing());
+        assert_eq!(form.position(), (1, 1));
+        press(&mut form, KeyCode::Char('n'));
+        press(&mut form, KeyCode::Enter);
+        assert_eq!(press(&mut form, KeyCode::Enter), EventOutcome::Submitted);
+        assert_eq!(form.values().bool("save"), Some(false));
+
+        let mut form = form.with_summary(false);
+        form.handle_event(&Event::Key(back));
+        assert_eq!(press(&mut form, KeyCode::Enter), EventOutcome::Submitted);
+    }
+
+    #[test]
+    fn asks_on_plain_lines_without_a_terminal() {
+        let mut form = form();
+        let mut output = Vec::new();
+        let mut input = "\nAda\n2\n1,3\nn\n\n".as_bytes();
+        let values = form.prompt_plain(&mut input, &mut output).unwrap().ok();
+        let values = values.expect("every field was answered");
+        assert_eq!(values.text("name"), Some("Ada"));
+        assert_eq!(values.text("color"), Some("green"));
+        let toppings = ["cheese".to_string(), "olives".to_string()];
+        assert_eq!(values.choices("toppings"), Some(&toppings[..]));
+        assert_eq!(values.bool("save"), Some(false));
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.starts_with("Profile\nName: ✗ This field is required\nName: "));
+        assert!(output.contains("Summary\n  Name: Ada\n  Color: green\n"));
+        assert!(output.ends_with("  Save?: No\nSubmit? [Y/n] "));
+
+        let mut input = "Ada\n".as_bytes();
+        let result = form.prompt_plain(&mut input, &mut Vec::new()).unwrap();
+        assert_eq!(result, PromptResult::Cancelled);
+    }
+}
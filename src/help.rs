@@ -1,18 +1,25 @@
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
-    style::{Color, Modifier, Style},
-    text::{Line, Span, Text},
-    widgets::{Block, Borders, Paragraph, Widget},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Paragraph, Widget},
 };
 
-use crate::command::CommandInfo;
+use crate::{
+    command::CommandInfo,
+    keymap::{Action, KeyMap},
+    theme::Theme,
+    unicode,
+};
 
 #[derive(Clone, Debug)]
 pub struct HelpComponent {
     pub normal_commands: Vec<CommandInfo>,
     filter_commands: Option<Vec<CommandInfo>>,
     active_commands: Vec<CommandInfo>,
+    pending: Option<String>,
+    theme: Theme,
 }
 
 impl HelpComponent {
@@ -21,9 +28,28 @@ impl HelpComponent {
             normal_commands: normal_commands.clone(),
             filter_commands: Some(filter_commands),
             active_commands: normal_commands, // Start in normal mode by default
+            pending: None,
+            theme: Theme::default(),
         }
     }
 
+    /// A help bar listing the keys `keymap` binds to `actions`.
+    pub fn from_keymap(keymap: &KeyMap, actions: &[Action]) -> Self {
+        Self::new(keymap.commands(actions), vec![])
+    }
+
+    /// Show the keys of an unfinished sequence or count, see
+    /// [`KeyBuffer::pending`](crate::keymap::KeyBuffer::pending).
+    pub fn with_pending(mut self, pending: Option<String>) -> Self {
+        self.pending = pending;
+        self
+    }
+
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
     // Method to switch to filter mode
     pub fn activate_filter_mode(&mut self) {
         self.active_commands = self.filter_commands.clone().unwrap();
@@ -34,39 +60,99 @@ impl HelpComponent {
         self.active_commands = self.normal_commands.clone();
     }
 }
+const SEPARATOR: &str = " • ";
+
+// Keeps whole commands together, starting a new row when the next one
+// doesn't fit and ending with `…` when the rows run out
 impl Widget for HelpComponent {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        let mut spans = Vec::new();
-
-        for (indx, cmd) in self.active_commands.iter().enumerate() {
-            spans.push(Span::styled(
-                format!("{} ", cmd.key),
-                Style::default().fg(Color::DarkGray),
-            ));
+        let width = area.width as usize;
+        let rows = area.height.max(1) as usize;
+        let key_style = Style::default().fg(self.theme.muted.resolve());
+        let description_style = key_style.add_modifier(Modifier::BOLD);
 
-            spans.push(Span::styled(
-                format!("{}", cmd.description),
+        let mut lines = vec![Line::default()];
+        let mut used = 0;
+        if let Some(pending) = &self.pending {
+            let label = format!("{}… ", pending);
+            used = unicode::width(&label);
+            lines[0].spans.push(Span::styled(
+                label,
                 Style::default()
-                    .fg(Color::DarkGray)
+                    .fg(self.theme.accent.resolve())
                     .add_modifier(Modifier::BOLD),
             ));
+        }
 
-            if indx < self.active_commands.len() - 1 {
-                spans.push(Span::raw(" • "));
+        let mut overflow = false;
+        let mut first = true;
+        for cmd in &self.active_commands {
+            let key = format!("{} ", cmd.key);
+            let command_width = unicode::width(&key) + unicode::width(&cmd.description);
+            if !first && used + unicode::width(SEPARATOR) + command_width > width {
+                if lines.len() == rows {
+                    overflow = true;
+                    break;
+                }
+                lines.push(Line::default());
+                used = 0;
+            } else if !first {
+                lines.last_mut().unwrap().spans.push(Span::raw(SEPARATOR));
+                used += unicode::width(SEPARATOR);
             }
+            // A command wider than a whole row loses the end of its description
+            let room = width.saturating_sub(used + unicode::width(&key));
+            let description = unicode::truncate(&cmd.description, room).into_owned();
+            used += unicode::width(&key) + unicode::width(&description);
+            let line = lines.last_mut().unwrap();
+            line.spans.push(Span::styled(key, key_style));
+            line.spans
+                .push(Span::styled(description, description_style));
+            first = false;
+        }
+        if overflow && used + 2 <= width {
+            lines.last_mut().unwrap().spans.push(Span::raw(" …"));
+        }
 
-            let line = Line::from(spans.clone());
-            let text = Text::from(vec![line]);
+        Paragraph::new(lines).render(area, buf);
+    }
+}
 
-            let block = Block::default().borders(Borders::NONE);
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-            let inner_area = block.inner(area);
-            block.clone().render(area, buf);
+    fn commands() -> Vec<CommandInfo> {
+        [
+            ("↑/k", "up"),
+            ("↓/j", "down"),
+            ("⮐", "submit"),
+            ("esc", "quit"),
+        ]
+        .into_iter()
+        .map(|(key, description)| CommandInfo::new(key, description))
+        .collect()
+    }
 
-            let paragraph = Paragraph::new(text)
-                .block(block)
-                .wrap(ratatui::widgets::Wrap { trim: true });
-            paragraph.render(inner_area, buf);
-        }
+    fn render(help: HelpComponent, width: u16, height: u16) -> Vec<String> {
+        let area = Rect::new(0, 0, width, height);
+        let mut buf = Buffer::empty(area);
+        help.render(area, &mut buf);
+        (0..height)
+            .map(|y| (0..width).map(|x| buf.get(x, y).symbol()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn wraps_whole_commands_and_marks_overflow() {
+        let help = HelpComponent::new(commands(), vec![]);
+        assert_eq!(
+            render(help.clone(), 26, 2),
+            ["↑/k up • ↓/j down         ", "⮐ submit • esc quit       "].map(String::from)
+        );
+        assert_eq!(
+            render(help, 26, 1),
+            ["↑/k up • ↓/j down …       "].map(String::from)
+        );
     }
 }
@@ -1,31 +1,45 @@
-use crossterm::{
-    cursor::MoveTo,
-    execute,
-    style::{Color, Print, SetForegroundColor},
-};
+use ratatui::{buffer::Buffer, layout::Rect, style::Style, text::Span, widgets::Widget};
+
+use crate::{colors::AdaptiveColor, theme::Theme, tui, Result};
 
 pub struct Helper {
     text: String,
-    color: Color,
+    color: AdaptiveColor,
 }
 
 impl Helper {
     pub fn new(text: &str) -> Self {
         Helper {
             text: text.to_string(),
-            color: Color::DarkGrey, // Default color for helper text
+            color: Theme::default().muted,
         }
     }
 
-    pub fn render(&self, x: u16, y: u16) {
-        // Use crossterm to set color and position before printing the helper text
-        execute!(
-            std::io::stdout(),
-            MoveTo(x, y),
-            SetForegroundColor(self.color),
-            Print(&self.text),
-            SetForegroundColor(Color::Reset) // Reset color after rendering
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.set_theme(theme);
+        self
+    }
+
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.color = theme.muted;
+    }
+
+    /// The helper text styled for use inside a ratatui `Line`.
+    pub fn span(&self) -> Span<'_> {
+        Span::styled(
+            self.text.as_str(),
+            Style::default().fg(self.color.resolve()),
         )
-        .unwrap();
+    }
+
+    pub fn render(&self, x: u16, y: u16) -> Result<()> {
+        let area = tui::area_at(x, y, 1)?;
+        tui::draw_widget(self, area)
+    }
+}
+
+impl Widget for &Helper {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        self.span().render(area, buf);
     }
 }
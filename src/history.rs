@@ -0,0 +1,191 @@
+use std::{
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+use crate::Result;
+
+/// How many entries a [`History`] keeps by default.
+pub const DEFAULT_HISTORY_LIMIT: usize = 1000;
+
+/// Values submitted to a [`TextInput`](crate::input::TextInput), oldest
+/// first, for recalling them with Up and Down or searching with Ctrl+R.
+///
+/// Submitting a value that is already in the history moves it to the end
+/// rather than adding it twice, and the oldest entries are dropped past the
+/// limit. A history loaded from a file is written back to it, one entry per
+/// line, every time a value is added.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct History {
+    entries: Vec<String>,
+    path: Option<PathBuf>,
+    limit: Option<usize>,
+}
+
+impl History {
+    /// An empty history kept in memory only.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The history saved at `path`, or an empty one if the file doesn't
+    /// exist yet. It is created on the first save.
+    ///
+    /// Every entry in the file is loaded, so a limit set afterwards decides
+    /// which are kept; it applies when the limit is set, a value is pushed
+    /// or the history is saved.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let entries = match fs::read_to_string(path) {
+            Ok(text) => text
+                .lines()
+                .filter(|line| !line.is_empty())
+                .map(String::from)
+                .collect(),
+            Err(err) if err.kind() == ErrorKind::NotFound => Vec::new(),
+            Err(err) => return Err(err.into()),
+        };
+        let mut history = Self {
+            entries: Vec::new(),
+            path: Some(path.to_path_buf()),
+            limit: None,
+        };
+        for entry in entries {
+            history.add(entry);
+        }
+        Ok(history)
+    }
+
+    /// Keep at most `limit` entries, dropping the oldest.
+    /// [`DEFAULT_HISTORY_LIMIT`] applies otherwise.
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.set_limit(limit);
+        self
+    }
+
+    pub fn set_limit(&mut self, limit: usize) {
+        self.limit = Some(limit);
+        self.trim();
+    }
+
+    /// The entries, oldest first.
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The file the history is saved to, if it was loaded from one.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Add `entry` as the newest, and save the history if it has a file.
+    /// Blank entries and line breaks are left out, since entries are
+    /// stored one per line.
+    pub fn push(&mut self, entry: &str) -> Result<()> {
+        let entry = entry.replace(['\r', '\n'], "");
+        if entry.trim().is_empty() {
+            return Ok(());
+        }
+        self.add(entry);
+        self.trim();
+        self.save()
+    }
+
+    /// Write the entries within the limit to the history file, if there is
+    /// one.
+    pub fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let mut text = self.entries[self.excess()..].join("\n");
+        text.push('\n');
+        fs::write(path, text)?;
+        Ok(())
+    }
+
+    /// Index of the newest entry before `before` that contains `query`,
+    /// searching the whole history when `before` is `None`.
+    pub fn search(&self, query: &str, before: Option<usize>) -> Option<usize> {
+        let end = before.unwrap_or(self.entries.len()).min(self.entries.len());
+        self.entries[..end]
+            .iter()
+            .rposition(|entry| entry.contains(query))
+    }
+
+    fn add(&mut self, entry: String) {
+        self.entries.retain(|existing| *existing != entry);
+        self.entries.push(entry);
+    }
+
+    // How many of the oldest entries are past the limit
+    fn excess(&self) -> usize {
+        let limit = self.limit.unwrap_or(DEFAULT_HISTORY_LIMIT);
+        self.entries.len().saturating_sub(limit)
+    }
+
+    fn trim(&mut self) {
+        let excess = self.excess();
+        self.entries.drain(..excess);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deduplicates_and_limits_entries() {
+        let mut history = History::new().with_limit(3);
+        for entry in ["ls", "cd src", "ls", "", "git status", "cargo test"] {
+            history.push(entry).unwrap();
+        }
+        assert_eq!(history.entries(), ["ls", "git status", "cargo test"]);
+        assert_eq!(history.search("s", None), Some(2));
+        assert_eq!(history.search("s", Some(2)), Some(1));
+        assert_eq!(history.search("cd", None), None);
+    }
+
+    #[test]
+    fn saves_to_and_loads_from_a_file() {
+        let path = std::env::temp_dir().join(format!("rustubble-history-{}", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let mut history = History::load(&path).unwrap();
+        assert!(history.is_empty());
+        history.push("first").unwrap();
+        history.push("second\n").unwrap();
+
+        let history = History::load(&path).unwrap().with_limit(1);
+        assert_eq!(history.entries(), ["second"]);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn loads_more_than_the_default_limit() {
+        let path =
+            std::env::temp_dir().join(format!("rustubble-long-history-{}", std::process::id()));
+        let lines: Vec<String> = (0..1500).map(|i| format!("command {}", i)).collect();
+        fs::write(&path, lines.join("\n")).unwrap();
+
+        let mut history = History::load(&path).unwrap().with_limit(5000);
+        assert_eq!(history.len(), 1500);
+        history.push("command 1500").unwrap();
+        assert_eq!(History::load(&path).unwrap().len(), 1501);
+
+        // Without a limit of its own the default applies once it is saved
+        let mut history = History::load(&path).unwrap();
+        history.save().unwrap();
+        assert_eq!(History::load(&path).unwrap().len(), DEFAULT_HISTORY_LIMIT);
+        history.push("command 0").unwrap();
+        assert_eq!(history.entries().first().unwrap(), "command 502");
+        fs::remove_file(&path).unwrap();
+    }
+}
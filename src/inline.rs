@@ -0,0 +1,179 @@
+use std::io::{self, stdout, BufRead, Write};
+
+use crossterm::{cursor::MoveTo, event::read, execute};
+use ratatui::{
+    backend::CrosstermBackend,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    Terminal, TerminalOptions, Viewport,
+};
+
+use crate::{
+    event::{EventOutcome, HandleEvent, PromptResult},
+    fallback,
+    program::Model,
+    theme::Theme,
+    tui::{self, SessionOptions, TerminalSession},
+    Result,
+};
+
+/// A component that can run as an inline prompt, see [`run_inline`].
+pub trait InlinePrompt: HandleEvent + Model {
+    type Value;
+
+    /// Number of terminal lines the component needs below the cursor.
+    fn inline_height(&self) -> u16;
+
+    /// The value to return once the component reports
+    /// [`EventOutcome::Submitted`], or `None` if there is nothing to submit.
+    fn submitted_value(&self) -> Option<Self::Value>;
+
+    /// Ask for the value on plain lines of `input` instead, for when there
+    /// is no terminal. Without a plain prompt of its own a component submits
+    /// its current value.
+    fn fallback_value(
+        &mut self,
+        _input: &mut impl BufRead,
+        _output: &mut impl Write,
+    ) -> Result<PromptResult<Self::Value>> {
+        Ok(match self.submitted_value() {
+            Some(value) => PromptResult::Submitted(value),
+            None => PromptResult::Cancelled,
+        })
+    }
+
+    /// The value as shown in the [`InlineEnd::Summary`] line.
+    fn summary(&self, value: &Self::Value) -> String;
+
+    /// The theme the summary line is drawn with.
+    fn theme(&self) -> Theme {
+        Theme::default()
+    }
+}
+
+/// What an inline prompt leaves behind when it finishes.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum InlineEnd {
+    /// Erase the prompt, leaving the cursor where it started.
+    #[default]
+    Clear,
+    /// Collapse a submitted prompt into one line such as
+    /// `✔ Project name: foo`. A cancelled prompt is erased.
+    Summary(String),
+}
+
+/// Run `prompt` in the lines below the cursor instead of taking over the
+/// screen, so the shell's scrollback stays intact. Without a terminal the
+/// prompt asks on plain lines instead, see [`fallback`].
+pub fn run_inline<P: InlinePrompt>(
+    prompt: &mut P,
+    end: InlineEnd,
+) -> Result<PromptResult<P::Value>> {
+    if !fallback::is_interactive() {
+        return run_plain(prompt, &end, &mut io::stdin().lock(), &mut stdout());
+    }
+    let _session = TerminalSession::with_options(SessionOptions::new().bracketed_paste(true))?;
+    let mut terminal = Terminal::with_options(
+        CrosstermBackend::new(stdout()),
+        TerminalOptions {
+            viewport: Viewport::Inline(prompt.inline_height()),
+        },
+    )?;
+
+    let mut area = Rect::default();
+    let result = loop {
+        terminal.draw(|frame| {
+            area = frame.size();
+            prompt.view(frame, area);
+            tui::adapt_colors(frame);
+        })?;
+
+        match prompt.handle_event(&read()?) {
+            EventOutcome::Submitted => {
+                if let Some(value) = prompt.submitted_value() {
+                    break PromptResult::Submitted(value);
+                }
+            }
+            EventOutcome::Cancelled => break PromptResult::Cancelled,
+            EventOutcome::Interrupted => break PromptResult::Interrupted,
+            EventOutcome::Consumed | EventOutcome::Ignored => {}
+        }
+    };
+
+    // Leaves the cursor at the top of the cleared area
+    terminal.clear()?;
+    if let (InlineEnd::Summary(label), PromptResult::Submitted(value)) = (&end, &result) {
+        let line = summary_line(label, &prompt.summary(value), &prompt.theme());
+        tui::draw_widget(line, Rect::new(area.x, area.y, area.width, 1))?;
+        // Move below the summary, scrolling if it sits on the last row
+        let mut stdout = stdout();
+        execute!(stdout, MoveTo(0, area.y))?;
+        write!(stdout, "\r\n")?;
+        stdout.flush()?;
+    }
+    terminal.show_cursor()?;
+
+    Ok(result)
+}
+
+// The plain line version of `run_inline`, with the summary as text
+fn run_plain<P: InlinePrompt>(
+    prompt: &mut P,
+    end: &InlineEnd,
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+) -> Result<PromptResult<P::Value>> {
+    let result = prompt.fallback_value(input, output)?;
+    if let (InlineEnd::Summary(label), PromptResult::Submitted(value)) = (end, &result) {
+        writeln!(output, "✔ {}: {}", label, prompt.summary(value))?;
+    }
+    Ok(result)
+}
+
+fn summary_line<'a>(label: &'a str, value: &str, theme: &Theme) -> Line<'a> {
+    Line::from(vec![
+        Span::styled("✔ ", Style::default().fg(theme.success.resolve())),
+        Span::styled(label, Style::default().add_modifier(Modifier::BOLD)),
+        Span::raw(": "),
+        Span::styled(
+            value.to_string(),
+            Style::default().fg(theme.secondary.resolve()),
+        ),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::TextInput;
+    use ratatui::{buffer::Buffer, style::Color, widgets::Widget};
+
+    #[test]
+    fn renders_summary_line() {
+        let area = Rect::new(0, 0, 24, 1);
+        let mut buf = Buffer::empty(area);
+        summary_line("Project name", "foo", &Theme::default()).render(area, &mut buf);
+
+        let symbols: String = buf.content.iter().map(|cell| cell.symbol()).collect();
+        assert_eq!(symbols, "✔ Project name: foo     ");
+        assert_eq!(buf.get(0, 0).fg, Color::Green);
+    }
+
+    #[test]
+    fn runs_on_plain_lines_without_a_terminal() {
+        let mut input = TextInput::new(None, 0, "", "Project name", None, ">");
+        let mut output = Vec::new();
+        let end = InlineEnd::Summary("Project name".to_string());
+        let result = run_plain(&mut input, &end, &mut &b"foo\n"[..], &mut output).unwrap();
+        assert_eq!(result, PromptResult::Submitted("foo".to_string()));
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "Project name: ✔ Project name: foo\n"
+        );
+
+        let mut output = Vec::new();
+        let result = run_plain(&mut input, &end, &mut &b""[..], &mut output).unwrap();
+        assert_eq!(result, PromptResult::Cancelled);
+    }
+}
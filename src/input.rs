@@ -1,21 +1,133 @@
+use std::{
+    borrow::Cow,
+    io::{self, BufRead, Write},
+    time::Duration,
+};
+
 use crossterm::{
     cursor::MoveTo,
-    event::{read, Event, KeyCode, KeyEvent, KeyModifiers},
+    event::{poll, read, Event, KeyCode, KeyEvent, KeyModifiers, MouseEvent},
     execute,
-    style::{Color, Print, SetForegroundColor},
-    terminal::{Clear, ClearType},
 };
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Style,
+    text::{Line, Span},
+    widgets::{Paragraph, StatefulWidget, Widget},
+    Frame,
+};
+use unicode_segmentation::UnicodeSegmentation;
 
-use crate::helper::Helper;
+use crate::{
+    editor::TextBuffer,
+    event::{EventOutcome, HandleEvent, PromptResult},
+    fallback,
+    focus::Focusable,
+    helper::Helper,
+    history::History,
+    inline::InlinePrompt,
+    keymap::{Action, Component, KeyMap},
+    mouse,
+    program::{Cmd, Model, Msg},
+    theme::Theme,
+    tui, unicode,
+    validate::{ValidateOn, Validator},
+    Result,
+};
 
 pub struct TextInput {
-    text: String,
-    cursor_position: usize,
+    buffer: TextBuffer,
     placeholder: Option<String>,
     padding: usize,
     label: String,
     helper: Option<Helper>,
     prefix: String,
+    state: TextInputState,
+    keymap: KeyMap,
+    theme: Theme,
+    focused: bool,
+    paste_newlines: PasteNewlines,
+    echo_mode: EchoMode,
+    validators: Vec<Validator>,
+    validate_on: ValidateOn,
+    error: Option<String>,
+    suggestions: Option<SuggestionSource>,
+    matches: Vec<String>,
+    selected_match: usize,
+    suggestion_rows: usize,
+    history: Option<History>,
+    history_prefix_search: bool,
+    // The entry shown while walking the history
+    browsing: Option<usize>,
+    // The text typed before browsing started, restored when walking past
+    // the newest entry
+    draft: String,
+    search: Option<HistorySearch>,
+    width: Option<usize>,
+    char_limit: Option<usize>,
+}
+
+type SuggestionSource = Box<dyn Fn(&str) -> Vec<String>>;
+
+// An open Ctrl+R search: what has been typed and the newest entry containing it
+struct HistorySearch {
+    query: String,
+    found: Option<usize>,
+}
+
+const SEARCH_LABEL: &str = "(reverse-i-search)`";
+const FAILED_SEARCH_LABEL: &str = "(failed reverse-i-search)`";
+
+/// What a [`TextInput`] does with line breaks in pasted text, since it only
+/// holds one line.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PasteNewlines {
+    /// Remove the line breaks and insert the rest.
+    #[default]
+    Strip,
+    /// Ignore a paste that contains a line break.
+    Reject,
+}
+
+/// How a [`TextInput`] shows what is typed into it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EchoMode {
+    /// Show the text as it is.
+    #[default]
+    Normal,
+    /// Show each character as the given mask, such as `•`, for passwords
+    /// and tokens.
+    Password(char),
+    /// Show nothing and keep the cursor still, like `sudo`.
+    None,
+}
+
+/// Where a [`TextInput`] was last drawn. The cursor within the text is
+/// kept by its [`TextBuffer`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TextInputState {
+    cursor: Option<(u16, u16)>,
+    area: Rect,
+    scroll: usize,
+}
+
+impl TextInputState {
+    /// Screen position of the cursor after the last render, if it fit in the area.
+    pub fn cursor(&self) -> Option<(u16, u16)> {
+        self.cursor
+    }
+
+    /// The area of the last render, which mouse clicks are checked against.
+    pub fn area(&self) -> Rect {
+        self.area
+    }
+
+    /// Index of the first character shown, once the text is scrolled to
+    /// keep the cursor in view.
+    pub fn scroll_offset(&self) -> usize {
+        self.scroll
+    }
 }
 
 impl TextInput {
@@ -28,168 +140,1028 @@ impl TextInput {
         prefix: &str,
     ) -> Self {
         TextInput {
-            text: initial_text.to_string(),
-            cursor_position: initial_text.len(),
+            buffer: TextBuffer::from_text(&initial_text.replace(['\r', '\n'], "")),
             placeholder: placeholder.map(String::from),
             padding,
             label: label.to_string(),
-            helper: helper_text.map(|text| Helper::new(text)), // Initialize helper if provided
+            helper: helper_text.map(Helper::new), // Initialize helper if provided
             prefix: prefix.to_string(),
+            state: TextInputState::default(),
+            keymap: KeyMap::default_for(Component::TextInput),
+            theme: Theme::default(),
+            focused: true,
+            paste_newlines: PasteNewlines::default(),
+            echo_mode: EchoMode::default(),
+            validators: Vec::new(),
+            validate_on: ValidateOn::default(),
+            error: None,
+            suggestions: None,
+            matches: Vec::new(),
+            selected_match: 0,
+            suggestion_rows: 0,
+            history: None,
+            history_prefix_search: false,
+            browsing: None,
+            draft: String::new(),
+            search: None,
+            width: None,
+            char_limit: None,
+        }
+    }
+
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.set_theme(theme);
+        self
+    }
+
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+        if let Some(helper) = self.helper.as_mut() {
+            helper.set_theme(theme);
+        }
+    }
+
+    pub fn with_keymap(mut self, keymap: KeyMap) -> Self {
+        self.keymap = keymap;
+        self
+    }
+
+    pub fn set_keymap(&mut self, keymap: KeyMap) {
+        self.keymap = keymap;
+    }
+
+    pub fn with_paste_newlines(mut self, paste_newlines: PasteNewlines) -> Self {
+        self.set_paste_newlines(paste_newlines);
+        self
+    }
+
+    pub fn set_paste_newlines(&mut self, paste_newlines: PasteNewlines) {
+        self.paste_newlines = paste_newlines;
+    }
+
+    /// Hide the text as it is typed, for passwords and other secrets. The
+    /// placeholder is still shown while the input is empty.
+    pub fn with_echo_mode(mut self, echo_mode: EchoMode) -> Self {
+        self.set_echo_mode(echo_mode);
+        self
+    }
+
+    pub fn set_echo_mode(&mut self, echo_mode: EchoMode) {
+        self.echo_mode = echo_mode;
+    }
+
+    // `text` as the echo mode shows it
+    fn echoed<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        match self.echo_mode {
+            EchoMode::Normal => Cow::Borrowed(text),
+            EchoMode::Password(mask) => {
+                let count = text.graphemes(true).count();
+                Cow::Owned(mask.to_string().repeat(count))
+            }
+            EchoMode::None => Cow::Borrowed(""),
+        }
+    }
+
+    /// Show at most `width` columns of the text, scrolling it sideways to
+    /// keep the cursor in view. A `…` marks text hidden on either side.
+    /// Without a width the text scrolls at the edge of the area.
+    pub fn with_width(mut self, width: usize) -> Self {
+        self.set_width(width);
+        self
+    }
+
+    pub fn set_width(&mut self, width: usize) {
+        self.width = Some(width);
+    }
+
+    /// Accept at most `limit` characters. Typing stops at the limit and
+    /// longer pasted, suggested or recalled text is cut to it.
+    pub fn with_char_limit(mut self, limit: usize) -> Self {
+        self.set_char_limit(limit);
+        self
+    }
+
+    pub fn set_char_limit(&mut self, limit: usize) {
+        self.char_limit = Some(limit);
+        if self.value().graphemes(true).count() > limit {
+            let value = self.value().to_string();
+            self.replace_text(&value);
+        }
+    }
+
+    // The start of `text` that fits within the character limit after
+    // `used` characters
+    fn fit_to_limit<'a>(&self, text: &'a str, used: usize) -> &'a str {
+        let Some(limit) = self.char_limit else {
+            return text;
+        };
+        let room = limit.saturating_sub(used);
+        text.grapheme_indices(true)
+            .nth(room)
+            .map_or(text, |(end, _)| &text[..end])
+    }
+
+    // Replace the whole text, kept to one line and cut to the character limit
+    fn replace_text(&mut self, text: &str) {
+        let text = text.replace(['\r', '\n'], "");
+        self.buffer = TextBuffer::from_text(self.fit_to_limit(&text, 0));
+    }
+
+    /// Check the text before it can be submitted, with one of the
+    /// [`validate`](crate::validate) functions or any closure returning the
+    /// message to show. The message replaces the helper text, in red, until
+    /// the text passes.
+    ///
+    /// Without validators Enter takes any text other than the placeholder;
+    /// once one is added they alone decide, so add
+    /// [`validate::required`](crate::validate::required) to keep refusing
+    /// empty text.
+    pub fn with_validator<F>(mut self, validator: F) -> Self
+    where
+        F: Fn(&str) -> std::result::Result<(), String> + 'static,
+    {
+        self.validators.push(Box::new(validator));
+        self
+    }
+
+    /// Run the validators after every edit instead of only on Enter.
+    pub fn with_validate_on(mut self, validate_on: ValidateOn) -> Self {
+        self.set_validate_on(validate_on);
+        self
+    }
+
+    pub fn set_validate_on(&mut self, validate_on: ValidateOn) {
+        self.validate_on = validate_on;
+    }
+
+    /// Run the validators, keeping the message of the first that fails.
+    /// Returns whether the text passed.
+    pub fn validate(&mut self) -> bool {
+        let value = self.value();
+        self.error = self
+            .validators
+            .iter()
+            .find_map(|validator| validator(value).err());
+        self.error.is_none()
+    }
+
+    /// The message of the last failed validation, if it still applies.
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    // Whether Enter may submit the current text
+    fn can_submit(&mut self) -> bool {
+        if self.validators.is_empty() {
+            return !self.buffer.is_empty() && !self.is_placeholder();
+        }
+        self.validate()
+    }
+
+    // Called after every change to the text
+    fn edited(&mut self) {
+        self.browsing = None;
+        self.text_changed();
+    }
+
+    // Called when the text changes, typed or recalled from the history
+    fn text_changed(&mut self) {
+        if self.validate_on == ValidateOn::Change {
+            self.validate();
+        }
+        self.update_matches();
+    }
+
+    /// Recall earlier values with Up and Down, and search them with Ctrl+R.
+    /// [`handle_input`] adds each submitted value to it, which saves it if
+    /// the history was loaded from a file.
+    pub fn with_history(mut self, history: History) -> Self {
+        self.set_history(history);
+        self
+    }
+
+    pub fn set_history(&mut self, history: History) {
+        self.history = Some(history);
+        self.browsing = None;
+    }
+
+    pub fn history(&self) -> Option<&History> {
+        self.history.as_ref()
+    }
+
+    /// Have Up and Down only recall entries that start with the text typed
+    /// before browsing, like the history search of many shells.
+    pub fn with_history_prefix_search(mut self, prefix_search: bool) -> Self {
+        self.set_history_prefix_search(prefix_search);
+        self
+    }
+
+    pub fn set_history_prefix_search(&mut self, prefix_search: bool) {
+        self.history_prefix_search = prefix_search;
+    }
+
+    /// Add the text to the history, saving it to the history's file if it
+    /// has one. [`handle_input`] calls this on submit; call it yourself when
+    /// driving the input from your own event loop.
+    pub fn save_to_history(&mut self) -> Result<()> {
+        self.browsing = None;
+        if self.buffer.is_empty() || self.is_placeholder() {
+            return Ok(());
+        }
+        let value = self.value().to_string();
+        match self.history.as_mut() {
+            Some(history) => history.push(&value),
+            None => Ok(()),
+        }
+    }
+
+    /// Show the next older history entry, or the next older one starting
+    /// with the typed text when prefix search is on. Returns whether there
+    /// was one.
+    pub fn history_previous(&mut self) -> bool {
+        let Some(history) = &self.history else {
+            return false;
+        };
+        let end = match self.browsing {
+            Some(index) => index,
+            None => history.len(),
+        };
+        let draft = match self.browsing {
+            Some(_) => self.draft.as_str(),
+            None if self.is_placeholder() => "",
+            None => self.value(),
+        };
+        let prefix = if self.history_prefix_search {
+            draft
+        } else {
+            ""
+        };
+        let Some(index) = history.entries()[..end]
+            .iter()
+            .rposition(|entry| entry.starts_with(prefix))
+        else {
+            return false;
+        };
+        let (entry, draft) = (history.entries()[index].clone(), draft.to_string());
+        self.draft = draft;
+        self.browsing = Some(index);
+        self.recall(&entry);
+        true
+    }
+
+    /// Show the next newer history entry, and past the newest the text
+    /// typed before browsing. Returns whether the input was browsing.
+    pub fn history_next(&mut self) -> bool {
+        let (Some(history), Some(current)) = (&self.history, self.browsing) else {
+            return false;
+        };
+        let prefix = if self.history_prefix_search {
+            self.draft.as_str()
+        } else {
+            ""
+        };
+        let newer = history.entries()[current + 1..]
+            .iter()
+            .position(|entry| entry.starts_with(prefix));
+        match newer {
+            Some(offset) => {
+                let entry = history.entries()[current + 1 + offset].clone();
+                self.browsing = Some(current + 1 + offset);
+                self.recall(&entry);
+            }
+            None => {
+                self.browsing = None;
+                let draft = std::mem::take(&mut self.draft);
+                self.recall(&draft);
+            }
+        }
+        true
+    }
+
+    fn recall(&mut self, text: &str) {
+        self.replace_text(text);
+        self.text_changed();
+    }
+
+    /// Start a reverse incremental search of the history, like Ctrl+R in a
+    /// shell. Typing narrows it, Ctrl+R again finds an older match, Enter or
+    /// a movement key takes the match and Esc leaves the text as it was.
+    /// Returns whether there is a history to search.
+    pub fn start_history_search(&mut self) -> bool {
+        let Some(history) = &self.history else {
+            return false;
+        };
+        self.search = Some(HistorySearch {
+            query: String::new(),
+            found: history.search("", None),
+        });
+        true
+    }
+
+    pub fn is_searching_history(&self) -> bool {
+        self.search.is_some()
+    }
+
+    // Keys while a history search is open. Movement keys end the search with
+    // the match taken and are then handled as usual, so `None` is returned.
+    fn handle_search_key(&mut self, key: &KeyEvent) -> Option<EventOutcome> {
+        let (Some(history), Some(search)) = (&self.history, &mut self.search) else {
+            return None;
+        };
+        match self.keymap.action(key) {
+            Some(Action::Interrupt) => return Some(EventOutcome::Interrupted),
+            Some(Action::Cancel) => self.search = None,
+            Some(Action::SearchHistory) => {
+                if let Some(older) = history.search(&search.query, search.found) {
+                    search.found = Some(older);
+                }
+            }
+            Some(Action::Backspace) => {
+                search.query.pop();
+                search.found = history.search(&search.query, None);
+            }
+            Some(Action::Submit) => self.take_search_match(),
+            Some(
+                Action::Left
+                | Action::Right
+                | Action::LineStart
+                | Action::LineEnd
+                | Action::Up
+                | Action::Down,
+            ) => {
+                self.take_search_match();
+                return None;
+            }
+            _ => match key.code {
+                KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.extend_search(c.encode_utf8(&mut [0; 4]))
+                }
+                _ => return Some(EventOutcome::Ignored),
+            },
+        }
+        Some(EventOutcome::Consumed)
+    }
+
+    fn extend_search(&mut self, text: &str) {
+        let (Some(history), Some(search)) = (&self.history, &mut self.search) else {
+            return;
+        };
+        search.query.push_str(text);
+        // The match so far is the newest entry that can contain the longer query
+        let from = search.found.map(|found| found + 1);
+        search.found = history.search(&search.query, from);
+    }
+
+    fn take_search_match(&mut self) {
+        let Some(search) = self.search.take() else {
+            return;
+        };
+        let entry = search
+            .found
+            .and_then(|found| self.history.as_ref()?.entries().get(found).cloned());
+        if let Some(entry) = entry {
+            self.browsing = None;
+            self.recall(&entry);
+        }
+    }
+
+    /// Suggest the candidates that start with the text typed so far. The
+    /// best match is shown as dim ghost text after the value.
+    pub fn with_suggestions<I, S>(self, candidates: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let candidates: Vec<String> = candidates.into_iter().map(Into::into).collect();
+        self.with_suggestion_source(move |text| {
+            candidates
+                .iter()
+                .filter(|candidate| candidate.starts_with(text))
+                .cloned()
+                .collect()
+        })
+    }
+
+    /// Suggest whatever `source` returns for the text typed so far, best
+    /// match first. Only a match that starts with the text can be shown as
+    /// ghost text, but any match can be picked from the list below the
+    /// input set with [`with_suggestion_list`](Self::with_suggestion_list).
+    pub fn with_suggestion_source<F>(mut self, source: F) -> Self
+    where
+        F: Fn(&str) -> Vec<String> + 'static,
+    {
+        self.set_suggestion_source(source);
+        self
+    }
+
+    pub fn set_suggestion_source<F>(&mut self, source: F)
+    where
+        F: Fn(&str) -> Vec<String> + 'static,
+    {
+        self.suggestions = Some(Box::new(source));
+        self.update_matches();
+    }
+
+    /// List up to `rows` matches below the input, with the selected one
+    /// highlighted.
+    pub fn with_suggestion_list(mut self, rows: usize) -> Self {
+        self.set_suggestion_list(rows);
+        self
+    }
+
+    pub fn set_suggestion_list(&mut self, rows: usize) {
+        self.suggestion_rows = rows;
+    }
+
+    /// The suggestions for the current text, best first.
+    pub fn matches(&self) -> &[String] {
+        &self.matches
+    }
+
+    /// The suggestion Tab would accept.
+    pub fn suggestion(&self) -> Option<&str> {
+        self.matches.get(self.selected_match).map(String::as_str)
+    }
+
+    /// Replace the text with the selected suggestion. Returns whether there
+    /// was one.
+    pub fn accept_suggestion(&mut self) -> bool {
+        let Some(suggestion) = self.suggestion().map(String::from) else {
+            return false;
+        };
+        self.replace_text(&suggestion);
+        self.edited();
+        true
+    }
+
+    /// Select the next suggestion, wrapping around.
+    pub fn next_suggestion(&mut self) {
+        if !self.matches.is_empty() {
+            self.selected_match = (self.selected_match + 1) % self.matches.len();
+        }
+    }
+
+    /// Select the previous suggestion, wrapping around.
+    pub fn previous_suggestion(&mut self) {
+        if !self.matches.is_empty() {
+            let count = self.matches.len();
+            self.selected_match = (self.selected_match + count - 1) % count;
+        }
+    }
+
+    // Asks the source again for the current text. Nothing is suggested for
+    // empty text, and a match equal to the text has nothing left to add.
+    fn update_matches(&mut self) {
+        self.selected_match = 0;
+        self.matches.clear();
+        let Some(source) = &self.suggestions else {
+            return;
+        };
+        if self.buffer.is_empty() || self.is_placeholder() {
+            return;
+        }
+        let value = self.value();
+        self.matches = source(value)
+            .into_iter()
+            .filter(|candidate| candidate != value)
+            .collect();
+    }
+
+    // The rest of the selected suggestion after the text, shown dimmed
+    fn ghost_text(&self) -> Option<&str> {
+        if !self.focused || self.echo_mode != EchoMode::Normal || self.search.is_some() {
+            return None;
+        }
+        let (_, column) = self.buffer.cursor();
+        if column < self.value().graphemes(true).count() {
+            return None;
+        }
+        self.suggestion()?.strip_prefix(self.value())
+    }
+
+    pub fn value(&self) -> &str {
+        self.buffer.line(0)
+    }
+
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// The text being edited and the cursor within it.
+    pub fn buffer(&self) -> &TextBuffer {
+        &self.buffer
+    }
+
+    // Display columns before the value: padding, prefix and a space
+    fn value_start(&self) -> usize {
+        self.padding + unicode::width(&self.prefix) + 1
+    }
+
+    fn is_placeholder(&self) -> bool {
+        self.value() == self.placeholder.as_deref().unwrap_or("")
+    }
+
+    /// Ask for the value on a plain line of `input`, for when there is no
+    /// terminal. An empty line keeps the current value if it could be
+    /// submitted; otherwise the question is asked again, after the
+    /// validation message if there is one.
+    pub(crate) fn prompt_plain(
+        &mut self,
+        input: &mut impl BufRead,
+        output: &mut impl Write,
+    ) -> Result<PromptResult<String>> {
+        loop {
+            if !self.read_plain(input, output)? {
+                return Ok(PromptResult::Cancelled);
+            }
+            if self.can_submit() {
+                return Ok(PromptResult::Submitted(self.value().to_string()));
+            }
+            if let Some(error) = &self.error {
+                writeln!(output, "✗ {}", error)?;
+            }
+        }
+    }
+
+    /// Read one line of `input` as the new value, keeping the current one
+    /// for an empty line. Returns `false` once the input has ended.
+    pub(crate) fn read_plain(
+        &mut self,
+        input: &mut impl BufRead,
+        output: &mut impl Write,
+    ) -> Result<bool> {
+        let prompt = match self.buffer.is_empty() || self.is_placeholder() {
+            true => format!("{}: ", self.label),
+            false => format!("{} [{}]: ", self.label, self.echoed(self.value())),
+        };
+        let Some(line) = fallback::read_line(input, output, &prompt)? else {
+            return Ok(false);
+        };
+        if !line.is_empty() {
+            self.replace_text(&line);
+        }
+        Ok(true)
+    }
+
+    // A click on the value moves the cursor there
+    fn handle_mouse(&mut self, mouse: &MouseEvent) -> EventOutcome {
+        match mouse::left_click(mouse, self.state.area) {
+            // Nothing is shown to click on
+            Some(_) if self.echo_mode == EchoMode::None => EventOutcome::Ignored,
+            Some((column, 2)) => {
+                let shown = self.echoed(self.value());
+                let widths: Vec<usize> = shown.graphemes(true).map(unicode::width).collect();
+                let scroll = self.state.scroll.min(widths.len());
+                // Skip the `…` marking text scrolled off to the left
+                let mut width = (column as usize)
+                    .saturating_sub(self.value_start())
+                    .saturating_sub((scroll > 0) as usize);
+                let mut column = scroll;
+                for grapheme_width in &widths[scroll..] {
+                    if width < *grapheme_width {
+                        break;
+                    }
+                    width -= grapheme_width;
+                    column += 1;
+                }
+                self.buffer.set_cursor(0, column);
+                EventOutcome::Consumed
+            }
+            _ => EventOutcome::Ignored,
         }
     }
 
+    pub fn state(&self) -> &TextInputState {
+        &self.state
+    }
+
     pub fn insert_char(&mut self, c: char) {
-        if self.text == self.placeholder.as_ref().map_or("", String::as_str) || self.text.is_empty()
-        {
-            self.text.clear(); // Clear the initial or placeholder text
-            self.cursor_position = 0; // Reset the cursor position
+        if self.is_placeholder() {
+            self.buffer.clear(); // Clear the initial or placeholder text
+        }
+        // The input holds a single line, of at most `char_limit` characters
+        let length = self.value().graphemes(true).count();
+        if c != '\n' && self.char_limit.is_none_or(|limit| length < limit) {
+            self.buffer.insert_char(c);
+            self.edited();
         }
-        self.text.insert(self.cursor_position, c);
-        self.cursor_position += 1;
+    }
+
+    /// Insert pasted text at the cursor in one edit, handling line breaks
+    /// as set by [`with_paste_newlines`](Self::with_paste_newlines) and
+    /// cutting what goes past the character limit. Returns whether any text
+    /// was inserted.
+    pub fn paste(&mut self, text: &str) -> bool {
+        let has_newlines = text.contains(['\r', '\n']);
+        if has_newlines && self.paste_newlines == PasteNewlines::Reject {
+            return false;
+        }
+        if self.is_placeholder() {
+            self.buffer.clear();
+        }
+        let text = match has_newlines {
+            true => Cow::Owned(text.replace(['\r', '\n'], "")),
+            false => Cow::Borrowed(text),
+        };
+        let text = self.fit_to_limit(&text, self.value().graphemes(true).count());
+        if text.is_empty() {
+            return false;
+        }
+        self.buffer.insert_str(text);
+        self.edited();
+        true
     }
 
     pub fn delete_char(&mut self) {
-        if self.cursor_position > 0 {
-            self.text.remove(self.cursor_position - 1);
-            self.cursor_position -= 1;
+        if self.buffer.delete_backward() {
+            self.edited();
         }
     }
 
     pub fn move_cursor_left(&mut self) {
-        if self.cursor_position > 0 {
-            self.cursor_position -= 1;
-        }
+        self.buffer.move_left();
     }
 
     pub fn move_cursor_right(&mut self) {
-        if self.cursor_position < self.text.len() {
-            self.cursor_position += 1;
-        }
-    }
-
-    pub fn render(&self, x: u16, y: u16) {
-        // Move to the position and clear the line for the label
-        execute!(
-            std::io::stdout(),
-            MoveTo(x + self.padding as u16, y),
-            Clear(ClearType::CurrentLine),
-            Print(&self.label),
-        )
-        .unwrap();
-
-        execute!(
-            std::io::stdout(),
-            MoveTo(x, y + 2),
-            Clear(ClearType::CurrentLine)
-        )
-        .unwrap();
-        execute!(
-            std::io::stdout(),
-            MoveTo(x, y + 2),
-            Clear(ClearType::CurrentLine),
-            SetForegroundColor(Color::White),
-            Print(" ".repeat(self.padding)), // Left padding
-            Print(format!(
-                "{} ",
-                if self.prefix.is_empty() {
-                    ""
-                } else {
-                    self.prefix.as_str()
-                }
-            )), // Render the prefix
-            SetForegroundColor(Color::Grey),
-            Print(if self.text.is_empty() {
-                self.placeholder.as_deref().unwrap_or("")
-            } else {
-                &self.text
-            })
-        )
-        .unwrap();
-
-        if let Some(ref helper) = self.helper {
-            helper.render(x + self.padding as u16, y + 5);
-            // Render helper text below the input field
-        }
-
-        // Reset cursor position and color
-        execute!(
-            std::io::stdout(),
-            MoveTo(
-                x + self.padding as u16 + self.prefix.len() as u16 + self.cursor_position as u16,
-                y + 2
-            ),
-            SetForegroundColor(Color::Reset)
-        )
-        .unwrap();
-        // Reset cursor position to after the text input
-        execute!(
-            std::io::stdout(),
-            MoveTo(
-                x + self.padding as u16
-                    + self.prefix.len() as u16
-                    + self.cursor_position as u16
-                    + 1,
-                y + 2
-            )
-        )
-        .unwrap();
-        // Reset the color to default
-        execute!(std::io::stdout(), SetForegroundColor(Color::Reset)).unwrap();
-    }
-}
-
-pub fn handle_input(input: &mut TextInput, x: u16, y: u16) -> Option<String> {
-    input.render(x, y);
-    loop {
-        match read().unwrap() {
-            Event::Key(KeyEvent {
-                code: KeyCode::Char(c),
-                modifiers,
-                ..
-            }) => {
-                if modifiers.contains(KeyModifiers::CONTROL) && c == 'c' {
-                    return None;
+        self.buffer.move_right();
+    }
+
+    pub fn move_cursor_line_start(&mut self) {
+        self.buffer.move_line_start();
+    }
+
+    pub fn move_cursor_line_end(&mut self) {
+        self.buffer.move_line_end();
+    }
+
+    /// Delete the character under the cursor.
+    pub fn delete_char_forward(&mut self) {
+        if self.buffer.delete_forward() {
+            self.edited();
+        }
+    }
+
+    /// Number of rows the input needs, including the helper text or
+    /// validation message.
+    pub fn height(&self) -> u16 {
+        let rows = 3 + self.suggestion_rows as u16;
+        if self.helper.is_some() || !self.validators.is_empty() {
+            rows + 3
+        } else {
+            rows
+        }
+    }
+
+    pub fn render(&mut self, x: u16, y: u16) -> Result<()> {
+        let area = tui::area_at(x, y, self.height())?;
+        let mut state = self.state.clone();
+        tui::draw_stateful_widget(&*self, area, &mut state)?;
+        self.state = state;
+
+        if let Some((cursor_x, cursor_y)) = self.state.cursor {
+            execute!(std::io::stdout(), MoveTo(cursor_x, cursor_y))?;
+        }
+        Ok(())
+    }
+
+    /// Draw the component into `area`, for use inside a [`Program`](crate::program::Program).
+    pub fn view(&mut self, frame: &mut Frame, area: Rect) {
+        let mut state = self.state.clone();
+        frame.render_stateful_widget(&*self, area, &mut state);
+        self.state = state;
+
+        if let Some((cursor_x, cursor_y)) = self.state.cursor {
+            frame.set_cursor(cursor_x, cursor_y);
+        }
+    }
+}
+
+impl StatefulWidget for &TextInput {
+    type State = TextInputState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut TextInputState) {
+        let padding = " ".repeat(self.padding);
+        // Columns left for the value, scrolled to keep the cursor in view
+        let room = (area.width as usize).saturating_sub(self.value_start());
+        let room = self.width.map_or(room, |width| width.min(room));
+        let (value, cursor_width) = if self.buffer.is_empty() {
+            let placeholder = self.placeholder.as_deref().unwrap_or("");
+            state.scroll = 0;
+            (unicode::truncate(placeholder, room), 0)
+        } else {
+            let shown = self.echoed(self.value());
+            let graphemes: Vec<&str> = shown.graphemes(true).collect();
+            let widths: Vec<usize> = graphemes.iter().map(|g| unicode::width(g)).collect();
+            let cursor = self.buffer.cursor().1.min(graphemes.len());
+            state.scroll = scroll_to_cursor(&widths, cursor, state.scroll, room);
+            let cursor_width =
+                (state.scroll > 0) as usize + widths[state.scroll..cursor].iter().sum::<usize>();
+            let visible = visible_text(&graphemes, &widths, state.scroll, room);
+            (Cow::Owned(visible), cursor_width)
+        };
+        // Ghost text only goes after the end of the text, in what room is left
+        let ghost = self
+            .ghost_text()
+            .map(|ghost| unicode::truncate(ghost, room.saturating_sub(unicode::width(&value))))
+            .unwrap_or_default();
+        // A blurred input is dimmed and leaves the cursor to the focused one
+        let (prefix_color, value_color) = if self.focused {
+            (self.theme.text, self.theme.secondary)
+        } else {
+            (self.theme.muted, self.theme.muted)
+        };
+        // An open history search shows its query and the entry it found
+        let (search_prompt, value, query_end) = match &self.search {
+            Some(search) => {
+                let label = match search.found {
+                    None if !search.query.is_empty() => FAILED_SEARCH_LABEL,
+                    _ => SEARCH_LABEL,
+                };
+                let found = search
+                    .found
+                    .and_then(|found| self.history.as_ref()?.entries().get(found))
+                    .map_or("", String::as_str);
+                let prompt = format!("{}{}': ", label, search.query);
+                let query_end = unicode::width(label) + unicode::width(&search.query);
+                (prompt, self.echoed(found), Some(query_end))
+            }
+            None => (String::new(), value, None),
+        };
+        let mut lines = vec![
+            Line::from(format!("{}{}", padding, self.label)),
+            Line::default(),
+            Line::from(vec![
+                Span::styled(
+                    format!("{}{} ", padding, self.prefix),
+                    Style::default().fg(prefix_color.resolve()),
+                ),
+                Span::styled(
+                    search_prompt,
+                    Style::default().fg(self.theme.muted.resolve()),
+                ),
+                Span::styled(value, Style::default().fg(value_color.resolve())),
+                Span::styled(ghost, Style::default().fg(self.theme.muted.resolve())),
+            ]),
+        ];
+        // Keep the selected match within the rows shown
+        let first = (self.selected_match + 1).saturating_sub(self.suggestion_rows);
+        for row in 0..self.suggestion_rows {
+            let line = match self.matches.get(first + row) {
+                Some(candidate) if self.focused => {
+                    let style = match first + row == self.selected_match {
+                        true => Style::default().fg(self.theme.primary.resolve()),
+                        false => Style::default().fg(self.theme.muted.resolve()),
+                    };
+                    let indent = padding.len() + unicode::width(&self.prefix) + 1;
+                    Line::styled(format!("{}{}", " ".repeat(indent), candidate), style)
                 }
+                _ => Line::default(),
+            };
+            lines.push(line);
+        }
+        // A failed validation takes the helper's place until the text passes
+        let message = match (&self.error, &self.helper) {
+            (Some(error), _) => Some(Span::styled(
+                format!("✗ {}", error),
+                Style::default().fg(self.theme.error.resolve()),
+            )),
+            (None, Some(helper)) => Some(helper.span()),
+            (None, None) => None,
+        };
+        if let Some(message) = message {
+            lines.extend([Line::default(), Line::default()]);
+            lines.push(Line::from(vec![Span::raw(padding), message]));
+        }
+        Paragraph::new(lines).render(area, buf);
+
+        // While searching, the cursor follows the query
+        let cursor_width = query_end.unwrap_or(cursor_width);
+        let cursor_x = area.x + (self.value_start() + cursor_width) as u16;
+        state.cursor = (self.focused && cursor_x < area.right() && area.height > 2)
+            .then_some((cursor_x, area.y + 2));
+        state.area = area;
+    }
+}
+
+impl Widget for &TextInput {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let mut state = self.state.clone();
+        StatefulWidget::render(self, area, buf, &mut state);
+    }
+}
 
-                input.insert_char(c);
-                input.render(x, y);
-            }
-            Event::Key(KeyEvent {
-                code: KeyCode::Enter,
-                ..
-            }) => {
-                // Check if text is not just the placeholder
-                if !input.text.is_empty()
-                    && input.text != input.placeholder.as_ref().map_or("", String::as_str)
-                {
-                    return Some(input.text.clone());
+impl HandleEvent for TextInput {
+    fn handle_event(&mut self, event: &Event) -> EventOutcome {
+        let key = match event {
+            Event::Key(key) => key,
+            Event::Mouse(mouse) => return self.handle_mouse(mouse),
+            Event::Paste(text) if self.search.is_some() => {
+                self.extend_search(&text.replace(['\r', '\n'], ""));
+                return EventOutcome::Consumed;
+            }
+            Event::Paste(text) => {
+                return match self.paste(text) {
+                    true => EventOutcome::Consumed,
+                    false => EventOutcome::Ignored,
+                };
+            }
+            // Redraw at the new width
+            Event::Resize(..) => return EventOutcome::Consumed,
+            _ => return EventOutcome::Ignored,
+        };
+        if self.search.is_some() {
+            if let Some(outcome) = self.handle_search_key(key) {
+                return outcome;
+            }
+        }
+        match self.keymap.action(key) {
+            Some(Action::Interrupt) => return EventOutcome::Interrupted,
+            Some(Action::Cancel) => return EventOutcome::Cancelled,
+            // Check if text is not just the placeholder and passes the validators
+            Some(Action::Submit) => {
+                if self.can_submit() {
+                    return EventOutcome::Submitted;
                 }
+                return match self.error.is_some() {
+                    true => EventOutcome::Consumed,
+                    false => EventOutcome::Ignored,
+                };
             }
-            Event::Key(KeyEvent {
-                code: KeyCode::Backspace,
-                ..
-            }) => {
-                input.delete_char();
-                input.render(x, y);
+            Some(Action::Backspace) => self.delete_char(),
+            Some(Action::Delete) => self.delete_char_forward(),
+            Some(Action::Left) => self.move_cursor_left(),
+            // At the end of the text, Right takes the ghost text like Tab
+            Some(Action::Right) => {
+                if self.ghost_text().is_none() || !self.accept_suggestion() {
+                    self.move_cursor_right();
+                }
+            }
+            Some(Action::AcceptSuggestion) => {
+                if !self.accept_suggestion() {
+                    return EventOutcome::Ignored;
+                }
             }
-            Event::Key(KeyEvent {
-                code: KeyCode::Left,
-                ..
-            }) => {
-                input.move_cursor_left();
-                input.render(x, y);
+            Some(Action::NextSuggestion) if !self.matches.is_empty() => self.next_suggestion(),
+            Some(Action::PreviousSuggestion) if !self.matches.is_empty() => {
+                self.previous_suggestion()
             }
-            Event::Key(KeyEvent {
-                code: KeyCode::Right,
-                ..
-            }) => {
-                input.move_cursor_right();
-                input.render(x, y);
+            Some(Action::LineStart) => self.move_cursor_line_start(),
+            Some(Action::LineEnd) => self.move_cursor_line_end(),
+            Some(Action::Up) if self.history_previous() => {}
+            Some(Action::Down) if self.history_next() => {}
+            Some(Action::SearchHistory) if self.start_history_search() => {}
+            _ => match key.code {
+                KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.insert_char(c)
+                }
+                _ => return EventOutcome::Ignored,
+            },
+        }
+        EventOutcome::Consumed
+    }
+}
+
+impl Focusable for TextInput {
+    fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        TextInput::view(self, frame, area);
+    }
+}
+
+impl InlinePrompt for TextInput {
+    type Value = String;
+
+    fn inline_height(&self) -> u16 {
+        self.height()
+    }
+
+    fn submitted_value(&self) -> Option<String> {
+        Some(self.value().to_string())
+    }
+
+    fn fallback_value(
+        &mut self,
+        input: &mut impl BufRead,
+        output: &mut impl Write,
+    ) -> Result<PromptResult<String>> {
+        self.prompt_plain(input, output)
+    }
+
+    fn summary(&self, value: &String) -> String {
+        self.echoed(value).into_owned()
+    }
+
+    fn theme(&self) -> Theme {
+        self.theme
+    }
+}
+
+impl<M> Model<M> for TextInput {
+    fn update(&mut self, msg: Msg<M>) -> Cmd<M> {
+        if let Some(event) = msg.to_event() {
+            self.handle_event(&event);
+        }
+        Cmd::none()
+    }
+
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        TextInput::view(self, frame, area);
+    }
+}
+
+// The first character to show of text whose characters take `widths`
+// columns, so the cursor before character `cursor` fits in `room` columns.
+// It moves as little as possible from `scroll`, where the last render began,
+// and takes back columns left empty at the end, such as after a deletion.
+// Text hidden on either side costs a column for its `…`.
+fn scroll_to_cursor(widths: &[usize], cursor: usize, scroll: usize, room: usize) -> usize {
+    let left = |start: usize| (start > 0) as usize;
+    // Everything from `start` fits, with a column for the cursor after the end
+    let end = (cursor == widths.len()) as usize;
+    let fits_to_end =
+        |start: usize| left(start) + widths[start..].iter().sum::<usize>() + end <= room;
+    let shows_cursor = |start: usize| {
+        let before = left(start) + widths[start..cursor].iter().sum::<usize>();
+        let under = widths.get(cursor).copied().unwrap_or(1);
+        let after: usize = widths.get(cursor + 1..).map_or(0, |rest| rest.iter().sum());
+        let right = (before + under + after > room) as usize;
+        before + under + right <= room
+    };
+    let mut scroll = scroll.min(cursor);
+    while scroll < cursor && !shows_cursor(scroll) {
+        scroll += 1;
+    }
+    while scroll > 0 && fits_to_end(scroll - 1) {
+        scroll -= 1;
+    }
+    scroll
+}
+
+// The characters from `scroll` that fit in `room` columns, with `…` in place
+// of those hidden on either side
+fn visible_text(graphemes: &[&str], widths: &[usize], scroll: usize, room: usize) -> String {
+    if room == 0 {
+        return String::new();
+    }
+    let mut text = String::new();
+    if scroll > 0 {
+        text.push('…');
+    }
+    let mut used = (scroll > 0) as usize;
+    let rest: usize = widths[scroll..].iter().sum();
+    let limit = match used + rest <= room {
+        true => room,
+        false => room - 1,
+    };
+    for (grapheme, width) in graphemes[scroll..].iter().zip(&widths[scroll..]) {
+        if used + width > limit {
+            text.push('…');
+            break;
+        }
+        text.push_str(grapheme);
+        used += width;
+    }
+    text
+}
+
+pub fn handle_input(input: &mut TextInput, x: u16, y: u16) -> Result<PromptResult<String>> {
+    if !fallback::is_interactive() {
+        let result = input.prompt_plain(&mut io::stdin().lock(), &mut io::stdout())?;
+        if result.is_submitted() {
+            input.save_to_history()?;
+        }
+        return Ok(result);
+    }
+    // Mouse capture lets a click place the cursor, and bracketed paste
+    // delivers pasted text as one event
+    let options = tui::SessionOptions::new()
+        .mouse_capture(true)
+        .bracketed_paste(true);
+    let _session = tui::TerminalSession::with_options(options)?;
+    input.render(x, y)?;
+    loop {
+        let event = read()?;
+        if let Event::Resize(..) = event {
+            tui::clear_below(y)?;
+        }
+        match input.handle_event(&event) {
+            EventOutcome::Submitted => {
+                input.save_to_history()?;
+                return Ok(PromptResult::Submitted(input.value().to_string()));
             }
-            Event::Key(KeyEvent {
-                code: KeyCode::Esc, ..
-            }) => return None,
-            _ => {}
+            EventOutcome::Cancelled => return Ok(PromptResult::Cancelled),
+            EventOutcome::Interrupted => return Ok(PromptResult::Interrupted),
+            // Keys typed or pasted faster than we draw are applied before the next render
+            EventOutcome::Consumed if !poll(Duration::ZERO)? => input.render(x, y)?,
+            EventOutcome::Consumed => {}
+            EventOutcome::Ignored => {}
         }
     }
 }
@@ -197,21 +1169,23 @@ pub fn handle_input(input: &mut TextInput, x: u16, y: u16) -> Option<String> {
 #[cfg(test)]
 mod tests {
     use super::*; // Import everything from the outer module
+    use crate::validate;
+    use crossterm::event::KeyEvent;
 
     #[test]
     fn test_insert_char() {
         let mut text_input = TextInput::new(None, 0, "", "Label", None, "");
         text_input.insert_char('a');
-        assert_eq!(text_input.text, "a");
-        assert_eq!(text_input.cursor_position, 1);
+        assert_eq!(text_input.value(), "a");
+        assert_eq!(text_input.buffer.cursor().1, 1);
     }
 
     #[test]
     fn test_delete_char() {
         let mut text_input = TextInput::new(None, 0, "a", "Label", None, "");
         text_input.delete_char();
-        assert_eq!(text_input.text, "");
-        assert_eq!(text_input.cursor_position, 0);
+        assert_eq!(text_input.value(), "");
+        assert_eq!(text_input.buffer.cursor().1, 0);
     }
 
     #[test]
@@ -219,7 +1193,54 @@ mod tests {
         let mut text_input = TextInput::new(None, 0, "ab", "Label", None, "");
         text_input.move_cursor_right(); // Move cursor to end
         text_input.move_cursor_left();
-        assert_eq!(text_input.cursor_position, 1);
+        assert_eq!(text_input.buffer.cursor().1, 1);
+    }
+
+    #[test]
+    fn test_update_edits_text() {
+        let mut text_input = TextInput::new(None, 0, "", "Label", None, "");
+        let key = |code| Msg::<()>::Key(KeyEvent::new(code, KeyModifiers::NONE));
+        text_input.update(key(KeyCode::Char('a')));
+        text_input.update(key(KeyCode::Char('b')));
+        text_input.update(key(KeyCode::Left));
+        text_input.update(key(KeyCode::Backspace));
+        assert_eq!(text_input.value(), "b");
+        assert_eq!(text_input.buffer.cursor().1, 0);
+    }
+
+    #[test]
+    fn test_handle_event_outcomes() {
+        let mut text_input = TextInput::new(Some("name"), 0, "", "Label", None, "");
+        let key = |code| Event::Key(KeyEvent::new(code, KeyModifiers::NONE));
+        assert_eq!(
+            text_input.handle_event(&key(KeyCode::Enter)),
+            EventOutcome::Ignored
+        );
+        assert_eq!(
+            text_input.handle_event(&key(KeyCode::Char('a'))),
+            EventOutcome::Consumed
+        );
+        assert_eq!(
+            text_input.handle_event(&key(KeyCode::Enter)),
+            EventOutcome::Submitted
+        );
+        assert_eq!(
+            text_input.handle_event(&key(KeyCode::Tab)),
+            EventOutcome::Ignored
+        );
+        assert_eq!(
+            text_input.handle_event(&key(KeyCode::Esc)),
+            EventOutcome::Cancelled
+        );
+        assert_eq!(text_input.value(), "a");
+    }
+
+    #[test]
+    fn test_ctrl_c_interrupts() {
+        let mut text_input = TextInput::new(None, 0, "", "Label", None, "");
+        let ctrl_c = Event::Key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL));
+        assert_eq!(text_input.handle_event(&ctrl_c), EventOutcome::Interrupted);
+        assert_eq!(text_input.value(), "");
     }
 
     #[test]
@@ -229,6 +1250,306 @@ mod tests {
         text_input.move_cursor_right();
         text_input.move_cursor_left();
         text_input.move_cursor_right(); // Should be at the end now
-        assert_eq!(text_input.cursor_position, 3);
+        assert_eq!(text_input.buffer.cursor().1, 3);
+    }
+
+    #[test]
+    fn test_render_into_buffer() {
+        let text_input = TextInput::new(None, 1, "hi", "Name", None, ">");
+        let area = Rect::new(0, 0, 8, 3);
+        let mut buf = Buffer::empty(area);
+        let mut state = text_input.state().clone();
+        StatefulWidget::render(&text_input, area, &mut buf, &mut state);
+
+        let symbols: String = buf.content.iter().map(|cell| cell.symbol()).collect();
+        assert_eq!(symbols, " Name            > hi   ");
+        assert_eq!(state.cursor(), Some((5, 2)));
+    }
+
+    #[test]
+    fn test_click_places_cursor() {
+        let mut text_input = TextInput::new(None, 1, "hello", "Name", None, ">");
+        text_input.state.area = Rect::new(10, 5, 20, 3);
+        let click = |column, row| {
+            Event::Mouse(crossterm::event::MouseEvent {
+                kind: crossterm::event::MouseEventKind::Down(crossterm::event::MouseButton::Left),
+                column,
+                row,
+                modifiers: KeyModifiers::NONE,
+            })
+        };
+        // Padding, prefix and a space come before the text
+        assert_eq!(
+            text_input.handle_event(&click(15, 7)),
+            EventOutcome::Consumed
+        );
+        assert_eq!(text_input.buffer.cursor().1, 2);
+        text_input.handle_event(&click(29, 7));
+        assert_eq!(text_input.buffer.cursor().1, 5);
+        assert_eq!(
+            text_input.handle_event(&click(15, 5)),
+            EventOutcome::Ignored
+        );
+    }
+
+    #[test]
+    fn test_paste_newlines() {
+        let mut text_input = TextInput::new(Some("name"), 0, "", "Label", None, "");
+        let paste = |text: &str| Event::Paste(text.to_string());
+        assert_eq!(
+            text_input.handle_event(&paste("foo\r\nbar\n")),
+            EventOutcome::Consumed
+        );
+        assert_eq!(text_input.value(), "foobar");
+        text_input.move_cursor_left();
+        text_input.paste("日本");
+        assert_eq!(text_input.value(), "fooba日本r");
+
+        let mut text_input = text_input.with_paste_newlines(PasteNewlines::Reject);
+        assert_eq!(
+            text_input.handle_event(&paste("x\ny")),
+            EventOutcome::Ignored
+        );
+        assert_eq!(text_input.value(), "fooba日本r");
+
+        // Text set in other ways is kept to one line too
+        let mut text_input = TextInput::new(None, 0, "two\nlines", "Label", None, "")
+            .with_suggestion_source(|_| vec!["one\r\nmore".to_string()]);
+        assert_eq!(text_input.value(), "twolines");
+        text_input.insert_char('!');
+        assert!(text_input.accept_suggestion());
+        assert_eq!(text_input.value(), "onemore");
+        assert_eq!(text_input.buffer().cursor(), (0, 7));
+    }
+
+    #[test]
+    fn test_prompt_plain() {
+        let mut text_input = TextInput::new(Some("name"), 0, "", "Name", None, "");
+        let mut output = Vec::new();
+        let mut input = "\nAda\n".as_bytes();
+        let result = text_input.prompt_plain(&mut input, &mut output).unwrap();
+        assert_eq!(result, PromptResult::Submitted("Ada".to_string()));
+        assert_eq!(String::from_utf8(output).unwrap(), "Name: Name: ");
+
+        // An empty line keeps the value, and the end of input cancels
+        let mut output = Vec::new();
+        let mut input = "\n".as_bytes();
+        let result = text_input.prompt_plain(&mut input, &mut output).unwrap();
+        assert_eq!(result, PromptResult::Submitted("Ada".to_string()));
+        let result = text_input.prompt_plain(&mut input, &mut output).unwrap();
+        assert_eq!(result, PromptResult::Cancelled);
+    }
+
+    #[test]
+    fn test_validators_block_submit() {
+        let mut text_input = TextInput::new(None, 0, "", "Port", Some("1-65535"), ">")
+            .with_validator(validate::required())
+            .with_validator(validate::number_between(1.0, 65535.0));
+        let key = |code| Event::Key(KeyEvent::new(code, KeyModifiers::NONE));
+        text_input.handle_event(&key(KeyCode::Char('0')));
+        assert_eq!(text_input.error(), None);
+        assert_eq!(
+            text_input.handle_event(&key(KeyCode::Enter)),
+            EventOutcome::Consumed
+        );
+        assert_eq!(text_input.error(), Some("Must be between 1 and 65535"));
+
+        // The message takes the helper's row
+        let area = Rect::new(0, 0, 30, 6);
+        let mut buf = Buffer::empty(area);
+        let mut state = text_input.state().clone();
+        StatefulWidget::render(&text_input, area, &mut buf, &mut state);
+        let row: String = (0..30).map(|x| buf.get(x, 5).symbol()).collect();
+        assert_eq!(row.trim_end(), "✗ Must be between 1 and 65535");
+
+        // Validating on change clears it as soon as the text passes
+        text_input.set_validate_on(ValidateOn::Change);
+        text_input.handle_event(&key(KeyCode::Backspace));
+        assert_eq!(text_input.error(), Some("This field is required"));
+        text_input.handle_event(&key(KeyCode::Char('8')));
+        assert_eq!(text_input.error(), None);
+        assert_eq!(
+            text_input.handle_event(&key(KeyCode::Enter)),
+            EventOutcome::Submitted
+        );
+    }
+
+    #[test]
+    fn test_echo_modes() {
+        let render = |text_input: &TextInput| {
+            let area = Rect::new(0, 0, 12, 3);
+            let mut buf = Buffer::empty(area);
+            let mut state = text_input.state().clone();
+            StatefulWidget::render(text_input, area, &mut buf, &mut state);
+            let row: String = (0..12).map(|x| buf.get(x, 2).symbol()).collect();
+            (row, state.cursor())
+        };
+        let mut text_input = TextInput::new(Some("token"), 0, "", "Token", None, ">")
+            .with_echo_mode(EchoMode::Password('•'));
+        assert_eq!(render(&text_input).0, "> token     ");
+
+        text_input.paste("s3cré");
+        text_input.move_cursor_left();
+        assert_eq!(
+            render(&text_input),
+            ("> •••••     ".to_string(), Some((6, 2)))
+        );
+        assert_eq!(text_input.summary(&text_input.value().to_string()), "•••••");
+
+        text_input.set_echo_mode(EchoMode::None);
+        assert_eq!(
+            render(&text_input),
+            (">           ".to_string(), Some((2, 2)))
+        );
+        assert_eq!(text_input.value(), "s3cré");
+    }
+
+    #[test]
+    fn test_suggestions() {
+        let mut text_input = TextInput::new(None, 0, "", "Branch", None, ">")
+            .with_suggestions(["main", "feature/login", "feature/logout", "fix/typo"])
+            .with_suggestion_list(2);
+        let key = |code, modifiers| Event::Key(KeyEvent::new(code, modifiers));
+        let ctrl = |c| key(KeyCode::Char(c), KeyModifiers::CONTROL);
+        assert_eq!(text_input.suggestion(), None);
+        assert_eq!(
+            text_input.handle_event(&key(KeyCode::Tab, KeyModifiers::NONE)),
+            EventOutcome::Ignored
+        );
+
+        text_input.paste("fe");
+        assert_eq!(text_input.matches(), ["feature/login", "feature/logout"]);
+        text_input.handle_event(&ctrl('n'));
+        assert_eq!(text_input.suggestion(), Some("feature/logout"));
+
+        let area = Rect::new(0, 0, 20, 5);
+        let mut buf = Buffer::empty(area);
+        let mut state = text_input.state().clone();
+        StatefulWidget::render(&text_input, area, &mut buf, &mut state);
+        let row = |y| (0..20).map(|x| buf.get(x, y).symbol()).collect::<String>();
+        assert_eq!(row(2), "> feature/logout    ");
+        assert_eq!(buf.get(4, 2).fg, Theme::default().muted.resolve());
+        assert_eq!(row(3), "  feature/login     ");
+        assert_eq!(row(4), "  feature/logout    ");
+        assert_eq!(state.cursor(), Some((4, 2)));
+
+        // Right only accepts at the end of the text
+        text_input.handle_event(&ctrl('p'));
+        text_input.move_cursor_left();
+        text_input.handle_event(&key(KeyCode::Right, KeyModifiers::NONE));
+        assert_eq!(text_input.value(), "fe");
+        text_input.handle_event(&key(KeyCode::Right, KeyModifiers::NONE));
+        assert_eq!(text_input.value(), "feature/login");
+        assert!(text_input.matches().is_empty());
+    }
+
+    #[test]
+    fn test_history() {
+        let mut history = History::new();
+        for entry in ["git status", "cargo test", "git push"] {
+            history.push(entry).unwrap();
+        }
+        let mut text_input = TextInput::new(None, 0, "", "Command", None, ">")
+            .with_history(history)
+            .with_history_prefix_search(true);
+        let key = |code, modifiers| Event::Key(KeyEvent::new(code, modifiers));
+        let up = key(KeyCode::Up, KeyModifiers::NONE);
+        let down = key(KeyCode::Down, KeyModifiers::NONE);
+
+        text_input.paste("git");
+        text_input.handle_event(&up);
+        assert_eq!(text_input.value(), "git push");
+        text_input.handle_event(&up);
+        assert_eq!(text_input.value(), "git status");
+        assert_eq!(text_input.handle_event(&up), EventOutcome::Ignored);
+        text_input.handle_event(&down);
+        text_input.handle_event(&down);
+        assert_eq!(text_input.value(), "git");
+
+        // Ctrl+R searches anywhere in the entries, newest first
+        text_input.handle_event(&key(KeyCode::Char('r'), KeyModifiers::CONTROL));
+        text_input.handle_event(&Event::Paste("s".to_string()));
+        text_input.handle_event(&key(KeyCode::Char('t'), KeyModifiers::NONE));
+        let area = Rect::new(0, 0, 40, 3);
+        let mut buf = Buffer::empty(area);
+        let mut state = text_input.state().clone();
+        StatefulWidget::render(&text_input, area, &mut buf, &mut state);
+        let row: String = (0..40).map(|x| buf.get(x, 2).symbol()).collect();
+        assert_eq!(row.trim_end(), "> (reverse-i-search)`st': cargo test");
+        assert_eq!(state.cursor(), Some((23, 2)));
+
+        text_input.handle_event(&key(KeyCode::Char('r'), KeyModifiers::CONTROL));
+        text_input.handle_event(&key(KeyCode::Enter, KeyModifiers::NONE));
+        assert!(!text_input.is_searching_history());
+        assert_eq!(text_input.value(), "git status");
+
+        text_input.save_to_history().unwrap();
+        let entries = text_input.history().unwrap().entries();
+        assert_eq!(entries, ["cargo test", "git push", "git status"]);
+    }
+
+    #[test]
+    fn test_horizontal_scrolling() {
+        let mut text_input =
+            TextInput::new(None, 0, "0123456789abcdef", "Key", None, ">").with_width(8);
+        let render = |text_input: &mut TextInput| {
+            let area = Rect::new(0, 0, 20, 3);
+            let mut buf = Buffer::empty(area);
+            let mut state = text_input.state().clone();
+            StatefulWidget::render(&*text_input, area, &mut buf, &mut state);
+            text_input.state = state;
+            let row: String = (0..20).map(|x| buf.get(x, 2).symbol()).collect();
+            row.trim_end().to_string()
+        };
+        // The end is in view after the text is set, with the start hidden
+        assert_eq!(render(&mut text_input), "> …abcdef");
+        assert_eq!(text_input.state().scroll_offset(), 10);
+        assert_eq!(text_input.state().cursor(), Some((9, 2)));
+
+        text_input.move_cursor_line_start();
+        assert_eq!(render(&mut text_input), "> 0123456…");
+        assert_eq!(text_input.state().cursor(), Some((2, 2)));
+
+        // Clicks account for the scrolled text and the `…`
+        let click = |column| {
+            Event::Mouse(crossterm::event::MouseEvent {
+                kind: crossterm::event::MouseEventKind::Down(crossterm::event::MouseButton::Left),
+                column,
+                row: 2,
+                modifiers: KeyModifiers::NONE,
+            })
+        };
+        text_input.handle_event(&click(5));
+        assert_eq!(text_input.buffer.cursor().1, 3);
+        text_input.move_cursor_line_end();
+        render(&mut text_input);
+        text_input.handle_event(&click(4));
+        assert_eq!(text_input.buffer.cursor().1, 11);
+
+        // Deleting brings hidden text back into view
+        for _ in 0..4 {
+            text_input.delete_char_forward();
+            text_input.delete_char();
+        }
+        assert_eq!(render(&mut text_input), "> 0123456f");
+        assert_eq!(text_input.state().scroll_offset(), 0);
+    }
+
+    #[test]
+    fn test_char_limit() {
+        let mut text_input =
+            TextInput::new(None, 0, "a much too long subject", "Subject", None, "")
+                .with_char_limit(10);
+        assert_eq!(text_input.value(), "a much too");
+        text_input.insert_char('!');
+        assert_eq!(text_input.value(), "a much too");
+
+        text_input.move_cursor_line_start();
+        for _ in 0..4 {
+            text_input.delete_char_forward();
+        }
+        assert!(text_input.paste("日本語 ok?"));
+        assert_eq!(text_input.value(), "日本語 ch too");
+        assert!(!text_input.paste("more"));
     }
 }
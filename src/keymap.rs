@@ -0,0 +1,1002 @@
+use std::{
+    collections::BTreeMap,
+    fmt, fs,
+    path::Path,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+
+use crate::{command::CommandInfo, Error, Result};
+
+/// A key press together with its modifiers, e.g. `ctrl+c` or `j`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        // Shift is already part of an uppercase character and of BackTab
+        let modifiers = match code {
+            KeyCode::Char(_) | KeyCode::BackTab => modifiers - KeyModifiers::SHIFT,
+            _ => modifiers,
+        };
+        Self { code, modifiers }
+    }
+
+    /// Whether the chord types a character rather than triggering a command,
+    /// i.e. a character without ctrl or alt.
+    pub fn is_text(&self) -> bool {
+        matches!(self.code, KeyCode::Char(_))
+            && !self
+                .modifiers
+                .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+    }
+
+    /// The key as shown in the help bar, e.g. `↑` or `ctrl+c`.
+    pub fn symbol(&self) -> String {
+        let key = match self.code {
+            KeyCode::Up => "\u{2191}".to_string(),
+            KeyCode::Down => "\u{2193}".to_string(),
+            KeyCode::Left => "\u{2190}".to_string(),
+            KeyCode::Right => "\u{2192}".to_string(),
+            KeyCode::Enter => "\u{2B90} ".to_string(),
+            _ => return self.to_string(),
+        };
+        format!("{}{}", modifier_prefix(self.modifiers), key)
+    }
+}
+
+impl From<KeyEvent> for KeyChord {
+    fn from(key: KeyEvent) -> Self {
+        KeyChord::new(key.code, key.modifiers)
+    }
+}
+
+impl From<KeyCode> for KeyChord {
+    fn from(code: KeyCode) -> Self {
+        KeyChord::new(code, KeyModifiers::NONE)
+    }
+}
+
+fn modifier_prefix(modifiers: KeyModifiers) -> String {
+    let mut prefix = String::new();
+    for (modifier, name) in [
+        (KeyModifiers::CONTROL, "ctrl+"),
+        (KeyModifiers::ALT, "alt+"),
+        (KeyModifiers::SHIFT, "shift+"),
+    ] {
+        if modifiers.contains(modifier) {
+            prefix.push_str(name);
+        }
+    }
+    prefix
+}
+
+/// Formats the chord the way [`KeyChord::from_str`] parses it.
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", modifier_prefix(self.modifiers))?;
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::F(n) => write!(f, "f{}", n),
+            code => {
+                let name = KEY_NAMES
+                    .iter()
+                    .find(|(_, named)| *named == code)
+                    .map_or("?", |(name, _)| name);
+                write!(f, "{}", name)
+            }
+        }
+    }
+}
+
+const KEY_NAMES: [(&str, KeyCode); 15] = [
+    ("enter", KeyCode::Enter),
+    ("esc", KeyCode::Esc),
+    ("tab", KeyCode::Tab),
+    ("backtab", KeyCode::BackTab),
+    ("backspace", KeyCode::Backspace),
+    ("delete", KeyCode::Delete),
+    ("insert", KeyCode::Insert),
+    ("up", KeyCode::Up),
+    ("down", KeyCode::Down),
+    ("left", KeyCode::Left),
+    ("right", KeyCode::Right),
+    ("home", KeyCode::Home),
+    ("end", KeyCode::End),
+    ("pageup", KeyCode::PageUp),
+    ("pagedown", KeyCode::PageDown),
+];
+
+impl FromStr for KeyChord {
+    type Err = Error;
+
+    /// Parse chords such as `ctrl+c`, `alt+v`, `pagedown`, `space` or `G`.
+    /// `shift+g` is the same as `G`.
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || Error::Config(format!("invalid key `{}`", s));
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = s;
+        // A trailing `+` is the plus key itself, as in `ctrl++`
+        while let Some((modifier, key)) = rest.split_once('+').filter(|(_, key)| !key.is_empty()) {
+            modifiers |= match modifier.to_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" | "meta" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return Err(invalid()),
+            };
+            rest = key;
+        }
+
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            // Terminals report shift with a letter as the uppercase letter,
+            // and shift with other characters as the character it types
+            (Some(c), None) if modifiers.contains(KeyModifiers::SHIFT) => {
+                let mut upper = c.to_uppercase();
+                match (c.is_lowercase(), upper.next(), upper.next()) {
+                    (true, Some(upper), None) => KeyCode::Char(upper),
+                    _ if c.is_uppercase() => KeyCode::Char(c),
+                    _ => {
+                        return Err(Error::Config(format!(
+                            "invalid key `{}`: write the character shift types instead",
+                            s
+                        )))
+                    }
+                }
+            }
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match rest.to_lowercase().as_str() {
+                "space" => KeyCode::Char(' '),
+                "del" => KeyCode::Delete,
+                "escape" => KeyCode::Esc,
+                "return" => KeyCode::Enter,
+                "pgdn" => KeyCode::PageDown,
+                "pgup" => KeyCode::PageUp,
+                name => match name.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                    Some(n) => KeyCode::F(n),
+                    None => KEY_NAMES
+                        .iter()
+                        .find(|(known, _)| *known == name)
+                        .map(|(_, code)| *code)
+                        .ok_or_else(invalid)?,
+                },
+            },
+        };
+        Ok(KeyChord::new(code, modifiers))
+    }
+}
+
+/// One or more chords pressed in order, e.g. `g g` or `ctrl+x ctrl+s`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct KeySequence(Vec<KeyChord>);
+
+impl KeySequence {
+    pub fn new(chords: Vec<KeyChord>) -> Self {
+        Self(chords)
+    }
+
+    pub fn chords(&self) -> &[KeyChord] {
+        &self.0
+    }
+
+    /// The sequence as shown in the help bar, e.g. `g g`.
+    pub fn symbol(&self) -> String {
+        let symbols: Vec<String> = self.0.iter().map(KeyChord::symbol).collect();
+        symbols.join(" ")
+    }
+}
+
+impl From<KeyChord> for KeySequence {
+    fn from(chord: KeyChord) -> Self {
+        Self(vec![chord])
+    }
+}
+
+impl From<KeyCode> for KeySequence {
+    fn from(code: KeyCode) -> Self {
+        KeyChord::from(code).into()
+    }
+}
+
+impl fmt::Display for KeySequence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let chords: Vec<String> = self.0.iter().map(KeyChord::to_string).collect();
+        write!(f, "{}", chords.join(" "))
+    }
+}
+
+impl FromStr for KeySequence {
+    type Err = Error;
+
+    /// Parse space separated chords, e.g. `g g`.
+    fn from_str(s: &str) -> Result<Self> {
+        let chords = s
+            .split_whitespace()
+            .map(str::parse)
+            .collect::<Result<Vec<KeyChord>>>()?;
+        if chords.is_empty() {
+            return Err(Error::Config("empty key sequence".to_string()));
+        }
+        Ok(Self(chords))
+    }
+}
+
+/// Something a component can do in response to a key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+    Up,
+    Down,
+    Left,
+    Right,
+    PageUp,
+    PageDown,
+    Top,
+    Bottom,
+    LineStart,
+    LineEnd,
+    Backspace,
+    Delete,
+    NewLine,
+    Filter,
+    ToggleSelection,
+    Submit,
+    Cancel,
+    Interrupt,
+    FocusNext,
+    FocusPrevious,
+    AcceptSuggestion,
+    NextSuggestion,
+    PreviousSuggestion,
+    SearchHistory,
+}
+
+impl Action {
+    const ALL: [Action; 24] = [
+        Action::Up,
+        Action::Down,
+        Action::Left,
+        Action::Right,
+        Action::PageUp,
+        Action::PageDown,
+        Action::Top,
+        Action::Bottom,
+        Action::LineStart,
+        Action::LineEnd,
+        Action::Backspace,
+        Action::Delete,
+        Action::NewLine,
+        Action::Filter,
+        Action::ToggleSelection,
+        Action::Submit,
+        Action::Cancel,
+        Action::Interrupt,
+        Action::FocusNext,
+        Action::FocusPrevious,
+        Action::AcceptSuggestion,
+        Action::NextSuggestion,
+        Action::PreviousSuggestion,
+        Action::SearchHistory,
+    ];
+
+    /// The action's name in config files.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::Up => "up",
+            Action::Down => "down",
+            Action::Left => "left",
+            Action::Right => "right",
+            Action::PageUp => "page_up",
+            Action::PageDown => "page_down",
+            Action::Top => "top",
+            Action::Bottom => "bottom",
+            Action::LineStart => "line_start",
+            Action::LineEnd => "line_end",
+            Action::Backspace => "backspace",
+            Action::Delete => "delete",
+            Action::NewLine => "new_line",
+            Action::Filter => "filter",
+            Action::ToggleSelection => "toggle_selection",
+            Action::Submit => "submit",
+            Action::Cancel => "cancel",
+            Action::Interrupt => "interrupt",
+            Action::FocusNext => "focus_next",
+            Action::FocusPrevious => "focus_previous",
+            Action::AcceptSuggestion => "accept_suggestion",
+            Action::NextSuggestion => "next_suggestion",
+            Action::PreviousSuggestion => "previous_suggestion",
+            Action::SearchHistory => "search_history",
+        }
+    }
+
+    /// The action as described in the help bar.
+    pub fn description(&self) -> &'static str {
+        match self {
+            Action::PageUp => "page up",
+            Action::PageDown => "page down",
+            Action::LineStart => "start",
+            Action::LineEnd => "end",
+            Action::NewLine => "new line",
+            Action::ToggleSelection => "toggle",
+            Action::Interrupt => "exit",
+            Action::FocusNext => "next",
+            Action::FocusPrevious => "previous",
+            Action::AcceptSuggestion => "accept",
+            Action::NextSuggestion => "next match",
+            Action::PreviousSuggestion => "previous match",
+            Action::SearchHistory => "search",
+            action => action.name(),
+        }
+    }
+}
+
+impl FromStr for Action {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Action::ALL
+            .into_iter()
+            .find(|action| action.name() == s)
+            .ok_or_else(|| Error::Config(format!("unknown action `{}`", s)))
+    }
+}
+
+/// A built-in set of bindings.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Preset {
+    #[default]
+    Default,
+    Vim,
+    Emacs,
+}
+
+impl FromStr for Preset {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "default" => Ok(Preset::Default),
+            "vim" => Ok(Preset::Vim),
+            "emacs" => Ok(Preset::Emacs),
+            _ => Err(Error::Config(format!("unknown preset `{}`", s))),
+        }
+    }
+}
+
+/// The component a [`KeyMap`] is meant for; each has its own defaults.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Component {
+    TextInput,
+    TextArea,
+    List,
+    Menu,
+    Table,
+    Viewport,
+    Timer,
+    StopWatch,
+    /// The keys a [`FocusRing`](crate::focus::FocusRing) moves focus with.
+    Focus,
+    /// Moving between the fields of a [`Form`](crate::form::Form), and
+    /// answering its confirm fields.
+    Form,
+}
+
+/// Maps key chords to the [`Action`]s of one component.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyMap {
+    // Kept in insertion order so the help bar lists keys predictably
+    bindings: Vec<(KeySequence, Action)>,
+    timeout: Duration,
+}
+
+/// How long a [`KeyBuffer`] waits for the rest of a sequence by default.
+pub const DEFAULT_SEQUENCE_TIMEOUT: Duration = Duration::from_secs(1);
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        Self {
+            bindings: Vec::new(),
+            timeout: DEFAULT_SEQUENCE_TIMEOUT,
+        }
+    }
+}
+
+impl KeyMap {
+    /// A keymap without any bindings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The bindings `preset` gives `component`.
+    pub fn preset(preset: Preset, component: Component) -> Self {
+        let ctrl = |c| KeyChord::new(KeyCode::Char(c), KeyModifiers::CONTROL);
+        let alt = |c| KeyChord::new(KeyCode::Char(c), KeyModifiers::ALT);
+        let key = |code| KeyChord::new(code, KeyModifiers::NONE);
+        let seq = |chords: [KeyChord; 2]| KeySequence::new(chords.to_vec());
+
+        let mut keymap = KeyMap::new();
+        // The focused component handles ctrl+c itself
+        if component != Component::Focus {
+            keymap.bind(ctrl('c'), Action::Interrupt);
+        }
+        match component {
+            Component::TextInput | Component::TextArea => {
+                keymap.bind(key(KeyCode::Esc), Action::Cancel);
+                if component == Component::TextInput {
+                    keymap.bind(key(KeyCode::Enter), Action::Submit);
+                    keymap.bind(key(KeyCode::Tab), Action::AcceptSuggestion);
+                    keymap.bind(ctrl('n'), Action::NextSuggestion);
+                    keymap.bind(ctrl('p'), Action::PreviousSuggestion);
+                    keymap.bind(ctrl('r'), Action::SearchHistory);
+                } else {
+                    keymap.bind(key(KeyCode::Tab), Action::Submit);
+                    keymap.bind(key(KeyCode::Enter), Action::NewLine);
+                }
+                // An input walks through its history, a text area between lines
+                keymap.bind(key(KeyCode::Up), Action::Up);
+                keymap.bind(key(KeyCode::Down), Action::Down);
+                keymap.bind(key(KeyCode::Left), Action::Left);
+                keymap.bind(key(KeyCode::Right), Action::Right);
+                keymap.bind(key(KeyCode::Home), Action::LineStart);
+                keymap.bind(key(KeyCode::End), Action::LineEnd);
+                keymap.bind(key(KeyCode::Backspace), Action::Backspace);
+                keymap.bind(key(KeyCode::Delete), Action::Delete);
+                // Letters are typed into the text, so only emacs adds bindings
+                if preset == Preset::Emacs {
+                    keymap.bind(ctrl('g'), Action::Cancel);
+                    keymap.bind(ctrl('b'), Action::Left);
+                    keymap.bind(ctrl('f'), Action::Right);
+                    keymap.bind(ctrl('a'), Action::LineStart);
+                    keymap.bind(ctrl('e'), Action::LineEnd);
+                    keymap.bind(ctrl('h'), Action::Backspace);
+                    keymap.bind(ctrl('d'), Action::Delete);
+                    if component == Component::TextArea {
+                        keymap.bind(ctrl('p'), Action::Up);
+                        keymap.bind(ctrl('n'), Action::Down);
+                        keymap.bind(seq([ctrl('x'), ctrl('s')]), Action::Submit);
+                    }
+                }
+            }
+            Component::List | Component::Menu | Component::Table | Component::Viewport => {
+                keymap.bind(key(KeyCode::Char('q')), Action::Cancel);
+                keymap.bind(key(KeyCode::Esc), Action::Cancel);
+                if component == Component::List {
+                    keymap.bind(key(KeyCode::Char('/')), Action::Filter);
+                }
+                keymap.bind(key(KeyCode::Enter), Action::Submit);
+                keymap.bind(key(KeyCode::Up), Action::Up);
+                keymap.bind(key(KeyCode::Char('k')), Action::Up);
+                keymap.bind(key(KeyCode::Down), Action::Down);
+                keymap.bind(key(KeyCode::Char('j')), Action::Down);
+                keymap.bind(key(KeyCode::Home), Action::Top);
+                keymap.bind(key(KeyCode::End), Action::Bottom);
+                if component == Component::List {
+                    keymap.bind(key(KeyCode::Backspace), Action::Backspace);
+                }
+                if component == Component::Menu {
+                    keymap.bind(ctrl('t'), Action::ToggleSelection);
+                }
+                if component == Component::Viewport {
+                    keymap.bind(key(KeyCode::PageUp), Action::PageUp);
+                    keymap.bind(key(KeyCode::PageDown), Action::PageDown);
+                }
+                match preset {
+                    Preset::Default => {}
+                    Preset::Vim => {
+                        keymap.bind(seq([key(KeyCode::Char('g')); 2]), Action::Top);
+                        keymap.bind(key(KeyCode::Char('G')), Action::Bottom);
+                        if component == Component::Viewport {
+                            keymap.bind(ctrl('u'), Action::PageUp);
+                            keymap.bind(ctrl('d'), Action::PageDown);
+                        }
+                    }
+                    Preset::Emacs => {
+                        keymap.bind(ctrl('g'), Action::Cancel);
+                        keymap.bind(ctrl('p'), Action::Up);
+                        keymap.bind(ctrl('n'), Action::Down);
+                        keymap.bind(alt('<'), Action::Top);
+                        keymap.bind(alt('>'), Action::Bottom);
+                        if component == Component::List {
+                            keymap.bind(ctrl('s'), Action::Filter);
+                        }
+                        if component == Component::Viewport {
+                            keymap.bind(alt('v'), Action::PageUp);
+                            keymap.bind(ctrl('v'), Action::PageDown);
+                        }
+                    }
+                }
+            }
+            Component::Timer | Component::StopWatch => {
+                keymap.bind(key(KeyCode::Char('q')), Action::Cancel);
+                keymap.bind(key(KeyCode::Esc), Action::Cancel);
+                if component == Component::StopWatch {
+                    keymap.bind(key(KeyCode::Enter), Action::Submit);
+                }
+                if preset == Preset::Emacs {
+                    keymap.bind(ctrl('g'), Action::Cancel);
+                }
+            }
+            Component::Focus => {
+                keymap.bind(key(KeyCode::Tab), Action::FocusNext);
+                keymap.bind(key(KeyCode::BackTab), Action::FocusPrevious);
+            }
+            Component::Form => {
+                keymap.bind(key(KeyCode::Esc), Action::Cancel);
+                keymap.bind(key(KeyCode::Enter), Action::Submit);
+                keymap.bind(key(KeyCode::Tab), Action::FocusNext);
+                keymap.bind(key(KeyCode::BackTab), Action::FocusPrevious);
+                keymap.bind(key(KeyCode::Left), Action::Left);
+                keymap.bind(key(KeyCode::Right), Action::Right);
+                match preset {
+                    Preset::Default => {}
+                    Preset::Vim => {
+                        keymap.bind(key(KeyCode::Char('h')), Action::Left);
+                        keymap.bind(key(KeyCode::Char('l')), Action::Right);
+                    }
+                    Preset::Emacs => {
+                        keymap.bind(ctrl('g'), Action::Cancel);
+                        keymap.bind(ctrl('b'), Action::Left);
+                        keymap.bind(ctrl('f'), Action::Right);
+                    }
+                }
+            }
+        }
+        keymap
+    }
+
+    /// The default bindings for `component`.
+    pub fn default_for(component: Component) -> Self {
+        Self::preset(Preset::Default, component)
+    }
+
+    /// How long to wait for the rest of a sequence such as `g g`.
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Bind `keys` to `action`, replacing whatever they were bound to before.
+    ///
+    /// A sequence that is also the start of a longer one, like `g` next to
+    /// `g g`, fires as soon as it is complete, so the longer one is unreachable.
+    pub fn bind(&mut self, keys: impl Into<KeySequence>, action: Action) {
+        let keys = keys.into();
+        match self.bindings.iter_mut().find(|(bound, _)| *bound == keys) {
+            Some(binding) => binding.1 = action,
+            None => self.bindings.push((keys, action)),
+        }
+    }
+
+    pub fn unbind(&mut self, keys: impl Into<KeySequence>) {
+        let keys = keys.into();
+        self.bindings.retain(|(bound, _)| *bound != keys);
+    }
+
+    /// The keymap without the bindings that start by typing a character, for
+    /// components that temporarily accept text such as a list's filter.
+    pub fn without_text_keys(&self) -> KeyMap {
+        let bindings = self
+            .bindings
+            .iter()
+            .filter(|(keys, _)| !keys.chords()[0].is_text());
+        KeyMap {
+            bindings: bindings.cloned().collect(),
+            timeout: self.timeout,
+        }
+    }
+
+    /// The action bound to a single key press, if any. Use a [`KeyBuffer`] to
+    /// also match sequences.
+    pub fn action(&self, key: &KeyEvent) -> Option<Action> {
+        match self.lookup(&[KeyChord::from(*key)]) {
+            Lookup::Action(action) => Some(action),
+            Lookup::Prefix | Lookup::Unbound => None,
+        }
+    }
+
+    fn lookup(&self, chords: &[KeyChord]) -> Lookup {
+        let mut lookup = Lookup::Unbound;
+        for (keys, action) in &self.bindings {
+            if keys.chords() == chords {
+                return Lookup::Action(*action);
+            }
+            if keys.chords().starts_with(chords) {
+                lookup = Lookup::Prefix;
+            }
+        }
+        lookup
+    }
+
+    /// The key sequences bound to `action`, in the order they were bound.
+    pub fn keys_for(&self, action: Action) -> impl Iterator<Item = &KeySequence> + '_ {
+        self.bindings
+            .iter()
+            .filter(move |(_, bound)| *bound == action)
+            .map(|(keys, _)| keys)
+    }
+
+    /// Help bar entries for `actions`, skipping actions without a key.
+    pub fn commands(&self, actions: &[Action]) -> Vec<CommandInfo> {
+        actions
+            .iter()
+            .filter_map(|&action| {
+                let keys: Vec<String> = self.keys_for(action).map(|key| key.symbol()).collect();
+                (!keys.is_empty()).then(|| CommandInfo::new(&keys.join("/"), action.description()))
+            })
+            .collect()
+    }
+}
+
+enum Lookup {
+    Action(Action),
+    Prefix,
+    Unbound,
+}
+
+// Keeps a mistyped count from repeating an action for ages
+const MAX_COUNT: usize = 9999;
+
+/// What a key press amounted to, see [`KeyBuffer::resolve`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Resolved {
+    /// A binding is complete. `count` is the number typed before it, as in `5j`.
+    Action {
+        action: Action,
+        count: Option<usize>,
+    },
+    /// The key started or continued a sequence or count.
+    Pending,
+    /// The key isn't bound. Components that accept text type it instead.
+    Unbound,
+}
+
+impl Resolved {
+    /// The action and how often to repeat it.
+    pub fn repeated(self) -> Option<(Action, usize)> {
+        match self {
+            Resolved::Action { action, count } => Some((action, count.unwrap_or(1))),
+            Resolved::Pending | Resolved::Unbound => None,
+        }
+    }
+}
+
+/// Collects the keys of a multi-key sequence such as `g g` or `ctrl+x ctrl+s`
+/// and an optional count such as the `5` in `5j`, until they match a binding
+/// in a [`KeyMap`].
+///
+/// A pending prefix is dropped when the next key arrives after the keymap's
+/// [`timeout`](KeyMap::timeout).
+#[derive(Clone, Debug, Default)]
+pub struct KeyBuffer {
+    keys: Vec<KeyChord>,
+    count: Option<usize>,
+    last_key: Option<Instant>,
+    counts: bool,
+}
+
+impl KeyBuffer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accept counts, i.e. treat unbound digits as a repeat count.
+    pub fn counts(mut self, counts: bool) -> Self {
+        self.counts = counts;
+        self
+    }
+
+    pub fn resolve(&mut self, keymap: &KeyMap, key: &KeyEvent) -> Resolved {
+        let now = Instant::now();
+        if self
+            .last_key
+            .is_some_and(|last| now.duration_since(last) > keymap.timeout())
+        {
+            self.clear();
+        }
+        self.last_key = Some(now);
+
+        let chord = KeyChord::from(*key);
+        if let (KeyCode::Char(c), true) = (chord.code, chord.modifiers.is_empty()) {
+            // A leading `0` is a key of its own, like vim's "start of line"
+            let digit = c.to_digit(10).filter(|&d| d > 0 || self.count.is_some());
+            if let Some(digit) = digit.filter(|_| self.counts && self.keys.is_empty()) {
+                if let Lookup::Unbound = keymap.lookup(&[chord]) {
+                    let count = self.count.unwrap_or(0) * 10 + digit as usize;
+                    self.count = Some(count.min(MAX_COUNT));
+                    return Resolved::Pending;
+                }
+            }
+        }
+
+        self.keys.push(chord);
+        match keymap.lookup(&self.keys) {
+            Lookup::Action(action) => {
+                let count = self.count;
+                self.clear();
+                Resolved::Action { action, count }
+            }
+            Lookup::Prefix => Resolved::Pending,
+            Lookup::Unbound => {
+                let retry = self.keys.len() > 1;
+                self.clear();
+                // The key that broke a sequence may still be bound on its own
+                if retry {
+                    self.resolve(keymap, key)
+                } else {
+                    Resolved::Unbound
+                }
+            }
+        }
+    }
+
+    /// The count and keys typed so far, e.g. `5 g`, or `None` if nothing is
+    /// pending.
+    pub fn pending(&self) -> Option<String> {
+        let mut parts: Vec<String> = self.count.iter().map(usize::to_string).collect();
+        parts.extend(self.keys.iter().map(KeyChord::symbol));
+        (!parts.is_empty()).then(|| parts.join(" "))
+    }
+
+    pub fn clear(&mut self) {
+        self.keys.clear();
+        self.count = None;
+        self.last_key = None;
+    }
+}
+
+/// User overrides on top of a [`Preset`], usually loaded from a TOML file:
+///
+/// ```toml
+/// preset = "vim"
+///
+/// [list]
+/// "ctrl+n" = "down"
+/// "q" = "none"  # unbind
+/// "g g" = "top"
+/// ```
+///
+/// `sequence_timeout_ms` sets how long to wait for the rest of a sequence
+/// such as `g g`. Tables are named after the components: `text_input`,
+/// `text_area`, `list`, `menu`, `table`, `viewport`, `timer` and
+/// `stopwatch`, plus `focus` for the keys that move focus between
+/// components and `form` for those of forms.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyConfig {
+    preset: Preset,
+    timeout: Duration,
+    overrides: Vec<(Component, KeySequence, Option<Action>)>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawKeyConfig {
+    preset: Option<String>,
+    sequence_timeout_ms: Option<u64>,
+    text_input: BTreeMap<String, String>,
+    text_area: BTreeMap<String, String>,
+    list: BTreeMap<String, String>,
+    menu: BTreeMap<String, String>,
+    table: BTreeMap<String, String>,
+    viewport: BTreeMap<String, String>,
+    timer: BTreeMap<String, String>,
+    stopwatch: BTreeMap<String, String>,
+    focus: BTreeMap<String, String>,
+    form: BTreeMap<String, String>,
+}
+
+impl Default for KeyConfig {
+    fn default() -> Self {
+        Self::new(Preset::Default)
+    }
+}
+
+impl KeyConfig {
+    pub fn new(preset: Preset) -> Self {
+        Self {
+            preset,
+            timeout: DEFAULT_SEQUENCE_TIMEOUT,
+            overrides: Vec::new(),
+        }
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn parse(source: &str) -> Result<Self> {
+        let raw: RawKeyConfig =
+            toml::from_str(source).map_err(|err| Error::Config(err.to_string()))?;
+        let mut config = KeyConfig::new(match raw.preset {
+            Some(preset) => preset.parse()?,
+            None => Preset::Default,
+        });
+        if let Some(timeout) = raw.sequence_timeout_ms {
+            config.timeout = Duration::from_millis(timeout);
+        }
+        for (component, bindings) in [
+            (Component::TextInput, raw.text_input),
+            (Component::TextArea, raw.text_area),
+            (Component::List, raw.list),
+            (Component::Menu, raw.menu),
+            (Component::Table, raw.table),
+            (Component::Viewport, raw.viewport),
+            (Component::Timer, raw.timer),
+            (Component::StopWatch, raw.stopwatch),
+            (Component::Focus, raw.focus),
+            (Component::Form, raw.form),
+        ] {
+            for (keys, action) in bindings {
+                let action = match action.as_str() {
+                    "none" => None,
+                    action => Some(action.parse()?),
+                };
+                config.overrides.push((component, keys.parse()?, action));
+            }
+        }
+        Ok(config)
+    }
+
+    /// The keymap for `component`: the preset with the overrides applied.
+    pub fn keymap(&self, component: Component) -> KeyMap {
+        let mut keymap = KeyMap::preset(self.preset, component).with_timeout(self.timeout);
+        for (_, keys, action) in self.overrides.iter().filter(|(c, ..)| *c == component) {
+            match action {
+                Some(action) => keymap.bind(keys.clone(), *action),
+                None => keymap.unbind(keys.clone()),
+            }
+        }
+        keymap
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_formats_chords() {
+        for text in ["ctrl+c", "alt+v", "pagedown", "space", "G", "f5", "ctrl++"] {
+            let chord: KeyChord = text.parse().unwrap();
+            assert_eq!(chord.to_string(), text);
+        }
+        assert_eq!(
+            "Ctrl+X".parse::<KeyChord>().unwrap(),
+            KeyChord::new(KeyCode::Char('X'), KeyModifiers::CONTROL)
+        );
+        assert_eq!(
+            "shift+a".parse::<KeyChord>().unwrap(),
+            KeyChord::from(KeyCode::Char('A'))
+        );
+        assert!("shift+1".parse::<KeyChord>().is_err());
+        assert!("hyper+x".parse::<KeyChord>().is_err());
+        assert!("enterr".parse::<KeyChord>().is_err());
+    }
+
+    #[test]
+    fn ignores_shift_on_characters() {
+        let keymap = KeyMap::preset(Preset::Default, Component::List);
+        let key = KeyEvent::new(KeyCode::Char('K'), KeyModifiers::SHIFT);
+        assert_eq!(keymap.action(&key), None);
+        let key = KeyEvent::new(KeyCode::Char('k'), KeyModifiers::NONE);
+        assert_eq!(keymap.action(&key), Some(Action::Up));
+    }
+
+    #[test]
+    fn presets_differ() {
+        let ctrl_n = KeyEvent::new(KeyCode::Char('n'), KeyModifiers::CONTROL);
+        let emacs = KeyMap::preset(Preset::Emacs, Component::Table);
+        let default = KeyMap::default_for(Component::Table);
+        assert_eq!(emacs.action(&ctrl_n), Some(Action::Down));
+        assert_eq!(default.action(&ctrl_n), None);
+    }
+
+    #[test]
+    fn builds_help_from_bindings() {
+        let keymap = KeyMap::default_for(Component::Menu);
+        let commands = keymap.commands(&[Action::Down, Action::Filter]);
+        assert_eq!(commands.len(), 1);
+        assert_eq!(commands[0].key, "\u{2193}/j");
+        assert_eq!(commands[0].description, "down");
+    }
+
+    #[test]
+    fn parses_sequences() {
+        let keys: KeySequence = "ctrl+x  ctrl+s".parse().unwrap();
+        assert_eq!(keys.chords().len(), 2);
+        assert_eq!(keys.to_string(), "ctrl+x ctrl+s");
+        assert!("  ".parse::<KeySequence>().is_err());
+    }
+
+    #[test]
+    fn resolves_sequences_and_counts() {
+        let keymap = KeyMap::preset(Preset::Vim, Component::Table);
+        let mut keys = KeyBuffer::new().counts(true);
+        let mut press = |c| {
+            keys.resolve(
+                &keymap,
+                &KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE),
+            )
+        };
+
+        assert_eq!(press('g'), Resolved::Pending);
+        assert_eq!(
+            press('g'),
+            Resolved::Action {
+                action: Action::Top,
+                count: None
+            }
+        );
+        assert_eq!(press('1'), Resolved::Pending);
+        assert_eq!(press('2'), Resolved::Pending);
+        assert_eq!(press('j').repeated(), Some((Action::Down, 12)));
+        // A key that breaks a sequence is looked up on its own
+        assert_eq!(press('g'), Resolved::Pending);
+        assert_eq!(press('k').repeated(), Some((Action::Up, 1)));
+        assert_eq!(press('x'), Resolved::Unbound);
+    }
+
+    #[test]
+    fn shows_and_expires_pending_keys() {
+        let keymap = KeyMap::preset(Preset::Vim, Component::List);
+        let mut keys = KeyBuffer::new().counts(true);
+        let g = KeyEvent::new(KeyCode::Char('g'), KeyModifiers::NONE);
+
+        keys.resolve(
+            &keymap,
+            &KeyEvent::new(KeyCode::Char('3'), KeyModifiers::NONE),
+        );
+        keys.resolve(&keymap, &g);
+        assert_eq!(keys.pending().as_deref(), Some("3 g"));
+
+        let keymap = keymap.with_timeout(Duration::ZERO);
+        std::thread::sleep(Duration::from_millis(1));
+        assert_eq!(keys.resolve(&keymap, &g), Resolved::Pending);
+        assert_eq!(keys.pending().as_deref(), Some("g"));
+    }
+
+    #[test]
+    fn applies_config_overrides() {
+        let config = KeyConfig::parse(
+            r#"
+            preset = "vim"
+
+            sequence_timeout_ms = 500
+
+            [list]
+            "ctrl+n" = "down"
+            "q" = "none"
+            "g t" = "top"
+            "#,
+        )
+        .unwrap();
+        let keymap = config.keymap(Component::List);
+        let key = |code, modifiers| KeyEvent::new(code, modifiers);
+        assert_eq!(
+            keymap.action(&key(KeyCode::Char('n'), KeyModifiers::CONTROL)),
+            Some(Action::Down)
+        );
+        assert_eq!(
+            keymap.action(&key(KeyCode::Char('q'), KeyModifiers::NONE)),
+            None
+        );
+        // Other components keep the preset
+        let menu = config.keymap(Component::Menu);
+        assert_eq!(
+            menu.action(&key(KeyCode::Char('q'), KeyModifiers::NONE)),
+            Some(Action::Cancel)
+        );
+
+        assert!(KeyConfig::parse("[list]\nq = \"fly\"").is_err());
+        assert!(KeyConfig::parse("[lists]\nq = \"up\"").is_err());
+    }
+}
@@ -1,15 +1,31 @@
 pub mod colors;
 pub mod command;
+pub mod editor;
+pub mod error;
+pub mod event;
+pub mod fallback;
+pub mod focus;
+pub mod form;
 pub mod help;
 pub mod helper;
+pub mod history;
+pub mod inline;
 pub mod input;
+pub mod keymap;
 pub mod list;
 pub mod menu_list;
+pub mod mouse;
+pub mod program;
 pub mod progress_bar;
 pub mod spinner;
 pub mod stopwatch;
 pub mod table;
 pub mod text_area;
+pub mod theme;
 pub mod timer;
 pub mod tui;
+pub mod unicode;
+pub mod validate;
 pub mod viewport;
+
+pub use error::{Error, Result};
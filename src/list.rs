@@ -1,16 +1,30 @@
-use std::io;
+use std::{
+    cell::Cell,
+    io::{self, BufRead, Write},
+};
 
-use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::{self, Event, KeyCode, MouseEvent, MouseEventKind};
 use ratatui::{
     backend::{Backend, CrosstermBackend},
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::{Line, Span, Text},
-    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Widget},
-    Terminal,
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    Frame, Terminal,
 };
 
-use crate::{command::CommandInfo, help::HelpComponent};
+use crate::{
+    event::{EventOutcome, HandleEvent, PromptResult},
+    fallback,
+    focus::Focusable,
+    help::HelpComponent,
+    inline::InlinePrompt,
+    keymap::{Action, Component, KeyBuffer, KeyChord, KeyMap, Resolved},
+    mouse::{self, ClickTracker},
+    program::{Cmd, Model, Msg},
+    theme::Theme,
+    tui, unicode, Result,
+};
 
 #[derive(Clone)]
 pub struct Item {
@@ -18,6 +32,12 @@ pub struct Item {
     pub subtitle: String,
 }
 
+// Number of items visible at once when the list runs inline
+const INLINE_ITEMS: usize = 4;
+
+// Rows taken by each item: padding, title, subtitle and padding
+const ITEM_HEIGHT: usize = 4;
+
 pub struct ItemList {
     title: String,
     filtered_items: Vec<Item>,
@@ -25,6 +45,14 @@ pub struct ItemList {
     state: ListState,
     filter: String,
     showing_filter: bool,
+    keymap: KeyMap,
+    keys: KeyBuffer,
+    theme: Theme,
+    focused: bool,
+    // Where the items were last drawn and the first one shown, for clicks
+    list_area: Cell<Rect>,
+    list_offset: Cell<usize>,
+    clicks: ClickTracker,
 }
 
 impl ItemList {
@@ -39,13 +67,43 @@ impl ItemList {
             state,
             filter: String::new(),
             showing_filter: false,
+            keymap: KeyMap::default_for(Component::List),
+            keys: KeyBuffer::new().counts(true),
+            theme: Theme::default(),
+            focused: true,
+            list_area: Cell::default(),
+            list_offset: Cell::default(),
+            clicks: ClickTracker::new(),
         }
     }
 
+    pub fn with_keymap(mut self, keymap: KeyMap) -> Self {
+        self.set_keymap(keymap);
+        self
+    }
+
+    pub fn set_keymap(&mut self, keymap: KeyMap) {
+        self.keymap = keymap;
+        self.keys.clear();
+    }
+
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.set_theme(theme);
+        self
+    }
+
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+
     pub fn next(&mut self) {
+        // Selection indexes the visible rows, and there may be none
+        let Some(last) = self.filtered_items.len().checked_sub(1) else {
+            return;
+        };
         let i = match self.state.selected() {
             Some(i) => {
-                if i >= self.items.len() - 1 {
+                if i >= last {
                     0
                 } else {
                     i + 1
@@ -57,12 +115,15 @@ impl ItemList {
     }
 
     pub fn previous(&mut self) {
+        let Some(last) = self.filtered_items.len().checked_sub(1) else {
+            return;
+        };
         let i = match self.state.selected() {
             Some(i) => {
                 if i == 0 {
-                    self.items.len() - 1
+                    last
                 } else {
-                    i - 1
+                    (i - 1).min(last)
                 }
             }
             None => 0,
@@ -70,14 +131,47 @@ impl ItemList {
         self.state.select(Some(i));
     }
 
+    /// Select the `n`th visible item, counting from 1, or the last one.
+    pub fn select_nth(&mut self, n: Option<usize>) {
+        let last = self.filtered_items.len().saturating_sub(1);
+        let index = n.map_or(last, |n| n.saturating_sub(1).min(last));
+        self.state.select(Some(index));
+    }
+
     pub fn get_selected_item(&self) -> Option<&Item> {
         if let Some(selected) = self.state.selected() {
-            self.items.get(selected)
+            self.filtered_items.get(selected)
         } else {
             None
         }
     }
 
+    /// List the items numbered on plain lines and read the number of one
+    /// from `input`, for when there is no terminal.
+    pub(crate) fn prompt_plain(
+        &mut self,
+        input: &mut impl BufRead,
+        output: &mut impl Write,
+    ) -> Result<PromptResult<String>> {
+        let choices: Vec<String> = self
+            .items
+            .iter()
+            .map(|item| match item.subtitle.is_empty() {
+                true => item.title.clone(),
+                false => format!("{} - {}", item.title, item.subtitle),
+            })
+            .collect();
+        match fallback::choose(input, output, &self.title, &choices)? {
+            Some(index) => {
+                self.filter.clear();
+                self.update_filter();
+                self.state.select(Some(index));
+                Ok(PromptResult::Submitted(self.items[index].title.clone()))
+            }
+            None => Ok(PromptResult::Cancelled),
+        }
+    }
+
     pub fn update_filter(&mut self) {
         if self.filter.is_empty() {
             self.filtered_items = self.items.clone();
@@ -96,32 +190,32 @@ impl ItemList {
         self.state.select(Some(0)); // Reset selection
     }
 
-    pub fn create_custom_list_item(item: &Item) -> ListItem {
+    pub fn create_custom_list_item<'a>(item: &'a Item, theme: &Theme) -> ListItem<'a> {
         // Use '\n' to ensure titles and subtitles are on separate lines
         // and ensure that each line is treated as a separate span
-        let lines = vec![
+        let lines = [
             Span::styled(
                 " ",
                 Style::default()
-                    .fg(Color::Gray) // Subtitle can have a different style or color
+                    .fg(theme.secondary.resolve())
                     .add_modifier(Modifier::ITALIC),
             ),
             Span::styled(
                 &item.title,
                 Style::default()
-                    .fg(Color::White) // You might adjust color and style as needed
+                    .fg(theme.text.resolve())
                     .add_modifier(Modifier::BOLD),
             ),
             Span::styled(
                 &item.subtitle,
                 Style::default()
-                    .fg(Color::Gray) // Subtitle can have a different style or color
+                    .fg(theme.secondary.resolve()) // Subtitle can have a different style or color
                     .add_modifier(Modifier::ITALIC),
             ),
             Span::styled(
                 " ",
                 Style::default()
-                    .fg(Color::Gray) // Subtitle can have a different style or color
+                    .fg(theme.secondary.resolve())
                     .add_modifier(Modifier::ITALIC),
             ),
         ];
@@ -142,132 +236,285 @@ impl ItemList {
         terminal: &mut Terminal<B>,
         rect: Rect,
         help_component: &mut HelpComponent,
-    ) {
-        terminal
-            .draw(|f| {
-                let chunks = Layout::default()
-                    .direction(Direction::Vertical)
-                    .constraints(
-                        [
-                            Constraint::Length(1),
-                            Constraint::Percentage(50),
-                            Constraint::Length(3),
-                        ]
-                        .as_ref(),
-                    )
-                    .split(rect);
-
-                //TODO: add title widget on chunk[0]
-
-                if !self.showing_filter {
-                    let title_widget = Paragraph::new(self.title.as_str())
-                        .block(Block::default().borders(Borders::NONE));
-                    f.render_widget(title_widget, chunks[0]);
-                }
+    ) -> Result<()> {
+        terminal.draw(|f| {
+            self.draw(f, rect, help_component.clone());
+            tui::adapt_colors(f);
+        })?;
+        Ok(())
+    }
+
+    /// The help bar matching the list's current mode.
+    pub fn help_component(&self) -> HelpComponent {
+        let commands = self.keymap.commands(&[
+            Action::Interrupt,
+            Action::Cancel,
+            Action::Filter,
+            Action::Submit,
+            Action::Down,
+            Action::Up,
+        ]);
+
+        // Letters are typed into the filter, so only list the other keys
+        let filter_commands = self
+            .keymap
+            .without_text_keys()
+            .commands(&[Action::Cancel, Action::Submit]);
+
+        let mut help_component = HelpComponent::new(commands, filter_commands)
+            .with_pending(self.keys.pending())
+            .with_theme(self.theme);
+
+        if self.showing_filter {
+            help_component.activate_filter_mode();
+        } else {
+            help_component.deactivate_filter_mode();
+        }
+        help_component
+    }
+
+    fn draw(&self, f: &mut Frame, rect: Rect, help_component: HelpComponent) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(
+                [
+                    Constraint::Length(1),
+                    Constraint::Min(0),
+                    Constraint::Length(3),
+                ]
+                .as_ref(),
+            )
+            .split(rect);
+
+        //TODO: add title widget on chunk[0]
+
+        if !self.showing_filter {
+            let title_widget =
+                Paragraph::new(self.title.as_str()).block(Block::default().borders(Borders::NONE));
+            f.render_widget(title_widget, chunks[0]);
+        }
 
-                if self.showing_filter {
-                    let filter_title = "Filter:";
-                    let input = Paragraph::new(format!("{} {}", filter_title, self.filter))
-                        .block(Block::default().borders(Borders::NONE));
-                    f.render_widget(input, chunks[0]);
+        if self.showing_filter {
+            let filter_title = "Filter:";
+            let input = Paragraph::new(format!("{} {}", filter_title, self.filter))
+                .block(Block::default().borders(Borders::NONE));
+            f.render_widget(input, chunks[0]);
 
-                    let cursor_pos = filter_title.len() as u16 + 1 + self.filter.len() as u16; // "Filter: " is 7 chars + 1 space
-                    f.set_cursor(chunks[0].x + cursor_pos, chunks[0].y); // +1 because the text starts one line down in the block
+            let cursor_pos =
+                (unicode::width(filter_title) + 1 + unicode::width(&self.filter)) as u16; // "Filter: " is 7 chars + 1 space
+            if self.focused {
+                f.set_cursor(chunks[0].x + cursor_pos, chunks[0].y); // +1 because the text starts one line down in the block
+            }
+        }
+
+        let items: Vec<ListItem> = self
+            .filtered_items
+            .iter()
+            .map(|item| ItemList::create_custom_list_item(item, &self.theme))
+            .collect();
+
+        // A blurred list dims its highlight so the focused component stands out
+        let highlight = if self.focused {
+            self.theme.primary
+        } else {
+            self.theme.muted
+        };
+        let list = List::new(items)
+            .block(Block::default().title("").borders(Borders::NONE))
+            .highlight_style(
+                Style::default()
+                    .fg(highlight.resolve())
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol("│ ")
+            .repeat_highlight_symbol(true);
+
+        let mut state = self.state.clone();
+        f.render_stateful_widget(list, chunks[1], &mut state);
+        self.list_area.set(chunks[1]);
+        self.list_offset.set(state.offset());
+
+        f.render_widget(help_component, chunks[2])
+    }
+}
+
+impl ItemList {
+    /// Draw the component into `area`, for use inside a [`Program`](crate::program::Program).
+    pub fn view(&mut self, frame: &mut Frame, area: Rect) {
+        let help_component = self.help_component();
+        self.draw(frame, area, help_component);
+    }
+}
+
+impl ItemList {
+    // The wheel moves the selection, a click selects an item and a double
+    // click submits it
+    fn handle_mouse(&mut self, mouse: &MouseEvent) -> EventOutcome {
+        match mouse.kind {
+            MouseEventKind::ScrollUp => self.previous(),
+            MouseEventKind::ScrollDown => self.next(),
+            _ => {
+                let Some((_, row)) = mouse::left_click(mouse, self.list_area.get()) else {
+                    return EventOutcome::Ignored;
+                };
+                let index = self.list_offset.get() + row as usize / ITEM_HEIGHT;
+                if index >= self.filtered_items.len() {
+                    return EventOutcome::Ignored;
                 }
+                self.state.select(Some(index));
+                if self.clicks.click(mouse.column, mouse.row) {
+                    return EventOutcome::Submitted;
+                }
+            }
+        }
+        EventOutcome::Consumed
+    }
+}
 
-                let items: Vec<ListItem> = self
-                    .filtered_items
-                    .iter()
-                    .map(ItemList::create_custom_list_item)
-                    .collect();
-
-                let list = List::new(items)
-                    .block(Block::default().title("").borders(Borders::NONE))
-                    .highlight_style(
-                        Style::default()
-                            .fg(Color::LightMagenta)
-                            .add_modifier(Modifier::BOLD),
-                    )
-                    .highlight_symbol("│ ")
-                    .repeat_highlight_symbol(true);
-
-                f.render_stateful_widget(list, chunks[1], &mut self.state.clone());
-
-                f.render_widget(help_component.clone(), chunks[2])
-            })
-            .unwrap();
+impl HandleEvent for ItemList {
+    fn handle_event(&mut self, event: &Event) -> EventOutcome {
+        let key = match event {
+            Event::Key(key) => key,
+            Event::Mouse(mouse) => return self.handle_mouse(mouse),
+            // A redraw lays the list out for the new size
+            Event::Resize(..) => return EventOutcome::Consumed,
+            _ => return EventOutcome::Ignored,
+        };
+        // While filtering, characters are typed unless they close the filter
+        if let KeyCode::Char(c) = key.code {
+            if self.showing_filter
+                && self.keymap.action(key) != Some(Action::Filter)
+                && KeyChord::from(*key).is_text()
+            {
+                self.keys.clear();
+                self.filter.push(c);
+                self.update_filter();
+                return EventOutcome::Consumed;
+            }
+        }
+        let (action, count) = match self.keys.resolve(&self.keymap, key) {
+            Resolved::Action { action, count } => (action, count),
+            Resolved::Pending => return EventOutcome::Consumed,
+            Resolved::Unbound => return EventOutcome::Ignored,
+        };
+        match action {
+            Action::Interrupt => return EventOutcome::Interrupted,
+            Action::Filter => self.showing_filter = !self.showing_filter,
+            Action::Cancel if self.showing_filter => self.showing_filter = false,
+            Action::Cancel => return EventOutcome::Cancelled,
+            Action::Down => (0..count.unwrap_or(1)).for_each(|_| self.next()),
+            Action::Up => (0..count.unwrap_or(1)).for_each(|_| self.previous()),
+            Action::Top => self.state.select(Some(0)),
+            Action::Bottom => self.select_nth(count),
+            Action::Submit if !self.filtered_items.is_empty() => return EventOutcome::Submitted,
+            Action::Backspace if self.showing_filter => {
+                self.filter.pop();
+                self.update_filter();
+            }
+            _ => return EventOutcome::Ignored,
+        }
+        EventOutcome::Consumed
     }
 }
 
-pub fn handle_list(list: &mut ItemList, _x: u16, _y: u16) -> Option<String> {
-    let stdout = io::stdout();
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend).unwrap();
+impl Focusable for ItemList {
+    fn is_focused(&self) -> bool {
+        self.focused
+    }
 
-    loop {
-        terminal.clear().unwrap();
-
-        let commands = vec![
-            CommandInfo::new(KeyCode::Char('c'), KeyModifiers::CONTROL),
-            CommandInfo::new(KeyCode::Char('q'), KeyModifiers::NONE),
-            CommandInfo::new(KeyCode::Char('/'), KeyModifiers::NONE),
-            CommandInfo::new(KeyCode::Enter, KeyModifiers::NONE),
-            CommandInfo::new(KeyCode::Down, KeyModifiers::NONE),
-            CommandInfo::new(KeyCode::Up, KeyModifiers::NONE),
-        ];
+    fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+    }
 
-        let filter_commands = vec![
-            CommandInfo::new(KeyCode::Esc, KeyModifiers::NONE),
-            CommandInfo::new(KeyCode::Enter, KeyModifiers::NONE),
-        ];
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        ItemList::view(self, frame, area);
+    }
+}
 
-        let mut help_component = HelpComponent::new(commands, filter_commands);
+impl InlinePrompt for ItemList {
+    type Value = String;
 
-        if list.showing_filter {
-            help_component.activate_filter_mode();
-        } else {
-            help_component.deactivate_filter_mode();
+    fn inline_height(&self) -> u16 {
+        // Title, four lines per item and the help bar
+        let rows = self.items.len().min(INLINE_ITEMS) * ITEM_HEIGHT;
+        (1 + rows + 3) as u16
+    }
+
+    fn submitted_value(&self) -> Option<String> {
+        let selected = self.state.selected()?;
+        self.filtered_items
+            .get(selected)
+            .map(|item| item.title.clone())
+    }
+
+    fn fallback_value(
+        &mut self,
+        input: &mut impl BufRead,
+        output: &mut impl Write,
+    ) -> Result<PromptResult<String>> {
+        self.prompt_plain(input, output)
+    }
+
+    fn summary(&self, value: &String) -> String {
+        value.clone()
+    }
+
+    fn theme(&self) -> Theme {
+        self.theme
+    }
+}
+
+impl<M> Model<M> for ItemList {
+    fn update(&mut self, msg: Msg<M>) -> Cmd<M> {
+        if let Some(event) = msg.to_event() {
+            self.handle_event(&event);
         }
+        Cmd::none()
+    }
 
-        let size = terminal.size().unwrap();
-        list.render(&mut terminal, size, &mut help_component.clone());
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        ItemList::view(self, frame, area);
+    }
+}
 
-        if let Event::Key(KeyEvent {
-            code, modifiers, ..
-        }) = event::read().unwrap()
-        {
-            match code {
-                KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
-                    return None;
-                }
-                KeyCode::Char('/') => {
-                    list.showing_filter = !list.showing_filter;
-                }
-                KeyCode::Esc => list.showing_filter = false,
-                KeyCode::Char('q') => return None,
-                KeyCode::Down => list.next(),
-                KeyCode::Up => list.previous(),
-                KeyCode::Enter => {
-                    return Some(list.filtered_items[list.state.selected()?].title.clone())
-                }
-                KeyCode::Char(c) if list.showing_filter => {
-                    list.filter.push(c);
-                    list.update_filter();
-                }
-                KeyCode::Backspace if list.showing_filter => {
-                    list.filter.pop();
-                    list.update_filter();
+pub fn handle_list(list: &mut ItemList, _x: u16, _y: u16) -> Result<PromptResult<String>> {
+    if !fallback::is_interactive() {
+        return list.prompt_plain(&mut io::stdin().lock(), &mut io::stdout());
+    }
+    // Mouse capture lets clicks pick items and the wheel scroll
+    let _session =
+        tui::TerminalSession::with_options(tui::SessionOptions::new().mouse_capture(true))?;
+    let stdout = io::stdout();
+    let backend = CrosstermBackend::new(stdout);
+    let mut terminal = Terminal::new(backend)?;
+
+    loop {
+        terminal.clear()?;
+
+        let mut help_component = list.help_component();
+
+        let size = terminal.size()?;
+        list.render(&mut terminal, size, &mut help_component)?;
+
+        match list.handle_event(&event::read()?) {
+            EventOutcome::Submitted => {
+                // An empty filter result leaves nothing to submit
+                let selected = list.state.selected().unwrap_or_default();
+                if let Some(item) = list.filtered_items.get(selected) {
+                    return Ok(PromptResult::Submitted(item.title.clone()));
                 }
-                _ => {}
             }
+            EventOutcome::Cancelled => return Ok(PromptResult::Cancelled),
+            EventOutcome::Interrupted => return Ok(PromptResult::Interrupted),
+            EventOutcome::Consumed | EventOutcome::Ignored => {}
         }
-        list.render(&mut terminal, size, &mut help_component);
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crossterm::event::{KeyEvent, KeyModifiers};
 
     #[test]
     fn initializes_correctly() {
@@ -332,6 +579,48 @@ mod tests {
         assert_eq!(list.filtered_items[0].title, "Pocky");
     }
 
+    #[test]
+    fn handles_events() {
+        let items = vec![
+            Item {
+                title: "Pocky".into(),
+                subtitle: "Expensive".into(),
+            },
+            Item {
+                title: "Ginger".into(),
+                subtitle: "Exquisite".into(),
+            },
+        ];
+        let mut list = ItemList::new("Groceries".into(), items);
+        let key = |code| Event::Key(KeyEvent::new(code, KeyModifiers::NONE));
+
+        assert_eq!(
+            list.handle_event(&key(KeyCode::Down)),
+            EventOutcome::Consumed
+        );
+        assert_eq!(list.handle_event(&key(KeyCode::Tab)), EventOutcome::Ignored);
+        assert_eq!(
+            list.handle_event(&key(KeyCode::Enter)),
+            EventOutcome::Submitted
+        );
+
+        // While filtering, 'q' is typed into the filter instead of quitting
+        list.handle_event(&key(KeyCode::Char('/')));
+        assert_eq!(
+            list.handle_event(&key(KeyCode::Char('q'))),
+            EventOutcome::Consumed
+        );
+        assert_eq!(list.filter, "q");
+        assert_eq!(
+            list.handle_event(&key(KeyCode::Esc)),
+            EventOutcome::Consumed
+        );
+        assert_eq!(
+            list.handle_event(&key(KeyCode::Char('q'))),
+            EventOutcome::Cancelled
+        );
+    }
+
     #[test]
     fn selects_item_correctly() {
         let items = vec![
@@ -350,4 +639,74 @@ mod tests {
         let selected = list.get_selected_item();
         assert!(matches!(selected, Some(item) if item.title == "Ginger"));
     }
+
+    #[test]
+    fn selects_items_with_the_mouse() {
+        use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
+        let items = vec![
+            Item {
+                title: "Pasta".to_string(),
+                subtitle: String::new(),
+            },
+            Item {
+                title: "Ramen".to_string(),
+                subtitle: String::new(),
+            },
+        ];
+        let mut list = ItemList::new("Food".to_string(), items);
+        list.list_area.set(Rect::new(0, 1, 20, 8));
+        let mouse = |kind, row| {
+            Event::Mouse(MouseEvent {
+                kind,
+                column: 2,
+                row,
+                modifiers: KeyModifiers::NONE,
+            })
+        };
+        let click = MouseEventKind::Down(MouseButton::Left);
+
+        assert_eq!(list.handle_event(&mouse(click, 6)), EventOutcome::Consumed);
+        assert_eq!(list.state.selected(), Some(1));
+        assert_eq!(list.handle_event(&mouse(click, 6)), EventOutcome::Submitted);
+        list.handle_event(&mouse(MouseEventKind::ScrollDown, 0));
+        assert_eq!(list.state.selected(), Some(0));
+        assert_eq!(list.handle_event(&mouse(click, 0)), EventOutcome::Ignored);
+    }
+
+    #[test]
+    fn navigates_empty_and_filtered_lists() {
+        let key = |code| Event::Key(KeyEvent::new(code, KeyModifiers::NONE));
+        let wheel = |kind| {
+            Event::Mouse(MouseEvent {
+                kind,
+                column: 0,
+                row: 0,
+                modifiers: KeyModifiers::NONE,
+            })
+        };
+        let mut list = ItemList::new("Nothing".into(), Vec::new());
+        list.handle_event(&key(KeyCode::Down));
+        list.handle_event(&key(KeyCode::Up));
+        list.handle_event(&wheel(MouseEventKind::ScrollDown));
+        list.handle_event(&wheel(MouseEventKind::ScrollUp));
+        assert!(list.get_selected_item().is_none());
+        assert_eq!(
+            list.handle_event(&key(KeyCode::Enter)),
+            EventOutcome::Ignored
+        );
+
+        // Only the rows left by the filter can be selected
+        let items = ["Pocky", "Ginger", "Pretzels"].map(|title| Item {
+            title: title.into(),
+            subtitle: String::new(),
+        });
+        let mut list = ItemList::new("Groceries".into(), items.to_vec());
+        list.filter = "p".to_string();
+        list.update_filter();
+        list.next();
+        list.next();
+        assert_eq!(list.state.selected(), Some(0));
+        list.previous();
+        assert_eq!(list.get_selected_item().unwrap().title, "Pretzels");
+    }
 }
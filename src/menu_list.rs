@@ -1,15 +1,29 @@
-use std::io;
+use std::{
+    cell::Cell,
+    io::{self, BufRead, Write},
+};
 
-use crossterm::event::{read, Event, KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::{read, Event, MouseEvent, MouseEventKind};
 use ratatui::{
     backend::{Backend, CrosstermBackend},
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style, Stylize},
+    style::{Modifier, Style, Stylize},
     widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
-    Terminal,
+    Frame, Terminal,
 };
 
-use crate::{command::CommandInfo, help::HelpComponent};
+use crate::{
+    event::{EventOutcome, HandleEvent, PromptResult},
+    fallback,
+    focus::Focusable,
+    help::HelpComponent,
+    inline::InlinePrompt,
+    keymap::{Action, Component, KeyBuffer, KeyMap, Resolved},
+    mouse::{self, ClickTracker},
+    program::{Cmd, Model, Msg},
+    theme::Theme,
+    tui, Result,
+};
 
 #[derive(Clone, Debug)]
 struct MenuItem {
@@ -23,6 +37,14 @@ pub struct Menu {
     subtitle: String,
     items: Vec<MenuItem>,
     selection_state: ListState,
+    keymap: KeyMap,
+    keys: KeyBuffer,
+    theme: Theme,
+    focused: bool,
+    // Where the items were last drawn and the first one shown, for clicks
+    list_area: Cell<Rect>,
+    list_offset: Cell<usize>,
+    clicks: ClickTracker,
 }
 
 impl Menu {
@@ -43,6 +65,53 @@ impl Menu {
             subtitle,
             items: menu_items,
             selection_state: state,
+            keymap: KeyMap::default_for(Component::Menu),
+            keys: KeyBuffer::new().counts(true),
+            theme: Theme::default(),
+            focused: true,
+            list_area: Cell::default(),
+            list_offset: Cell::default(),
+            clicks: ClickTracker::new(),
+        }
+    }
+
+    pub fn with_keymap(mut self, keymap: KeyMap) -> Self {
+        self.set_keymap(keymap);
+        self
+    }
+
+    pub fn set_keymap(&mut self, keymap: KeyMap) {
+        self.keymap = keymap;
+        self.keys.clear();
+    }
+
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.set_theme(theme);
+        self
+    }
+
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+
+    /// List the items numbered on plain lines and read the number of one
+    /// from `input`, for when there is no terminal.
+    pub(crate) fn prompt_plain(
+        &mut self,
+        input: &mut impl BufRead,
+        output: &mut impl Write,
+    ) -> Result<PromptResult<String>> {
+        let title = match self.subtitle.is_empty() {
+            true => self.title.clone(),
+            false => format!("{}\n{}", self.title, self.subtitle),
+        };
+        let names: Vec<&str> = self.items.iter().map(|item| item.name.as_str()).collect();
+        match fallback::choose(input, output, &title, &names)? {
+            Some(index) => {
+                self.selection_state.select(Some(index));
+                Ok(PromptResult::Submitted(self.items[index].name.clone()))
+            }
+            None => Ok(PromptResult::Cancelled),
         }
     }
 
@@ -51,71 +120,102 @@ impl Menu {
         terminal: &mut Terminal<B>,
         area: Rect,
         help_component: &mut HelpComponent,
-    ) {
-        terminal
-            .draw(|f| {
-                let chunks = Layout::default()
-                    .direction(Direction::Vertical)
-                    .constraints(
-                        [
-                            Constraint::Length(1),
-                            Constraint::Length(2),
-                            Constraint::Max(10),
-                            Constraint::Length(3),
-                        ]
-                        .as_ref(),
-                    )
-                    .split(area);
-
-                let title_widget = format!("{}", self.title);
-                let title = Paragraph::new(title_widget.as_str())
-                    .style(Style::default().add_modifier(Modifier::BOLD))
-                    .fg(Color::LightMagenta)
-                    .block(Block::default().borders(Borders::NONE));
-                f.render_widget(title, chunks[0]);
-
-                let subtitle_widget = format!("{}", self.subtitle);
-                let subtitle = Paragraph::new(subtitle_widget.as_str())
-                    .style(
-                        Style::default()
-                            .add_modifier(Modifier::BOLD)
-                            .fg(Color::DarkGray),
-                    )
-                    .block(Block::default().borders(Borders::NONE));
-                f.render_widget(subtitle, chunks[1]);
-
-                let items: Vec<ListItem> = self
-                    .items
-                    .iter()
-                    .map(|item| {
-                        let content = if item.selected {
-                            format!("✓ {}", item.name)
-                        } else {
-                            format!("  {}", item.name)
-                        };
-                        ListItem::new(content)
-                    })
-                    .collect();
-
-                //TODO: add color to symbol
-                let symbol = "> ";
-                let list = List::new(items)
-                    .block(Block::default().borders(Borders::NONE))
-                    .highlight_style(Style::default().add_modifier(Modifier::BOLD))
-                    .highlight_symbol(symbol)
-                    .scroll_padding(4);
-                f.render_stateful_widget(list, chunks[2], &mut self.selection_state.clone());
-                //TODO: calculate the area and render widget help_component under list
-                f.render_widget(help_component.clone(), chunks[3]);
+    ) -> Result<()> {
+        terminal.draw(|f| {
+            self.draw(f, area, help_component.clone());
+            tui::adapt_colors(f);
+        })?;
+        Ok(())
+    }
+
+    /// The help bar listing the menu's key bindings.
+    pub fn help_component(&self) -> HelpComponent {
+        HelpComponent::from_keymap(
+            &self.keymap,
+            &[
+                Action::Interrupt,
+                Action::Cancel,
+                Action::ToggleSelection,
+                Action::Submit,
+                Action::Down,
+                Action::Up,
+            ],
+        )
+        .with_pending(self.keys.pending())
+        .with_theme(self.theme)
+    }
+
+    fn draw(&self, f: &mut Frame, area: Rect, help_component: HelpComponent) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(
+                [
+                    Constraint::Length(1),
+                    Constraint::Length(2),
+                    Constraint::Max(10),
+                    Constraint::Length(3),
+                ]
+                .as_ref(),
+            )
+            .split(area);
+
+        // A blurred menu dims its title so the focused component stands out
+        let title_color = if self.focused {
+            self.theme.primary
+        } else {
+            self.theme.muted
+        };
+        let title = Paragraph::new(self.title.as_str())
+            .style(Style::default().add_modifier(Modifier::BOLD))
+            .fg(title_color.resolve())
+            .block(Block::default().borders(Borders::NONE));
+        f.render_widget(title, chunks[0]);
+
+        let subtitle = Paragraph::new(self.subtitle.as_str())
+            .style(
+                Style::default()
+                    .add_modifier(Modifier::BOLD)
+                    .fg(self.theme.muted.resolve()),
+            )
+            .block(Block::default().borders(Borders::NONE));
+        f.render_widget(subtitle, chunks[1]);
+
+        let items: Vec<ListItem> = self
+            .items
+            .iter()
+            .map(|item| {
+                let content = if item.selected {
+                    format!("✓ {}", item.name)
+                } else {
+                    format!("  {}", item.name)
+                };
+                ListItem::new(content)
             })
-            .unwrap();
+            .collect();
+
+        //TODO: add color to symbol
+        let symbol = "> ";
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::NONE))
+            .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+            .highlight_symbol(symbol)
+            .scroll_padding(4);
+        let mut state = self.selection_state.clone();
+        f.render_stateful_widget(list, chunks[2], &mut state);
+        self.list_area.set(chunks[2]);
+        self.list_offset.set(state.offset());
+        //TODO: calculate the area and render widget help_component under list
+        f.render_widget(help_component, chunks[3]);
     }
 
     pub fn up(&mut self) {
+        let Some(last) = self.items.len().checked_sub(1) else {
+            return;
+        };
         let i = match self.selection_state.selected() {
             Some(i) => {
                 if i == 0 {
-                    self.items.len() - 1
+                    last
                 } else {
                     i - 1
                 }
@@ -126,9 +226,12 @@ impl Menu {
     }
 
     pub fn down(&mut self) {
+        let Some(last) = self.items.len().checked_sub(1) else {
+            return;
+        };
         let i = match self.selection_state.selected() {
             Some(i) => {
-                if i >= self.items.len() - 1 {
+                if i >= last {
                     0
                 } else {
                     i + 1
@@ -139,74 +242,176 @@ impl Menu {
         self.selection_state.select(Some(i));
     }
 
+    /// Move the cursor to the `n`th item, counting from 1, or the last one.
+    pub fn select_nth(&mut self, n: Option<usize>) {
+        let last = self.items.len().saturating_sub(1);
+        let index = n.map_or(last, |n| n.saturating_sub(1).min(last));
+        self.selection_state.select(Some(index));
+    }
+
     pub fn toggle_selection(&mut self) {
-        if let Some(i) = self.selection_state.selected() {
-            self.items[i].selected = !self.items[i].selected;
+        if let Some(item) = self
+            .selection_state
+            .selected()
+            .and_then(|i| self.items.get_mut(i))
+        {
+            item.selected = !item.selected;
         }
     }
 
     // Add methods to handle key inputs: up, down, toggle selection, etc.
 }
 
-pub fn handle_menu_list(menu: &mut Menu, x: u16, y: u16) -> Option<String> {
+impl Menu {
+    /// Draw the component into `area`, for use inside a [`Program`](crate::program::Program).
+    pub fn view(&mut self, frame: &mut Frame, area: Rect) {
+        let help_component = self.help_component();
+        self.draw(frame, area, help_component);
+    }
+}
+
+impl Menu {
+    // The wheel moves the cursor, a click moves it to an item and a double
+    // click submits it
+    fn handle_mouse(&mut self, mouse: &MouseEvent) -> EventOutcome {
+        match mouse.kind {
+            MouseEventKind::ScrollUp => self.up(),
+            MouseEventKind::ScrollDown => self.down(),
+            _ => {
+                let Some((_, row)) = mouse::left_click(mouse, self.list_area.get()) else {
+                    return EventOutcome::Ignored;
+                };
+                let index = self.list_offset.get() + row as usize;
+                if index >= self.items.len() {
+                    return EventOutcome::Ignored;
+                }
+                self.selection_state.select(Some(index));
+                if self.clicks.click(mouse.column, mouse.row) {
+                    return EventOutcome::Submitted;
+                }
+            }
+        }
+        EventOutcome::Consumed
+    }
+}
+
+impl HandleEvent for Menu {
+    fn handle_event(&mut self, event: &Event) -> EventOutcome {
+        let key = match event {
+            Event::Key(key) => key,
+            Event::Mouse(mouse) => return self.handle_mouse(mouse),
+            // A redraw lays the menu out for the new size
+            Event::Resize(..) => return EventOutcome::Consumed,
+            _ => return EventOutcome::Ignored,
+        };
+        let (action, count) = match self.keys.resolve(&self.keymap, key) {
+            Resolved::Action { action, count } => (action, count),
+            Resolved::Pending => return EventOutcome::Consumed,
+            Resolved::Unbound => return EventOutcome::Ignored,
+        };
+        match action {
+            Action::Interrupt => return EventOutcome::Interrupted,
+            Action::ToggleSelection => self.toggle_selection(),
+            Action::Cancel => return EventOutcome::Cancelled,
+            Action::Down => (0..count.unwrap_or(1)).for_each(|_| self.down()),
+            Action::Up => (0..count.unwrap_or(1)).for_each(|_| self.up()),
+            Action::Top => self.selection_state.select(Some(0)),
+            Action::Bottom => self.select_nth(count),
+            Action::Submit if !self.items.is_empty() => return EventOutcome::Submitted,
+            _ => return EventOutcome::Ignored,
+        }
+        EventOutcome::Consumed
+    }
+}
+
+impl Focusable for Menu {
+    fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        Menu::view(self, frame, area);
+    }
+}
+
+impl InlinePrompt for Menu {
+    type Value = String;
+
+    fn inline_height(&self) -> u16 {
+        // Title, subtitle, at most ten items and the help bar
+        (3 + self.items.len().min(10) + 3) as u16
+    }
+
+    fn submitted_value(&self) -> Option<String> {
+        let selected = self.selection_state.selected()?;
+        self.items.get(selected).map(|item| item.name.clone())
+    }
+
+    fn fallback_value(
+        &mut self,
+        input: &mut impl BufRead,
+        output: &mut impl Write,
+    ) -> Result<PromptResult<String>> {
+        self.prompt_plain(input, output)
+    }
+
+    fn summary(&self, value: &String) -> String {
+        value.clone()
+    }
+
+    fn theme(&self) -> Theme {
+        self.theme
+    }
+}
+
+impl<M> Model<M> for Menu {
+    fn update(&mut self, msg: Msg<M>) -> Cmd<M> {
+        if let Some(event) = msg.to_event() {
+            self.handle_event(&event);
+        }
+        Cmd::none()
+    }
+
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        Menu::view(self, frame, area);
+    }
+}
+
+pub fn handle_menu_list(menu: &mut Menu, x: u16, y: u16) -> Result<PromptResult<String>> {
+    if !fallback::is_interactive() {
+        return menu.prompt_plain(&mut io::stdin().lock(), &mut io::stdout());
+    }
+    // Mouse capture lets clicks pick items and the wheel scroll
+    let _session =
+        tui::TerminalSession::with_options(tui::SessionOptions::new().mouse_capture(true))?;
     // Render the menu
     let stdout = io::stdout();
     let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend).unwrap();
+    let mut terminal = Terminal::new(backend)?;
     loop {
-        terminal.clear().unwrap();
-
-        let commands = vec![
-            CommandInfo::new(KeyCode::Char('c'), KeyModifiers::CONTROL),
-            CommandInfo::new(KeyCode::Char('q'), KeyModifiers::NONE),
-            CommandInfo::new(KeyCode::Enter, KeyModifiers::NONE),
-            CommandInfo::new(KeyCode::Down, KeyModifiers::NONE),
-            CommandInfo::new(KeyCode::Up, KeyModifiers::NONE),
-        ];
-
-        let mut help_component = HelpComponent::new(commands, vec![]);
-
-        menu.render(&mut terminal, Rect::new(x, y, 40, 50), &mut help_component);
-
-        match read().unwrap() {
-            Event::Key(KeyEvent {
-                code: KeyCode::Char(c),
-                modifiers,
-                ..
-            }) => {
-                if c == 'j' {
-                    menu.down();
-                }
-                if c == 'k' {
-                    menu.up();
-                }
-                if c == 'q' {
-                    return None;
-                }
-                if modifiers.contains(KeyModifiers::CONTROL) && c == 't' {
-                    menu.toggle_selection();
-                }
-                if modifiers.contains(KeyModifiers::CONTROL) && c == 'c' {
-                    return None;
-                }
-            }
-            Event::Key(KeyEvent {
-                code: KeyCode::Up, ..
-            }) => menu.up(),
-            Event::Key(KeyEvent {
-                code: KeyCode::Down,
-                ..
-            }) => menu.down(),
-            Event::Key(KeyEvent {
-                code: KeyCode::Enter,
-                ..
-            }) => {
-                if let Some(i) = menu.selection_state.selected() {
-                    return Some(menu.items[i].name.clone());
+        terminal.clear()?;
+
+        let mut help_component = menu.help_component();
+
+        // Keep within the terminal, which may have been resized since the last draw
+        let size = terminal.size()?;
+        let area = Rect::new(x, y, 40, size.height.saturating_sub(y)).intersection(size);
+        menu.render(&mut terminal, area, &mut help_component)?;
+
+        match menu.handle_event(&read()?) {
+            EventOutcome::Submitted => {
+                let selected = menu.selection_state.selected().unwrap_or_default();
+                if let Some(item) = menu.items.get(selected) {
+                    return Ok(PromptResult::Submitted(item.name.clone()));
                 }
             }
-
-            _ => {}
+            EventOutcome::Cancelled => return Ok(PromptResult::Cancelled),
+            EventOutcome::Interrupted => return Ok(PromptResult::Interrupted),
+            EventOutcome::Consumed | EventOutcome::Ignored => {}
         }
     }
 }
@@ -214,6 +419,7 @@ pub fn handle_menu_list(menu: &mut Menu, x: u16, y: u16) -> Option<String> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 
     #[test]
     fn initializes_correctly() {
@@ -242,6 +448,38 @@ mod tests {
         assert_eq!(menu.selection_state.selected(), Some(1));
     }
 
+    #[test]
+    fn handles_events() {
+        let mut menu = Menu::new(
+            "Title".to_string(),
+            "Subtitle".to_string(),
+            vec!["Option 1".to_string(), "Option 2".to_string()],
+        );
+        let key = |code, modifiers| Event::Key(KeyEvent::new(code, modifiers));
+
+        assert_eq!(
+            menu.handle_event(&key(KeyCode::Char('j'), KeyModifiers::NONE)),
+            EventOutcome::Consumed
+        );
+        assert_eq!(
+            menu.handle_event(&key(KeyCode::Char('t'), KeyModifiers::CONTROL)),
+            EventOutcome::Consumed
+        );
+        assert!(menu.items[1].selected);
+        assert_eq!(
+            menu.handle_event(&key(KeyCode::Tab, KeyModifiers::NONE)),
+            EventOutcome::Ignored
+        );
+        assert_eq!(
+            menu.handle_event(&key(KeyCode::Enter, KeyModifiers::NONE)),
+            EventOutcome::Submitted
+        );
+        assert_eq!(
+            menu.handle_event(&key(KeyCode::Char('q'), KeyModifiers::NONE)),
+            EventOutcome::Cancelled
+        );
+    }
+
     #[test]
     fn selects_item_correctly() {
         let mut menu = Menu::new(
@@ -251,6 +489,26 @@ mod tests {
         );
         menu.toggle_selection();
 
-        assert_eq!(menu.items[0].selected, true);
+        assert!(menu.items[0].selected);
+    }
+
+    #[test]
+    fn navigates_an_empty_menu() {
+        let mut menu = Menu::new("Title".to_string(), "Subtitle".to_string(), vec![]);
+        let key = |code| Event::Key(KeyEvent::new(code, KeyModifiers::NONE));
+        menu.handle_event(&key(KeyCode::Down));
+        menu.handle_event(&key(KeyCode::Up));
+        menu.down();
+        menu.up();
+        assert_eq!(menu.selection_state.selected(), Some(0));
+        menu.toggle_selection();
+        menu.handle_event(&Event::Key(KeyEvent::new(
+            KeyCode::Char('t'),
+            KeyModifiers::CONTROL,
+        )));
+        assert_eq!(
+            menu.handle_event(&key(KeyCode::Enter)),
+            EventOutcome::Ignored
+        );
     }
 }
@@ -0,0 +1,65 @@
+use std::time::{Duration, Instant};
+
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::{Position, Rect};
+
+/// How quickly a second click on the same cell must follow the first to
+/// count as a double click.
+pub const DOUBLE_CLICK_TIME: Duration = Duration::from_millis(400);
+
+/// Tells double clicks apart from single ones, since terminals only report
+/// each press on its own.
+#[derive(Clone, Debug, Default)]
+pub struct ClickTracker {
+    last: Option<(Instant, u16, u16)>,
+}
+
+impl ClickTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a press at `(column, row)` and return whether it completes a
+    /// double click.
+    pub fn click(&mut self, column: u16, row: u16) -> bool {
+        let now = Instant::now();
+        let double = self.last.is_some_and(|(at, x, y)| {
+            (x, y) == (column, row) && now.duration_since(at) <= DOUBLE_CLICK_TIME
+        });
+        // A third click starts over instead of making another double click
+        self.last = (!double).then_some((now, column, row));
+        double
+    }
+}
+
+/// The position of a left button press inside `area`, relative to the
+/// area's top left corner.
+pub fn left_click(mouse: &MouseEvent, area: Rect) -> Option<(u16, u16)> {
+    let pressed = mouse.kind == MouseEventKind::Down(MouseButton::Left);
+    let inside = area.contains(Position::new(mouse.column, mouse.row));
+    (pressed && inside).then(|| (mouse.column - area.x, mouse.row - area.y))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+
+    #[test]
+    fn detects_double_clicks_and_hits() {
+        let mut clicks = ClickTracker::new();
+        assert!(!clicks.click(3, 4));
+        assert!(clicks.click(3, 4));
+        assert!(!clicks.click(3, 4));
+        assert!(!clicks.click(5, 4));
+
+        let mouse = MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column: 12,
+            row: 6,
+            modifiers: KeyModifiers::NONE,
+        };
+        assert_eq!(left_click(&mouse, Rect::new(10, 5, 4, 2)), Some((2, 1)));
+        assert_eq!(left_click(&mouse, Rect::new(0, 0, 4, 2)), None);
+    }
+}
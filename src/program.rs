@@ -0,0 +1,378 @@
+use std::{
+    collections::VecDeque,
+    fmt, io,
+    marker::PhantomData,
+    sync::mpsc::{self, Receiver, Sender},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crossterm::event::{self, Event, KeyEvent, MouseEvent};
+use ratatui::{
+    backend::{Backend, CrosstermBackend},
+    layout::Rect,
+    Frame, Terminal,
+};
+
+use crate::{
+    colors,
+    tui::{self, SessionOptions, TerminalSession},
+    Result,
+};
+
+// How long the runtime waits for terminal input before checking for ticks and
+// messages produced by background commands.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// A message delivered to [`Model::update`].
+///
+/// Terminal events are translated into `Key`, `Mouse`, `Resize` and `Paste`, the
+/// runtime emits `Tick` at the program's tick rate, and `App` carries the
+/// application's own messages, usually produced by a [`Cmd`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Msg<M = ()> {
+    Key(KeyEvent),
+    Mouse(MouseEvent),
+    Resize(u16, u16),
+    Paste(String),
+    Tick,
+    App(M),
+}
+
+impl<M> Msg<M> {
+    /// Translate a crossterm event, returning `None` for events the runtime
+    /// does not forward (focus changes).
+    pub fn from_event(event: Event) -> Option<Self> {
+        match event {
+            Event::Key(key) => Some(Msg::Key(key)),
+            Event::Mouse(mouse) => Some(Msg::Mouse(mouse)),
+            Event::Resize(width, height) => Some(Msg::Resize(width, height)),
+            Event::Paste(text) => Some(Msg::Paste(text)),
+            _ => None,
+        }
+    }
+
+    /// The terminal event this message was built from, if any.
+    pub fn to_event(&self) -> Option<Event> {
+        match self {
+            Msg::Key(key) => Some(Event::Key(*key)),
+            Msg::Mouse(mouse) => Some(Event::Mouse(*mouse)),
+            Msg::Resize(width, height) => Some(Event::Resize(*width, *height)),
+            Msg::Paste(text) => Some(Event::Paste(text.clone())),
+            _ => None,
+        }
+    }
+}
+
+/// A side effect requested by [`Model::init`] or [`Model::update`].
+#[derive(Default)]
+pub enum Cmd<M = ()> {
+    /// Do nothing.
+    #[default]
+    None,
+    /// Stop the program after the current update.
+    Quit,
+    /// Deliver a message to the model on the next update.
+    Msg(M),
+    /// Run several commands.
+    Batch(Vec<Cmd<M>>),
+    /// Run a closure on a background thread and deliver its result.
+    Perform(Box<dyn FnOnce() -> M + Send + 'static>),
+}
+
+impl<M> Cmd<M> {
+    pub fn none() -> Self {
+        Cmd::None
+    }
+
+    pub fn quit() -> Self {
+        Cmd::Quit
+    }
+
+    pub fn msg(msg: M) -> Self {
+        Cmd::Msg(msg)
+    }
+
+    pub fn batch(cmds: impl IntoIterator<Item = Cmd<M>>) -> Self {
+        Cmd::Batch(cmds.into_iter().collect())
+    }
+
+    pub fn perform<F>(f: F) -> Self
+    where
+        F: FnOnce() -> M + Send + 'static,
+    {
+        Cmd::Perform(Box::new(f))
+    }
+
+    pub fn is_none(&self) -> bool {
+        matches!(self, Cmd::None)
+    }
+
+    /// Convert the message type, typically to lift a sub-model's command into
+    /// its parent's message type.
+    pub fn map<N, F>(self, f: F) -> Cmd<N>
+    where
+        M: 'static,
+        F: Fn(M) -> N + Clone + Send + 'static,
+    {
+        match self {
+            Cmd::None => Cmd::None,
+            Cmd::Quit => Cmd::Quit,
+            Cmd::Msg(msg) => Cmd::Msg(f(msg)),
+            Cmd::Batch(cmds) => {
+                Cmd::Batch(cmds.into_iter().map(|cmd| cmd.map(f.clone())).collect())
+            }
+            Cmd::Perform(task) => Cmd::Perform(Box::new(move || f(task()))),
+        }
+    }
+}
+
+impl<M: fmt::Debug> fmt::Debug for Cmd<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Cmd::None => write!(f, "None"),
+            Cmd::Quit => write!(f, "Quit"),
+            Cmd::Msg(msg) => f.debug_tuple("Msg").field(msg).finish(),
+            Cmd::Batch(cmds) => f.debug_tuple("Batch").field(cmds).finish(),
+            Cmd::Perform(_) => write!(f, "Perform(..)"),
+        }
+    }
+}
+
+/// The state of an application or of one of its components.
+///
+/// Every built-in component implements `Model<M>` for any message type, so it
+/// can be embedded in a parent model and driven by forwarding messages to it.
+pub trait Model<M = ()> {
+    /// Called once before the first frame is drawn.
+    fn init(&mut self) -> Cmd<M> {
+        Cmd::None
+    }
+
+    /// Apply a message and return any follow-up command.
+    fn update(&mut self, msg: Msg<M>) -> Cmd<M>;
+
+    /// Draw the model into `area` of the frame.
+    fn view(&mut self, frame: &mut Frame, area: Rect);
+}
+
+/// Runs a [`Model`] against the terminal until it returns [`Cmd::Quit`].
+pub struct Program<T, M = ()> {
+    model: T,
+    tick_rate: Option<Duration>,
+    _msg: PhantomData<fn(M)>,
+}
+
+impl<T, M> Program<T, M>
+where
+    T: Model<M>,
+    M: Send + 'static,
+{
+    pub fn new(model: T) -> Self {
+        Self {
+            model,
+            tick_rate: None,
+            _msg: PhantomData,
+        }
+    }
+
+    /// Deliver [`Msg::Tick`] to the model at the given interval.
+    pub fn with_tick_rate(mut self, tick_rate: Duration) -> Self {
+        self.tick_rate = Some(tick_rate);
+        self
+    }
+
+    /// Take over the terminal and run until the model quits, returning the
+    /// final model so the caller can read its state.
+    pub fn run(mut self) -> Result<T> {
+        let session = TerminalSession::with_options(
+            SessionOptions::new()
+                .alternate_screen(true)
+                .mouse_capture(true)
+                .bracketed_paste(true),
+        )?;
+        let mut terminal = Terminal::new(CrosstermBackend::new(io::stdout()))?;
+        let result = self.run_with(&mut terminal, |timeout| {
+            if event::poll(timeout)? {
+                event::read().map(Some)
+            } else {
+                Ok(None)
+            }
+        });
+        session.restore()?;
+        result.map(|_| self.model)
+    }
+
+    fn run_with<B, E>(&mut self, terminal: &mut Terminal<B>, mut next_event: E) -> Result<()>
+    where
+        B: Backend,
+        E: FnMut(Duration) -> io::Result<Option<Event>>,
+    {
+        // Drawing only reads the background, so know it before the first frame
+        colors::detect_background();
+        let (sender, receiver) = mpsc::channel();
+        let mut queue = VecDeque::new();
+
+        let cmd = self.model.init();
+        if execute(cmd, &sender, &mut queue) {
+            return Ok(());
+        }
+
+        let mut last_tick = Instant::now();
+        let mut dirty = true;
+        loop {
+            if dirty {
+                terminal.draw(|frame| {
+                    let area = frame.size();
+                    self.model.view(frame, area);
+                    tui::adapt_colors(frame);
+                })?;
+                dirty = false;
+            }
+
+            drain(&receiver, &mut queue);
+            if queue.is_empty() {
+                let timeout = match self.tick_rate {
+                    Some(rate) => rate.saturating_sub(last_tick.elapsed()).min(POLL_INTERVAL),
+                    None => POLL_INTERVAL,
+                };
+                if let Some(msg) = next_event(timeout)?.and_then(Msg::from_event) {
+                    queue.push_back(msg);
+                }
+                if let Some(rate) = self.tick_rate {
+                    if last_tick.elapsed() >= rate {
+                        queue.push_back(Msg::Tick);
+                        last_tick = Instant::now();
+                    }
+                }
+            }
+
+            while let Some(msg) = queue.pop_front() {
+                dirty = true;
+                let cmd = self.model.update(msg);
+                if execute(cmd, &sender, &mut queue) {
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+fn drain<M>(receiver: &Receiver<M>, queue: &mut VecDeque<Msg<M>>) {
+    while let Ok(msg) = receiver.try_recv() {
+        queue.push_back(Msg::App(msg));
+    }
+}
+
+// Returns true when the command asks the program to quit.
+fn execute<M: Send + 'static>(
+    cmd: Cmd<M>,
+    sender: &Sender<M>,
+    queue: &mut VecDeque<Msg<M>>,
+) -> bool {
+    match cmd {
+        Cmd::None => false,
+        Cmd::Quit => true,
+        Cmd::Msg(msg) => {
+            queue.push_back(Msg::App(msg));
+            false
+        }
+        Cmd::Batch(cmds) => {
+            // Every command runs, even after one of them asks to quit.
+            let mut quit = false;
+            for cmd in cmds {
+                quit |= execute(cmd, sender, queue);
+            }
+            quit
+        }
+        Cmd::Perform(task) => {
+            let sender = sender.clone();
+            thread::spawn(move || {
+                // The program may already have exited; the result is dropped then.
+                let _ = sender.send(task());
+            });
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::{KeyCode, KeyModifiers};
+    use ratatui::{backend::TestBackend, widgets::Paragraph};
+
+    #[derive(Debug, PartialEq)]
+    enum CounterMsg {
+        Add(i32),
+    }
+
+    struct Counter {
+        count: i32,
+    }
+
+    impl Model<CounterMsg> for Counter {
+        fn init(&mut self) -> Cmd<CounterMsg> {
+            Cmd::msg(CounterMsg::Add(10))
+        }
+
+        fn update(&mut self, msg: Msg<CounterMsg>) -> Cmd<CounterMsg> {
+            match msg {
+                Msg::Key(key) if key.code == KeyCode::Char('+') => Cmd::msg(CounterMsg::Add(1)),
+                Msg::Key(key) if key.code == KeyCode::Char('q') => Cmd::quit(),
+                Msg::App(CounterMsg::Add(n)) => {
+                    self.count += n;
+                    Cmd::none()
+                }
+                _ => Cmd::none(),
+            }
+        }
+
+        fn view(&mut self, frame: &mut Frame, area: Rect) {
+            frame.render_widget(Paragraph::new(self.count.to_string()), area);
+        }
+    }
+
+    fn key(c: char) -> Event {
+        Event::Key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE))
+    }
+
+    #[test]
+    fn runs_until_quit() {
+        let mut terminal = Terminal::new(TestBackend::new(10, 1)).unwrap();
+        let mut program = Program::new(Counter { count: 0 });
+        let mut events = VecDeque::from([key('+'), key('+'), key('q')]);
+
+        program
+            .run_with(&mut terminal, |_| Ok(events.pop_front()))
+            .unwrap();
+
+        assert_eq!(program.model.count, 12);
+        terminal
+            .backend()
+            .assert_buffer(&ratatui::buffer::Buffer::with_lines(vec!["12        "]));
+    }
+
+    #[test]
+    fn perform_delivers_result() {
+        let (sender, receiver) = mpsc::channel();
+        let mut queue = VecDeque::new();
+        assert!(!execute(Cmd::perform(|| 7), &sender, &mut queue));
+        assert_eq!(receiver.recv().unwrap(), 7);
+    }
+
+    #[test]
+    fn batch_quits_if_any_command_quits() {
+        let (sender, _receiver) = mpsc::channel();
+        let mut queue = VecDeque::new();
+        let cmd = Cmd::batch([Cmd::msg(1), Cmd::quit(), Cmd::msg(2)]);
+        assert!(execute(cmd, &sender, &mut queue));
+        assert_eq!(queue, VecDeque::from([Msg::App(1), Msg::App(2)]));
+    }
+
+    #[test]
+    fn maps_commands() {
+        let cmd = Cmd::msg(2).map(|n: i32| n * 10);
+        assert!(matches!(cmd, Cmd::Msg(20)));
+    }
+}
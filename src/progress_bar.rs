@@ -1,12 +1,24 @@
-use crossterm::{
-    cursor::{Hide, MoveTo},
-    execute,
-    style::{Color, Print, ResetColor, SetForegroundColor},
-    terminal::{Clear, ClearType},
+use crossterm::{cursor::Hide, execute, style::Color};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Style,
+    text::{Line, Span},
+    widgets::Widget,
+    Frame,
+};
+use std::{
+    io::{stdout, IsTerminal, Write},
+    time::Instant,
 };
-use std::io::{stdout, Write};
 
-use crate::colors::blend_color;
+use crate::{
+    colors::blend_color,
+    fallback,
+    program::{Cmd, Model, Msg},
+    theme::Theme,
+    tui, Result,
+};
 
 pub struct ProgressBar {
     progress: f32,
@@ -14,6 +26,9 @@ pub struct ProgressBar {
     start_color: Color,
     end_color: Color,
     prefix: String,
+    theme: Theme,
+    // When a status line was last printed without a terminal, and its percentage
+    reported: Option<(Instant, u32)>,
 }
 
 impl ProgressBar {
@@ -30,62 +45,127 @@ impl ProgressBar {
             start_color,
             end_color,
             prefix,
+            theme: Theme::default(),
+            reported: None,
         }
     }
 
-    pub fn update(&mut self, progress: f32, x: u16, y: u16) {
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.set_theme(theme);
+        self
+    }
+
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+
+    pub fn set_progress(&mut self, progress: f32) {
         self.progress = progress;
-        self.render(x, y);
     }
 
-    fn render(&self, x: u16, y: u16) {
-        let mut stdout = stdout();
+    /// Set the progress and redraw the bar. When stdout is not a terminal a
+    /// plain status line is printed instead, at most once per
+    /// [`STATUS_INTERVAL`](fallback::STATUS_INTERVAL) and on completion.
+    pub fn update(&mut self, progress: f32, x: u16, y: u16) -> Result<()> {
+        self.progress = progress;
+        if !stdout().is_terminal() {
+            return self.report(&mut stdout());
+        }
+        self.render(x, y)
+    }
 
-        execute!(stdout, MoveTo(x, y), Clear(ClearType::CurrentLine)).unwrap();
+    fn report(&mut self, output: &mut impl Write) -> Result<()> {
+        let percent = (self.progress.clamp(0.0, 1.0) * 100.0).round() as u32;
+        let due = match self.reported {
+            None => true,
+            Some((_, reported)) if reported == percent => false,
+            Some((at, _)) => percent == 100 || at.elapsed() >= fallback::STATUS_INTERVAL,
+        };
+        if due {
+            match self.prefix.is_empty() {
+                true => writeln!(output, "{}%", percent)?,
+                false => writeln!(output, "{} {}%", self.prefix, percent)?,
+            }
+            self.reported = Some((Instant::now(), percent));
+        }
+        Ok(())
+    }
+
+    fn render(&self, x: u16, y: u16) -> Result<()> {
+        let area = tui::area_at(x, y, 1)?;
+        tui::draw_widget(self, area)?;
         // Hide the cursor to avoid flicker
-        execute!(stdout, Hide).unwrap();
+        execute!(stdout(), Hide)?;
+        Ok(())
+    }
 
-        let show_prefix = !self.prefix.is_empty();
+    /// Draw the component into `area`, for use inside a [`Program`](crate::program::Program).
+    pub fn view(&mut self, frame: &mut Frame, area: Rect) {
+        frame.render_widget(&*self, area);
+    }
+}
 
-        // Build the progress bar string
+impl Widget for &ProgressBar {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let mut spans = Vec::new();
+        if !self.prefix.is_empty() {
+            spans.push(Span::raw(format!("{} ", self.prefix)));
+        }
+        // Build the progress bar, blending the filled part from start to end color
         for i in 0..self.length {
-            if show_prefix {
-                let print_prefix = format!("{} ", self.prefix.as_str());
-                execute!(stdout, MoveTo(x, y), Print(print_prefix),).unwrap();
-            }
             let gradient_ratio = i as f32 / self.length as f32;
             let color = if gradient_ratio < self.progress {
-                blend_color(self.start_color, self.end_color, gradient_ratio)
+                blend_color(self.start_color, self.end_color, gradient_ratio).into()
             } else {
-                Color::DarkGrey // Background color of the unfilled part
+                self.theme.track.resolve() // Background color of the unfilled part
             };
-
-            execute!(
-                stdout,
-                MoveTo(x + i, y),
-                SetForegroundColor(color),
-                Print("▇")
-            )
-            .unwrap();
+            spans.push(Span::styled("▇", Style::default().fg(color)));
         }
+        spans.push(Span::styled(
+            format!("  {:.0}%", self.progress * 100.0),
+            Style::default().fg(self.theme.text.resolve()),
+        ));
+        Line::from(spans).render(area, buf);
+    }
+}
 
-        let percentage = format!(" {:.0}%", self.progress * 100.0);
-
-        execute!(
-            stdout,
-            MoveTo(x + self.length + 1, y),
-            SetForegroundColor(Color::White),
-            Print(percentage),
-            ResetColor,
-            Hide
-        )
-        .unwrap();
+impl<M> Model<M> for ProgressBar {
+    fn update(&mut self, _msg: Msg<M>) -> Cmd<M> {
+        // Progress is driven by the owner through `set_progress`
+        Cmd::none()
+    }
 
-        stdout.flush().unwrap(); // Ensure all changes are flushed and visible
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        ProgressBar::view(self, frame, area);
     }
 }
 
-pub fn handle_progress_bar(progress_bar: &mut ProgressBar, progress: f32, x: u16, y: u16) {
-    progress_bar.update(progress, x, y);
+pub fn handle_progress_bar(
+    progress_bar: &mut ProgressBar,
+    progress: f32,
+    x: u16,
+    y: u16,
+) -> Result<()> {
+    progress_bar.update(progress, x, y)
     // progress_bar.render(x, y);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_status_lines_without_a_terminal() {
+        let mut bar = ProgressBar::new("Copying".into(), 0.0, 10, Color::Red, Color::Blue);
+        let mut output = Vec::new();
+        for progress in [0.0, 0.25, 0.5, 1.0, 1.0] {
+            bar.set_progress(progress);
+            bar.report(&mut output).unwrap();
+        }
+        // Updates within the interval are skipped, but completion never is
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "Copying 0%\nCopying 100%\n"
+        );
+    }
+}
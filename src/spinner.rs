@@ -1,23 +1,21 @@
-use crossterm::cursor::MoveTo;
 use crossterm::event::{read, Event, KeyCode, KeyEvent, KeyModifiers};
-use crossterm::style::{Color, Print, ResetColor};
-use crossterm::terminal::{Clear, ClearType};
-use crossterm::{execute};
+use crossterm::style::Color;
 use lazy_static::lazy_static;
 use maplit::{self, hashmap};
 use std::collections::HashMap;
-use std::io::{stdout, Write};
+use std::io::{self, BufRead, IsTerminal, Write};
 use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-fn color_to_ansi(color: Color) -> String {
-    match color {
-        Color::Rgb { r, g, b } => format!("\x1b[38;2;{};{};{}m", r, g, b),
-        // Handle other color cases if needed
-        _ => String::new(), // Default case
-    }
-}
+use ratatui::{buffer::Buffer, layout::Rect, style::Style, text::Span, widgets::Widget, Frame};
+
+use crate::{
+    event::PromptResult,
+    fallback,
+    program::{Cmd, Model, Msg},
+    tui, Error, Result,
+};
 
 pub struct SpinnerData {
     pub frames: Vec<&'static str>,
@@ -1205,15 +1203,17 @@ pub struct Spinner {
 }
 
 impl Spinner {
-    pub fn new(color: Color, message: String, style: &str) -> Self {
-        let spinner_data = SPINNERS.get(style).unwrap();
-        Self {
+    pub fn new(color: Color, message: String, style: &str) -> Result<Self> {
+        let spinner_data = SPINNERS
+            .get(style)
+            .ok_or_else(|| Error::UnknownSpinnerStyle(style.to_string()))?;
+        Ok(Self {
             is_running: Arc::new(Mutex::new(false)),
             frames: Arc::new(spinner_data.frames.clone()),
             current_frame: Arc::new(Mutex::new(0)),
             color,
             message,
-        }
+        })
     }
 
     pub fn start(&self, x: u16, y: u16) {
@@ -1225,6 +1225,14 @@ impl Spinner {
 
         *running.lock().unwrap() = true;
 
+        // Without a terminal to animate in, print the message now and then
+        if !io::stdout().is_terminal() {
+            thread::spawn(move || {
+                Spinner::report(&running, &current_frame, frames.len(), &message)
+            });
+            return;
+        }
+
         thread::spawn(move || {
             while *running.lock().unwrap() {
                 // Retrieve the current frame index
@@ -1234,8 +1242,12 @@ impl Spinner {
                     *frame = (*frame + 1) % frames.len(); // Update frame index
                     val
                 };
-                // Call the render function with the current state
-                Spinner::render(x, y, frames[frame_idx], color, &message);
+                // Call the render function with the current state, giving up
+                // once the terminal can no longer be written to
+                if Spinner::render(x, y, frames[frame_idx], color, &message).is_err() {
+                    *running.lock().unwrap() = false;
+                    break;
+                }
                 thread::sleep(Duration::from_millis(120));
             }
         });
@@ -1246,89 +1258,172 @@ impl Spinner {
         *running = false;
     }
 
+    // Print the message with the time taken so far every status interval,
+    // still stepping the frames for anyone drawing the spinner with `view`
+    fn report(running: &Mutex<bool>, current_frame: &Mutex<usize>, frames: usize, message: &str) {
+        let started = Instant::now();
+        let mut next_report = started;
+        while *running.lock().unwrap() {
+            {
+                let mut frame = current_frame.lock().unwrap();
+                *frame = (*frame + 1) % frames;
+            }
+            if Instant::now() >= next_report {
+                let line = match started.elapsed().as_secs() {
+                    0 => message.to_string(),
+                    secs => format!("{} ({}s)", message, secs),
+                };
+                if writeln!(io::stdout(), "{}", line).is_err() {
+                    *running.lock().unwrap() = false;
+                    break;
+                }
+                next_report += fallback::STATUS_INTERVAL;
+            }
+            thread::sleep(Duration::from_millis(120));
+        }
+    }
+
     // Moved render back to being an associated function of Spinner for clarity
-    fn render(x: u16, y: u16, frame: &str, color: Color, message: &str) {
-        let mut stdout = stdout();
+    fn render(x: u16, y: u16, frame: &str, color: Color, message: &str) -> Result<()> {
+        let area = tui::area_at(x, y, 1)?;
+        tui::draw_widget(Spinner::span(frame, color, message), area)
+    }
 
+    fn span<'a>(frame: &str, color: Color, message: &str) -> Span<'a> {
         let full_display = format!("{}  {}", frame, message);
-        execute!(
-            stdout,
-            MoveTo(x, y),
-            Clear(ClearType::CurrentLine),
-            Print(color_to_ansi(color)),
-            Print(full_display),
-            ResetColor
-        )
-        .unwrap();
-        stdout.flush().unwrap();
+        Span::styled(full_display, Style::default().fg(color.into()))
+    }
+
+    /// Draw the component into `area`, for use inside a [`Program`](crate::program::Program).
+    pub fn view(&mut self, frame: &mut Frame, area: Rect) {
+        frame.render_widget(&*self, area);
+    }
+}
+
+impl Widget for &Spinner {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let frame_idx = *self.current_frame.lock().unwrap();
+        Spinner::span(self.frames[frame_idx], self.color, &self.message).render(area, buf);
+    }
+}
+
+impl<M> Model<M> for Spinner {
+    fn update(&mut self, msg: Msg<M>) -> Cmd<M> {
+        if let Msg::Tick = msg {
+            let mut frame = self.current_frame.lock().unwrap();
+            *frame = (*frame + 1) % self.frames.len();
+        }
+        Cmd::none()
+    }
+
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        Spinner::view(self, frame, area);
     }
 }
 
-pub fn handle_spinner(spinner: &Spinner, x: u16, y: u16) {
+/// Animate the spinner until the user leaves it; it never submits a value.
+///
+/// Without a terminal the spinner prints status lines until a line is read
+/// from stdin or stdin ends, either of which leaves it like Esc and returns
+/// [`PromptResult::Cancelled`]. That waits for as long as stdin stays open,
+/// so to show progress beside your own work, call [`Spinner::start`] and
+/// [`Spinner::stop`] instead.
+pub fn handle_spinner(spinner: &Spinner, x: u16, y: u16) -> Result<PromptResult<()>> {
+    if !fallback::is_interactive() {
+        spinner.start(x, y);
+        let result = io::stdin().lock().read_line(&mut String::new());
+        spinner.stop();
+        result?;
+        return Ok(PromptResult::Cancelled);
+    }
+    let _session = tui::TerminalSession::new()?;
     spinner.start(x, y);
     loop {
-        match read().unwrap() {
+        match read()? {
             Event::Key(KeyEvent {
-                code: KeyCode::Char(c),
+                code: KeyCode::Char('c'),
                 modifiers,
                 ..
-            }) => {
-                if modifiers.contains(KeyModifiers::CONTROL) && c == 'c' {
-                    spinner.stop();
-                    break;
-                }
+            }) if modifiers.contains(KeyModifiers::CONTROL) => {
+                spinner.stop();
+                return Ok(PromptResult::Interrupted);
             }
             Event::Key(KeyEvent {
                 code: KeyCode::Esc, ..
             }) => {
                 spinner.stop();
-                break;
+                return Ok(PromptResult::Cancelled);
             }
             _ => {}
         }
     }
 }
 
-
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::sync::{Arc, Mutex};
 
     #[test]
     fn test_spinner_start_stop() {
         let spinner = Spinner::new(
-            Color::Rgb { r: 0, g: 255, b: 255 },
+            Color::Rgb {
+                r: 0,
+                g: 255,
+                b: 255,
+            },
             "Loading... Please wait.".to_string(),
             "FingerDance",
-        );
+        )
+        .unwrap();
 
         let is_running = spinner.is_running.clone();
-        assert!(!*is_running.lock().unwrap(), "Spinner should not be running initially");
+        assert!(
+            !*is_running.lock().unwrap(),
+            "Spinner should not be running initially"
+        );
 
         spinner.start(10, 10);
-        assert!(*is_running.lock().unwrap(), "Spinner should be running after start");
+        assert!(
+            *is_running.lock().unwrap(),
+            "Spinner should be running after start"
+        );
 
         // Simulate some runtime
         std::thread::sleep(std::time::Duration::from_millis(100));
         spinner.stop();
-        assert!(!*is_running.lock().unwrap(), "Spinner should stop after stop");
+        assert!(
+            !*is_running.lock().unwrap(),
+            "Spinner should stop after stop"
+        );
+    }
+
+    #[test]
+    fn test_unknown_style() {
+        let result = Spinner::new(Color::White, "Loading".to_string(), "NoSuchStyle");
+        assert!(matches!(result, Err(Error::UnknownSpinnerStyle(style)) if style == "NoSuchStyle"));
     }
 
     #[test]
     fn test_frame_change() {
         let spinner = Spinner::new(
-            Color::Rgb { r: 0, g: 255, b: 255 },
+            Color::Rgb {
+                r: 0,
+                g: 255,
+                b: 255,
+            },
             "Loading... Please wait.".to_string(),
             "FingerDance",
-        );
+        )
+        .unwrap();
 
         spinner.start(10, 10);
         let initial_frame = *spinner.current_frame.lock().unwrap();
         std::thread::sleep(std::time::Duration::from_millis(200)); // wait enough time for at least one change
-        assert_ne!(initial_frame, *spinner.current_frame.lock().unwrap(), "Frame should change after interval");
+        assert_ne!(
+            initial_frame,
+            *spinner.current_frame.lock().unwrap(),
+            "Frame should change after interval"
+        );
         spinner.stop();
     }
 }
-
-
@@ -1,18 +1,34 @@
-use crossterm::event::{poll, read, Event, KeyCode, KeyModifiers};
+use crossterm::event::{poll, read, Event};
 use ratatui::{
     backend::Backend,
     layout::{Alignment, Rect},
     prelude::{CrosstermBackend, Terminal},
     widgets::Paragraph,
+    Frame,
 };
 use std::{
-    io::stdout,
+    io::{stdin, stdout, Write},
     time::{Duration, Instant},
 };
 
+use crate::{
+    event::{EventOutcome, HandleEvent, PromptResult},
+    fallback,
+    keymap::{Action, Component, KeyMap},
+    program::{Cmd, Model, Msg},
+    tui, Result,
+};
+
 pub struct StopWatch {
     start_time: Instant,
     running: bool,
+    keymap: KeyMap,
+}
+
+impl Default for StopWatch {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl StopWatch {
@@ -20,9 +36,19 @@ impl StopWatch {
         Self {
             start_time: Instant::now(),
             running: true,
+            keymap: KeyMap::default_for(Component::StopWatch),
         }
     }
 
+    pub fn with_keymap(mut self, keymap: KeyMap) -> Self {
+        self.keymap = keymap;
+        self
+    }
+
+    pub fn set_keymap(&mut self, keymap: KeyMap) {
+        self.keymap = keymap;
+    }
+
     pub fn elapsed(&self) -> Duration {
         if self.running {
             self.start_time.elapsed()
@@ -65,41 +91,84 @@ impl StopWatch {
         }
     }
 
-    fn render<B: Backend>(&self, terminal: &mut Terminal<B>, x: u16, y: u16) {
-        terminal
-            .draw(|f| {
-                let size = f.size();
-                let area = Rect::new(x, y, size.width - x, 1);
+    fn render<B: Backend>(&self, terminal: &mut Terminal<B>, x: u16, y: u16) -> Result<()> {
+        terminal.draw(|f| {
+            let size = f.size();
+            let area = Rect::new(x, y, size.width.saturating_sub(x), 1).intersection(size);
+
+            let elapsed = self.elapsed();
+            let timer_text = StopWatch::format_duration(elapsed);
+
+            let paragraph = Paragraph::new(timer_text).alignment(Alignment::Left);
+            f.render_widget(paragraph, area);
+        })?;
+        Ok(())
+    }
+}
+
+impl StopWatch {
+    /// Draw the component into `area`, for use inside a [`Program`](crate::program::Program).
+    pub fn view(&mut self, frame: &mut Frame, area: Rect) {
+        let paragraph =
+            Paragraph::new(StopWatch::format_duration(self.elapsed())).alignment(Alignment::Left);
+        frame.render_widget(paragraph, area);
+    }
+}
+
+impl HandleEvent for StopWatch {
+    fn handle_event(&mut self, event: &Event) -> EventOutcome {
+        let Event::Key(key) = event else {
+            return EventOutcome::Ignored;
+        };
+        match self.keymap.action(key) {
+            Some(Action::Interrupt) => EventOutcome::Interrupted,
+            Some(Action::Cancel) => EventOutcome::Cancelled,
+            Some(Action::Submit) => EventOutcome::Submitted,
+            _ => EventOutcome::Ignored,
+        }
+    }
+}
 
-                let elapsed = self.elapsed();
-                let timer_text = StopWatch::format_duration(elapsed);
+impl<M> Model<M> for StopWatch {
+    fn update(&mut self, _msg: Msg<M>) -> Cmd<M> {
+        // The display is derived from the clock, so ticks only trigger a redraw
+        Cmd::none()
+    }
 
-                let paragraph = Paragraph::new(timer_text).alignment(Alignment::Left);
-                f.render_widget(paragraph, area);
-            })
-            .unwrap();
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        StopWatch::view(self, frame, area);
     }
 }
 
-pub fn handle_stopwatch(timer: &mut StopWatch, x: u16, y: u16) {
+/// Run the stopwatch until Enter stops it, returning the elapsed time.
+pub fn handle_stopwatch(timer: &mut StopWatch, x: u16, y: u16) -> Result<PromptResult<Duration>> {
+    // Without a terminal, a line of input stops the stopwatch
+    if !fallback::is_interactive() {
+        let mut output = stdout();
+        let line = fallback::read_line(&mut stdin().lock(), &mut output, "Press Enter to stop ")?;
+        if line.is_none() {
+            return Ok(PromptResult::Cancelled);
+        }
+        let elapsed = timer.elapsed();
+        writeln!(output, "{}", StopWatch::format_duration(elapsed))?;
+        return Ok(PromptResult::Submitted(elapsed));
+    }
+    let _session = tui::TerminalSession::new()?;
     let mut stdout = stdout();
     let backend = CrosstermBackend::new(&mut stdout);
-    let mut terminal = Terminal::new(backend).unwrap();
+    let mut terminal = Terminal::new(backend)?;
 
-    terminal.clear().unwrap();
+    terminal.clear()?;
     let tick_rate = Duration::from_millis(50); // Update every second
 
     loop {
-        timer.render(&mut terminal, x, y);
-        if poll(tick_rate).unwrap() {
-            if let Ok(Event::Key(key)) = read() {
-                if key.code == KeyCode::Esc
-                    || (key.code == KeyCode::Char('c')
-                        && key.modifiers.contains(KeyModifiers::CONTROL))
-                    || key.code == KeyCode::Char('q')
-                {
-                    break;
-                }
+        timer.render(&mut terminal, x, y)?;
+        if poll(tick_rate)? {
+            match timer.handle_event(&read()?) {
+                EventOutcome::Submitted => return Ok(PromptResult::Submitted(timer.elapsed())),
+                EventOutcome::Cancelled => return Ok(PromptResult::Cancelled),
+                EventOutcome::Interrupted => return Ok(PromptResult::Interrupted),
+                EventOutcome::Consumed | EventOutcome::Ignored => {}
             }
         }
     }
@@ -1,25 +1,75 @@
-use std::io::{stdout, Write};
+use std::{
+    cell::Cell,
+    cmp::Ordering,
+    io::{self, stdout, BufRead, Write},
+};
 
-use crate::colors::custom::PURPLE;
+use crate::{
+    event::{EventOutcome, HandleEvent, PromptResult},
+    fallback,
+    focus::Focusable,
+    inline::InlinePrompt,
+    keymap::{Action, Component, KeyBuffer, KeyMap, Resolved},
+    mouse::{self, ClickTracker},
+    program::{Cmd, Model, Msg},
+    theme::Theme,
+    tui, unicode, Result,
+};
 use crossterm::{
-    cursor::MoveTo,
-    event::{read, Event, KeyCode, KeyEvent, KeyModifiers},
+    event::{read, Event, MouseEvent, MouseEventKind},
     execute,
-    style::{Print, ResetColor, SetBackgroundColor},
     terminal::{Clear, ClearType},
 };
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    style::Style,
+    text::{Line, Span},
+    widgets::{Paragraph, StatefulWidget, Widget},
+    Frame,
+};
 
-// execute!(stdout, Clear(ClearType::All)).unwrap();
 pub struct Table {
     table_headers: Vec<String>,
     table_data: Vec<Vec<String>>,
-    selected_row: usize,
-    table_width: usize,
+    // Indexes into `table_data` in display order, which sorting rearranges
+    order: Vec<usize>,
+    sort: Option<(usize, bool)>,
     padding: usize,
     visible_lines: usize,
+    state: TableState,
+    keymap: KeyMap,
+    keys: KeyBuffer,
+    theme: Theme,
+    focused: bool,
+    area: Cell<Rect>,
+    clicks: ClickTracker,
+}
+
+/// Selection and scroll state of a [`Table`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TableState {
+    selected_row: usize,
     scroll_offset: usize,
 }
 
+impl TableState {
+    pub fn new(selected_row: usize) -> Self {
+        Self {
+            selected_row,
+            scroll_offset: 0,
+        }
+    }
+
+    pub fn selected_row(&self) -> usize {
+        self.selected_row
+    }
+
+    pub fn scroll_offset(&self) -> usize {
+        self.scroll_offset
+    }
+}
+
 impl Table {
     pub fn new(
         table_headers: Vec<String>,
@@ -28,22 +78,146 @@ impl Table {
         padding: usize,
         visible_lines: usize,
     ) -> Self {
-        let widths: Vec<usize> =
-            Self::calculate_column_widths(&table_headers, &table_data, padding);
-
-        let table_width = widths.iter().sum::<usize>() + widths.len() + 1;
-
         Table {
             table_headers,
+            order: (0..table_data.len()).collect(),
+            sort: None,
             table_data,
-            selected_row,
-            table_width,
             padding,
             visible_lines,
-            scroll_offset: 0,
+            state: TableState::new(selected_row),
+            keymap: KeyMap::default_for(Component::Table),
+            keys: KeyBuffer::new().counts(true),
+            theme: Theme::default(),
+            focused: true,
+            area: Cell::default(),
+            clicks: ClickTracker::new(),
         }
     }
 
+    pub fn with_keymap(mut self, keymap: KeyMap) -> Self {
+        self.set_keymap(keymap);
+        self
+    }
+
+    pub fn set_keymap(&mut self, keymap: KeyMap) {
+        self.keymap = keymap;
+        self.keys.clear();
+    }
+
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.set_theme(theme);
+        self
+    }
+
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+
+    pub fn state(&self) -> &TableState {
+        &self.state
+    }
+
+    /// Index into the rows passed to [`Table::new`] of the selected row,
+    /// which differs from [`TableState::selected_row`] once the table is sorted.
+    pub fn selected_index(&self) -> usize {
+        self.order
+            .get(self.state.selected_row)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// List the rows numbered on plain lines, with cells separated by `|`,
+    /// and read the number of one from `input`, for when there is no
+    /// terminal.
+    pub(crate) fn prompt_plain(
+        &mut self,
+        input: &mut impl BufRead,
+        output: &mut impl Write,
+    ) -> Result<PromptResult<usize>> {
+        let rows: Vec<String> = self
+            .order
+            .iter()
+            .map(|&index| self.table_data[index].join(" | "))
+            .collect();
+        let headers = self.table_headers.join(" | ");
+        match fallback::choose(input, output, &headers, &rows)? {
+            Some(row) => {
+                self.state.selected_row = row;
+                Ok(PromptResult::Submitted(self.selected_index()))
+            }
+            None => Ok(PromptResult::Cancelled),
+        }
+    }
+
+    /// Sort the rows by `column`, numerically if every cell in it is a
+    /// number. Sorting by the same column again reverses the order. The
+    /// selection stays on the same row.
+    pub fn sort_by_column(&mut self, column: usize) {
+        if column >= self.table_headers.len() {
+            return;
+        }
+        let descending = self.sort == Some((column, false));
+        let numbers: Option<Vec<f64>> = self
+            .table_data
+            .iter()
+            .map(|row| row[column].trim().parse().ok())
+            .collect();
+        let selected = self.selected_index();
+        let data = &self.table_data;
+        self.order.sort_by(|&a, &b| {
+            let ordering = match &numbers {
+                Some(numbers) => numbers[a]
+                    .partial_cmp(&numbers[b])
+                    .unwrap_or(Ordering::Equal),
+                None => data[a][column].cmp(&data[b][column]),
+            };
+            if descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+        self.sort = Some((column, descending));
+        let position = self.order.iter().position(|&row| row == selected);
+        self.select_nth(position.map(|position| position + 1));
+    }
+
+    // The wheel moves the selection, a click on a header sorts by its column,
+    // a click on a row selects it and a double click submits it
+    fn handle_mouse(&mut self, mouse: &MouseEvent) -> EventOutcome {
+        match mouse.kind {
+            MouseEventKind::ScrollUp => self.move_cursor_up(),
+            MouseEventKind::ScrollDown => self.move_cursor_down(),
+            _ => match mouse::left_click(mouse, self.area.get()) {
+                Some((column, 1)) => {
+                    let widths = self.fitted_column_widths(self.area.get().width);
+                    let mut start = 1;
+                    let hit = widths.iter().position(|width| {
+                        start += width;
+                        (column as usize) < start
+                    });
+                    match hit {
+                        Some(index) if column > 0 => self.sort_by_column(index),
+                        _ => return EventOutcome::Ignored,
+                    }
+                }
+                Some((_, row)) if (3..3 + self.visible_lines as u16).contains(&row) => {
+                    let index = self.state.scroll_offset + (row - 3) as usize;
+                    if index >= self.table_data.len() {
+                        return EventOutcome::Ignored;
+                    }
+                    self.state.selected_row = index;
+                    if self.clicks.click(mouse.column, mouse.row) {
+                        return EventOutcome::Submitted;
+                    }
+                }
+                _ => return EventOutcome::Ignored,
+            },
+        }
+        EventOutcome::Consumed
+    }
+
     fn calculate_column_widths(
         headers: &[String],
         data: &[Vec<String>],
@@ -53,223 +227,347 @@ impl Table {
 
         // Calculate max width for each column based on headers and data
         for (i, header) in headers.iter().enumerate() {
-            widths[i] = widths[i].max(header.len() + 2 * padding);
+            widths[i] = widths[i].max(unicode::width(header) + 2 * padding);
             for row in data {
-                if row[i].len() + 2 * padding > widths[i] {
-                    widths[i] = row[i].len() + 2 * padding;
+                let width = unicode::width(&row[i]) + 2 * padding;
+                if width > widths[i] {
+                    widths[i] = width;
                 }
             }
         }
         widths
     }
 
-    pub fn render(&self, x: u16, y: u16) {
-        let mut stdout = stdout();
-        execute!(stdout, Clear(ClearType::All)).unwrap();
+    // The column widths shrunk to fit `width` columns, borders included,
+    // taking from the widest column first. Cells narrower than their text
+    // are truncated, and a table that still doesn't fit is cut off.
+    fn fitted_column_widths(&self, width: u16) -> Vec<usize> {
+        let mut widths =
+            Self::calculate_column_widths(&self.table_headers, &self.table_data, self.padding);
+        let available = (width as usize).saturating_sub(2);
+        let narrowest = 2 * self.padding + 1;
+        while widths.iter().sum::<usize>() > available {
+            match widths.iter_mut().filter(|w| **w > narrowest).max() {
+                Some(widest) => *widest -= 1,
+                None => break,
+            }
+        }
+        widths
+    }
 
-        let column_widths =
+    /// Number of columns the table needs to show every cell in full.
+    pub fn width(&self) -> u16 {
+        let widths =
             Self::calculate_column_widths(&self.table_headers, &self.table_data, self.padding);
+        (widths.iter().sum::<usize>() + 2) as u16
+    }
 
-        // Render the top border
-        self.render_top_border(x, y, &column_widths);
+    /// Number of rows the table needs: borders, header and visible rows.
+    pub fn height(&self) -> u16 {
+        (4 + self.visible_lines) as u16
+    }
 
-        // Render table headers
-        self.render_headers(x, y + 1);
+    pub fn render(&self, x: u16, y: u16) -> Result<()> {
+        let mut stdout = stdout();
+        execute!(stdout, Clear(ClearType::All))?;
 
-        // Render separator line
-        self.render_horizontal_line(x, y + 2, self.table_width, &column_widths);
+        let area = tui::area_at(x, y, self.height())?;
+        tui::draw_widget(self, area)?;
 
-        // Render table rows with total lines to clear
-        let start_row = self.scroll_offset;
-        let end_row = usize::min(
-            self.scroll_offset + self.visible_lines,
-            self.table_data.len(),
-        );
+        //TODO: remove cursor
+        self.hide_cursor()?;
+        stdout.flush()?;
+        Ok(())
+    }
+
+    /// Draw the component into `area`, for use inside a [`Program`](crate::program::Program).
+    pub fn view(&mut self, frame: &mut Frame, area: Rect) {
+        frame.render_stateful_widget(&*self, area, &mut self.state.clone());
+    }
+
+    pub fn move_cursor_down(&mut self) {
+        if self.state.selected_row + 1 < self.table_data.len() {
+            self.state.selected_row += 1;
+            if self.state.selected_row >= self.state.scroll_offset + self.visible_lines {
+                self.state.scroll_offset += 1; // Ensure the new row is visible by adjusting the scroll offset.
+            }
+        }
+    }
 
-        for (idx, row) in self.table_data[start_row..end_row].iter().enumerate() {
-            let is_selected = (self.scroll_offset + idx) == self.selected_row;
-            self.render_row(x, y + 3 + idx as u16, row, is_selected);
+    pub fn move_cursor_up(&mut self) {
+        if self.state.selected_row > 0 {
+            self.state.selected_row -= 1;
+            if self.state.selected_row < self.state.scroll_offset {
+                self.state.scroll_offset -= 1; // Scroll up
+            }
         }
+    }
 
-        // Render the bottom border
-        self.render_bottom_border(x, y + 3, &column_widths);
+    /// Select the `n`th row, counting from 1, or the last one.
+    pub fn select_nth(&mut self, n: Option<usize>) {
+        let last = self.table_data.len().saturating_sub(1);
+        self.state.selected_row = n.map_or(last, |n| n.saturating_sub(1).min(last));
+        // Scroll just far enough to show the row
+        if self.state.selected_row < self.state.scroll_offset {
+            self.state.scroll_offset = self.state.selected_row;
+        } else if self.state.selected_row >= self.state.scroll_offset + self.visible_lines {
+            self.state.scroll_offset = self.state.selected_row + 1 - self.visible_lines;
+        }
+    }
 
-        //TODO: remove cursor
-        self.hide_cursor();
-        stdout.flush().unwrap();
+    fn hide_cursor(&self) -> Result<()> {
+        write!(stdout(), "\x1B[?25l")?; // Hide the cursor
+        stdout().flush()?; // Ensure the command is applied immediately
+        Ok(())
     }
 
-    fn render_row(&self, x: u16, y: u16, items: &[String], selected: bool) {
-        let mut cursor_x = x;
+    pub fn show_cursor(&self) -> Result<()> {
+        write!(stdout(), "\x1B[?25h")?; // Show the cursor
+        stdout().flush()?; // Ensure the command is applied immediately
+        Ok(())
+    }
+}
 
-        execute!(stdout(), MoveTo(cursor_x, y)).unwrap();
-        print!("│");
-        cursor_x += 1;
-        let padding = " ".repeat(self.padding);
+impl StatefulWidget for &Table {
+    type State = TableState;
 
-        let column_widths =
-            Self::calculate_column_widths(&self.table_headers, &self.table_data, padding.len());
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut TableState) {
+        let column_widths = self.fitted_column_widths(area.width);
+        let border = "─".repeat(column_widths.iter().sum());
+        let padding = " ".repeat(self.padding);
 
-        for (idx, item) in items.iter().enumerate() {
-            let cell_width = column_widths[idx]; // The calculated width for this column
-            let padded_item = format!(
-                "{}{:^cell_width$}{}",
-                padding,
-                item,
+        // A blurred table dims its borders and selection
+        let (border_color, selection_color) = if self.focused {
+            (self.theme.border, self.theme.selection)
+        } else {
+            (self.theme.muted, self.theme.track)
+        };
+        let border_style = Style::default().fg(border_color.resolve());
+        let edge = || Span::styled("│", border_style);
+
+        let mut lines = vec![Line::styled(format!("┌{}┐", border), border_style)];
+
+        let mut header = vec![edge()];
+        for (i, (header_text, width)) in self.table_headers.iter().zip(&column_widths).enumerate() {
+            // The sort direction takes the first cell of the right padding
+            let trailing = match self.sort {
+                Some((column, descending)) if column == i && self.padding > 0 => {
+                    let arrow = if descending { "▼" } else { "▲" };
+                    format!("{}{}", arrow, " ".repeat(self.padding - 1))
+                }
+                _ => padding.clone(),
+            };
+            let cell_width = width - 2 * self.padding;
+            let text = unicode::truncate(header_text, cell_width);
+            header.push(Span::raw(format!(
+                "{}{}{}",
                 padding,
-                cell_width = cell_width - 2 * padding.len()
-            ); // Right-align the text within the space
-            execute!(stdout(), MoveTo(cursor_x, y)).unwrap();
-            if selected {
-                execute!(stdout(), SetBackgroundColor(PURPLE)).unwrap();
-                print!("{padded_item}"); // Print item within background
-                execute!(stdout(), ResetColor).unwrap();
+                unicode::pad(&text, cell_width, Alignment::Left),
+                trailing
+            )));
+        }
+        header.push(edge());
+        lines.push(Line::from(header));
+        lines.push(Line::styled(format!("│{}│", border), border_style));
+
+        let end_row = usize::min(
+            state.scroll_offset + self.visible_lines,
+            self.table_data.len(),
+        );
+        for (idx, row) in self.order[state.scroll_offset..end_row]
+            .iter()
+            .map(|&row| &self.table_data[row])
+            .enumerate()
+        {
+            let style = if state.scroll_offset + idx == state.selected_row {
+                Style::default().bg(selection_color.resolve())
             } else {
-                print!("{padded_item}");
+                Style::default()
+            };
+            let mut spans = vec![edge()];
+            for (item, width) in row.iter().zip(&column_widths) {
+                // Center the text within the cell
+                let cell_width = width - 2 * self.padding;
+                let text = unicode::truncate(item, cell_width);
+                spans.push(Span::styled(
+                    format!(
+                        "{}{}{}",
+                        padding,
+                        unicode::pad(&text, cell_width, Alignment::Center),
+                        padding
+                    ),
+                    style,
+                ));
             }
-            cursor_x += column_widths[idx as usize] as u16;
+            spans.push(edge());
+            lines.push(Line::from(spans));
         }
+        lines.resize(3 + self.visible_lines, Line::default());
+        // Show an unfinished key sequence such as `5 g` on the bottom border
+        let bottom = match self.keys.pending() {
+            Some(pending) => {
+                let label = format!(" {}… ", pending);
+                let rest = border
+                    .chars()
+                    .count()
+                    .saturating_sub(unicode::width(&label));
+                Line::from(vec![
+                    Span::styled("└", border_style),
+                    Span::styled(label, Style::default().fg(self.theme.accent.resolve())),
+                    Span::styled(format!("{}┘", "─".repeat(rest)), border_style),
+                ])
+            }
+            None => Line::styled(format!("└{}┘", border), border_style),
+        };
+        lines.push(bottom);
 
-        print!("│");
-        println!();
+        Paragraph::new(lines).render(area, buf);
+        self.area.set(area);
     }
+}
 
-    fn render_headers(&self, x: u16, y: u16) {
-        let mut stdout = stdout();
-        let mut cursor_x = x;
-        execute!(stdout, MoveTo(cursor_x, y)).unwrap();
-        print!("│");
-        cursor_x += 1;
-
-        let column_widths =
-            Self::calculate_column_widths(&self.table_headers, &self.table_data, self.padding);
+impl Widget for &Table {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        StatefulWidget::render(self, area, buf, &mut self.state.clone());
+    }
+}
 
-        for (idx, header) in self.table_headers.iter().enumerate() {
-            let padding = " ".repeat(self.padding);
-            let content = format!("{}{}{}", padding, header, padding);
-            execute!(stdout, MoveTo(cursor_x, y)).unwrap();
-            execute!(stdout, Print(content), ResetColor).unwrap();
-            // TOOD: calulate the header width with table_width
-            cursor_x += column_widths[idx as usize] as u16;
+impl HandleEvent for Table {
+    fn handle_event(&mut self, event: &Event) -> EventOutcome {
+        let key = match event {
+            Event::Key(key) => key,
+            Event::Mouse(mouse) => return self.handle_mouse(mouse),
+            // The next render fits the columns to the new size
+            Event::Resize(..) => return EventOutcome::Consumed,
+            _ => return EventOutcome::Ignored,
+        };
+        let (action, count) = match self.keys.resolve(&self.keymap, key) {
+            Resolved::Action { action, count } => (action, count),
+            Resolved::Pending => return EventOutcome::Consumed,
+            Resolved::Unbound => return EventOutcome::Ignored,
+        };
+        match action {
+            Action::Interrupt => return EventOutcome::Interrupted,
+            Action::Up => (0..count.unwrap_or(1)).for_each(|_| self.move_cursor_up()),
+            Action::Down => (0..count.unwrap_or(1)).for_each(|_| self.move_cursor_down()),
+            Action::Top => self.select_nth(Some(1)),
+            Action::Bottom => self.select_nth(count),
+            Action::Submit if !self.table_data.is_empty() => return EventOutcome::Submitted,
+            Action::Cancel => return EventOutcome::Cancelled,
+            _ => return EventOutcome::Ignored,
         }
-        print!("│");
-        println!(); // Move to the next line after headers
+        EventOutcome::Consumed
     }
+}
 
-    fn render_horizontal_line(
-        &self,
-        x: u16,
-        y: u16,
-        total_width: usize,
-        column_widths: &Vec<usize>,
-    ) {
-        let bottom_border = format!("│{}│", "─".repeat(total_width - (column_widths.len() + 1)));
-        // let line = "─".repeat(total_width);
-        execute!(stdout(), MoveTo(x, y), Print(bottom_border)).unwrap();
+impl Focusable for Table {
+    fn is_focused(&self) -> bool {
+        self.focused
     }
 
-    fn render_top_border(&self, x: u16, y: u16, column_widths: &Vec<usize>) {
-        let mut stdout = stdout();
-        let top_border = format!(
-            "┌{}┐",
-            "─".repeat(self.table_width - (column_widths.len() + 1))
-        );
-        execute!(stdout, MoveTo(x, y)).unwrap();
-        println!("{}", top_border);
+    fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
     }
 
-    fn render_bottom_border(&self, x: u16, y: u16, column_widths: &Vec<usize>) {
-        let mut stdout = stdout();
-        let bottom_border = format!(
-            "└{}┘",
-            "─".repeat(self.table_width - (column_widths.len() + 1))
-        );
-        execute!(stdout, MoveTo(x, y + (self.visible_lines) as u16)).unwrap();
-        println!("{}", bottom_border);
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        Table::view(self, frame, area);
     }
+}
 
-    pub fn move_cursor_down(&mut self) {
-        if self.selected_row < self.table_data.len() - 1 {
-            self.selected_row += 1;
-            if self.selected_row >= self.scroll_offset + self.visible_lines {
-                self.scroll_offset += 1;  // Ensure the new row is visible by adjusting the scroll offset.
-            }
-        }
+impl InlinePrompt for Table {
+    type Value = usize;
+
+    fn inline_height(&self) -> u16 {
+        self.height()
     }
-    
 
-    pub fn move_cursor_up(&mut self) {
-        if self.selected_row > 0 {
-            self.selected_row -= 1;
-            if self.selected_row < self.scroll_offset {
-                self.scroll_offset -= 1; // Scroll up
-            }
-        }
+    fn submitted_value(&self) -> Option<usize> {
+        Some(self.selected_index())
+    }
+
+    fn fallback_value(
+        &mut self,
+        input: &mut impl BufRead,
+        output: &mut impl Write,
+    ) -> Result<PromptResult<usize>> {
+        self.prompt_plain(input, output)
     }
 
-    fn hide_cursor(&self) {
-        print!("\x1B[?25l"); // Hide the cursor
-        std::io::stdout().flush().unwrap(); // Ensure the command is applied immediately
+    fn summary(&self, value: &usize) -> String {
+        self.table_data
+            .get(*value)
+            .map(|row| row.join(" "))
+            .unwrap_or_default()
     }
 
-    pub fn show_cursor(&self) {
-        print!("\x1B[?25h"); // Show the cursor
-        std::io::stdout().flush().unwrap(); // Ensure the command is applied immediately
+    fn theme(&self) -> Theme {
+        self.theme
     }
 }
 
-pub fn handle_table(table: &mut Table, x: u16, y: u16) {
+impl<M> Model<M> for Table {
+    fn update(&mut self, msg: Msg<M>) -> Cmd<M> {
+        if let Some(event) = msg.to_event() {
+            self.handle_event(&event);
+        }
+        Cmd::none()
+    }
+
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        Table::view(self, frame, area);
+    }
+}
+
+/// Browse the table until a row is chosen with Enter or a double click,
+/// returning its index in the rows passed to [`Table::new`].
+pub fn handle_table(table: &mut Table, x: u16, y: u16) -> Result<PromptResult<usize>> {
+    if !fallback::is_interactive() {
+        return table.prompt_plain(&mut io::stdin().lock(), &mut io::stdout());
+    }
+    // Mouse capture lets clicks pick rows and sort columns
+    let _session =
+        tui::TerminalSession::with_options(tui::SessionOptions::new().mouse_capture(true))?;
     // Clear the screen initially to start with a clean slate
-    table.render(x, y);
+    table.render(x, y)?;
 
     loop {
-        match read().unwrap() {
-            Event::Key(KeyEvent {
-                code: KeyCode::Char(c),
-                modifiers,
-                ..
-            }) => {
-                if modifiers.contains(KeyModifiers::CONTROL) && c == 'c' {
-                    break; // Handle Ctrl+C gracefully
-                }
-            }
-            Event::Key(KeyEvent {
-                code: KeyCode::Up, ..
-            }) => {
-                table.move_cursor_up();
+        match table.handle_event(&read()?) {
+            EventOutcome::Submitted => {
+                return Ok(PromptResult::Submitted(table.selected_index()));
             }
-            Event::Key(KeyEvent {
-                code: KeyCode::Down,
-                ..
-            }) => {
-                table.move_cursor_down();
-            }
-
-            Event::Key(KeyEvent {
-                code: KeyCode::Esc, ..
-            }) => break,
-            _ => {}
+            EventOutcome::Cancelled => return Ok(PromptResult::Cancelled),
+            EventOutcome::Interrupted => return Ok(PromptResult::Interrupted),
+            EventOutcome::Consumed => table.render(x, y)?,
+            EventOutcome::Ignored => {}
         }
-        table.render(x, y);
-        // Clear the screen before each render to avoid duplicate lines
     }
-
-    // Ensure the terminal is properly reset on exit
-    println!("Exiting gracefully...");
 }
 
 #[cfg(test)]
 mod tests {
     use super::*; // Import everything from the parent module.
+    use crossterm::event::{KeyCode, KeyModifiers};
 
     // Helper function to create a table for testing
     fn setup_test_table() -> Table {
-        let headers = vec!["ID".to_string(), "Name".to_string(), "Occupation".to_string()];
+        let headers = vec![
+            "ID".to_string(),
+            "Name".to_string(),
+            "Occupation".to_string(),
+        ];
         let data = vec![
             vec!["1".to_string(), "Alice".to_string(), "Engineer".to_string()],
             vec!["2".to_string(), "Bob".to_string(), "Artist".to_string()],
-            vec!["3".to_string(), "Charlie".to_string(), "Teacher".to_string()],
-            vec!["4".to_string(), "Charlie".to_string(), "Teacher".to_string()],
+            vec![
+                "3".to_string(),
+                "Charlie".to_string(),
+                "Teacher".to_string(),
+            ],
+            vec![
+                "4".to_string(),
+                "Charlie".to_string(),
+                "Teacher".to_string(),
+            ],
             // Add more rows as needed for thorough testing
         ];
 
@@ -279,9 +577,18 @@ mod tests {
     #[test]
     fn test_initialization() {
         let table = setup_test_table();
-        assert_eq!(table.selected_row, 0, "Initial selected row should be 0");
-        assert_eq!(table.scroll_offset, 0, "Initial scroll offset should be 0");
-        assert!(!table.table_data.is_empty(), "Table data should not be empty");
+        assert_eq!(
+            table.state.selected_row, 0,
+            "Initial selected row should be 0"
+        );
+        assert_eq!(
+            table.state.scroll_offset, 0,
+            "Initial scroll offset should be 0"
+        );
+        assert!(
+            !table.table_data.is_empty(),
+            "Table data should not be empty"
+        );
     }
 
     #[test]
@@ -291,13 +598,15 @@ mod tests {
         table.move_cursor_down(); // Should move to row 1
         table.move_cursor_down(); // Should move to row 2
         table.move_cursor_down(); // Should move to row 3, still visible without scroll
-        assert_eq!(table.selected_row, 3, "Cursor should be at row 3");
-    
+        assert_eq!(table.state.selected_row, 3, "Cursor should be at row 3");
+
         // Now move down to require scrolling
         table.move_cursor_down(); // Should move to row 4, requiring scroll
-        assert_eq!(table.selected_row, 3, "Cursor should render row 4 on table height");
+        assert_eq!(
+            table.state.selected_row, 3,
+            "Cursor should render row 4 on table height"
+        );
     }
-    
 
     #[test]
     fn test_move_cursor_up() {
@@ -306,24 +615,209 @@ mod tests {
         table.move_cursor_down();
         table.move_cursor_down();
         table.move_cursor_up();
-        assert_eq!(table.selected_row, 1, "Cursor should move up to the second row");
+        assert_eq!(
+            table.state.selected_row, 1,
+            "Cursor should move up to the second row"
+        );
 
         // Test boundary condition
         table.move_cursor_up();
         table.move_cursor_up(); // Try to move above the first row
-        assert_eq!(table.selected_row, 0, "Cursor should not move above the first row");
-        assert_eq!(table.scroll_offset, 0, "Scroll offset should remain at 0 when at the top of the table");
+        assert_eq!(
+            table.state.selected_row, 0,
+            "Cursor should not move above the first row"
+        );
+        assert_eq!(
+            table.state.scroll_offset, 0,
+            "Scroll offset should remain at 0 when at the top of the table"
+        );
+    }
+
+    #[test]
+    fn test_handle_event_outcomes() {
+        let mut table = setup_test_table();
+        let key = |code| Event::Key(crossterm::event::KeyEvent::new(code, KeyModifiers::NONE));
+        assert_eq!(
+            table.handle_event(&key(KeyCode::Down)),
+            EventOutcome::Consumed
+        );
+        assert_eq!(table.state.selected_row, 1);
+        assert_eq!(
+            table.handle_event(&key(KeyCode::Left)),
+            EventOutcome::Ignored
+        );
+        assert_eq!(
+            table.handle_event(&key(KeyCode::Enter)),
+            EventOutcome::Submitted
+        );
+        assert_eq!(
+            table.handle_event(&key(KeyCode::Esc)),
+            EventOutcome::Cancelled
+        );
+    }
+
+    #[test]
+    fn test_vim_sequences_and_counts() {
+        let keymap = KeyMap::preset(crate::keymap::Preset::Vim, Component::Table);
+        let mut table = setup_test_table().with_keymap(keymap);
+        let key = |c| {
+            Event::Key(crossterm::event::KeyEvent::new(
+                KeyCode::Char(c),
+                KeyModifiers::NONE,
+            ))
+        };
+
+        table.handle_event(&key('G'));
+        assert_eq!(table.state.selected_row, 3);
+        assert_eq!(table.handle_event(&key('g')), EventOutcome::Consumed);
+        let mut buf = Buffer::empty(Rect::new(0, 0, table.width(), table.height()));
+        Widget::render(&table, buf.area, &mut buf);
+        let bottom: String = (0..buf.area.width)
+            .map(|x| buf.get(x, buf.area.height - 1).symbol())
+            .collect();
+        assert!(bottom.starts_with("└ g… ─"));
+
+        table.handle_event(&key('g'));
+        assert_eq!(table.state.selected_row, 0);
+        table.handle_event(&key('2'));
+        table.handle_event(&key('j'));
+        assert_eq!(table.state.selected_row, 2);
+        table.handle_event(&key('1'));
+        table.handle_event(&key('G'));
+        assert_eq!(table.state.selected_row, 0);
     }
 
     #[test]
     fn test_column_width_calculation() {
         let table = setup_test_table();
         let expected_widths = vec![6, 11, 14]; // Adjusted expected widths to account for padding and actual content lengths
-        let calculated_widths = Table::calculate_column_widths(&table.table_headers, &table.table_data, 2);
-    
-        assert_eq!(calculated_widths, expected_widths, "Column widths should be calculated correctly based on content and padding");
+        let calculated_widths =
+            Table::calculate_column_widths(&table.table_headers, &table.table_data, 2);
+
+        assert_eq!(
+            calculated_widths, expected_widths,
+            "Column widths should be calculated correctly based on content and padding"
+        );
     }
 
     // Additional tests for rendering and edge cases can be added here
-}
 
+    #[test]
+    fn test_render_highlights_selected_row() {
+        let mut table = setup_test_table();
+        table.move_cursor_down();
+        let area = Rect::new(0, 0, 33, table.height());
+        let mut buf = Buffer::empty(area);
+        Widget::render(&table, area, &mut buf);
+
+        let row = |y: u16| -> String { (0..area.width).map(|x| buf.get(x, y).symbol()).collect() };
+        assert_eq!(row(1), "│  ID    Name       Occupation  │");
+        assert_eq!(row(4), "│  2       Bob        Artist    │");
+        assert_eq!(buf.get(1, 4).bg, Theme::default().selection.resolve());
+        assert_ne!(buf.get(1, 3).bg, Theme::default().selection.resolve());
+    }
+
+    #[test]
+    fn test_render_uses_theme() {
+        let theme = Theme::builtin("dracula").unwrap();
+        let table = setup_test_table().with_theme(theme);
+        let area = Rect::new(0, 0, 33, table.height());
+        let mut buf = Buffer::empty(area);
+        Widget::render(&table, area, &mut buf);
+
+        assert_eq!(buf.get(0, 0).fg, theme.border.resolve());
+        assert_eq!(buf.get(1, 3).bg, theme.selection.resolve());
+    }
+
+    #[test]
+    fn test_mouse_selects_and_sorts() {
+        use crossterm::event::{MouseButton, MouseEvent};
+        let mut table = setup_test_table();
+        let area = Rect::new(0, 0, 33, table.height());
+        let mut buf = Buffer::empty(area);
+        Widget::render(&table, area, &mut buf);
+        let click = |column, row| {
+            Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Down(MouseButton::Left),
+                column,
+                row,
+                modifiers: KeyModifiers::NONE,
+            })
+        };
+
+        assert_eq!(table.handle_event(&click(10, 4)), EventOutcome::Consumed);
+        assert_eq!(table.state.selected_row, 1);
+        assert_eq!(table.handle_event(&click(10, 4)), EventOutcome::Submitted);
+
+        // Sorting by name twice reverses it, keeping Bob selected
+        table.handle_event(&click(10, 1));
+        table.handle_event(&click(10, 1));
+        assert_eq!(table.selected_index(), 1);
+        assert_eq!(table.order, vec![2, 3, 1, 0]);
+        assert_eq!(table.state.selected_row, 2);
+
+        Widget::render(&table, area, &mut buf);
+        let header: String = (0..area.width).map(|x| buf.get(x, 1).symbol()).collect();
+        assert_eq!(header, "│  ID    Name   ▼   Occupation  │");
+    }
+
+    #[test]
+    fn test_columns_shrink_to_fit() {
+        let table = setup_test_table();
+        assert_eq!(table.width(), 33);
+        let area = Rect::new(0, 0, 25, table.height());
+        let mut buf = Buffer::empty(area);
+        Widget::render(&table, area, &mut buf);
+
+        let row = |y: u16| -> String { (0..area.width).map(|x| buf.get(x, y).symbol()).collect() };
+        assert_eq!(row(0), "┌───────────────────────┐");
+        assert_eq!(row(1), "│  ID    Name     Occ…  │");
+        assert_eq!(row(5), "│  3     Char…    Tea…  │");
+    }
+
+    #[test]
+    fn test_wide_characters_keep_borders_aligned() {
+        let headers = vec!["名前".to_string(), "City".to_string()];
+        let data = vec![
+            vec!["東京太郎".to_string(), "Zürich".to_string()],
+            vec!["Ana".to_string(), "東京".to_string()],
+        ];
+        let table = Table::new(headers, data, 0, 1, 2);
+        assert_eq!(
+            Table::calculate_column_widths(&table.table_headers, &table.table_data, 1),
+            vec![10, 8]
+        );
+        let area = Rect::new(0, 0, table.width(), table.height());
+        let mut buf = Buffer::empty(area);
+        Widget::render(&table, area, &mut buf);
+        // Skip the cell a wide character spills into
+        let row = |y: u16| -> String {
+            let mut hidden = 0;
+            let mut text = String::new();
+            for x in 0..area.width {
+                let symbol = buf.get(x, y).symbol();
+                if hidden > 0 {
+                    hidden -= 1;
+                    continue;
+                }
+                hidden = unicode::width(symbol).saturating_sub(1);
+                text.push_str(symbol);
+            }
+            text
+        };
+        assert_eq!(buf.get(area.width - 1, 4).symbol(), "│");
+        assert_eq!(row(1), "│ 名前      City   │");
+        assert_eq!(row(3), "│ 東京太郎  Zürich │");
+        assert_eq!(row(4), "│   Ana      東京  │");
+    }
+
+    #[test]
+    fn test_empty_table() {
+        let mut table = Table::new(vec!["ID".to_string()], Vec::new(), 0, 2, 3);
+        let key = |code| Event::Key(crossterm::event::KeyEvent::new(code, KeyModifiers::NONE));
+        table.handle_event(&key(KeyCode::Down));
+        table.handle_event(&key(KeyCode::Up));
+        table.move_cursor_down();
+        assert_eq!(table.state.selected_row, 0);
+    }
+}
@@ -1,265 +1,448 @@
-use crate::helper::Helper;
+use crate::{
+    editor::TextBuffer,
+    event::{EventOutcome, HandleEvent, PromptResult},
+    fallback,
+    focus::Focusable,
+    helper::Helper,
+    inline::InlinePrompt,
+    keymap::{Action, Component, KeyBuffer, KeyMap, Resolved},
+    mouse,
+    program::{Cmd, Model, Msg},
+    theme::Theme,
+    tui, Result,
+};
 use crossterm::{
     cursor::MoveTo,
-    event::{read, Event, KeyCode, KeyEvent, KeyModifiers},
+    event::{poll, read, Event, KeyCode, KeyModifiers, MouseEvent, MouseEventKind},
     execute,
-    style::Print,
-    terminal::{Clear, ClearType},
 };
-use std::io::stdout;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Style,
+    text::Line,
+    widgets::{Paragraph, StatefulWidget, Widget},
+    Frame,
+};
+use std::{
+    io::{self, stdout, BufRead, Write},
+    time::Duration,
+};
+
+// Columns taken by the line numbers before the text, as in `|  1 `
+const GUTTER_WIDTH: usize = 5;
 
 pub struct TextArea {
-    text: Vec<String>, // Stores multiple lines of text
-    cursor_x: usize,
-    cursor_y: usize,
-    scroll_offset: usize, // Top visible line index
+    buffer: TextBuffer,
     label: String,
     helper: Option<Helper>,
     visible_lines: usize,
+    state: TextAreaState,
+    keymap: KeyMap,
+    keys: KeyBuffer,
+    theme: Theme,
+    focused: bool,
+}
+
+/// Scroll state of a [`TextArea`] and where it was last drawn. The cursor
+/// within the text is kept by its [`TextBuffer`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TextAreaState {
+    scroll_offset: usize, // Top visible line index
+    cursor: Option<(u16, u16)>,
+    area: Rect,
+}
+
+impl TextAreaState {
+    pub fn scroll_offset(&self) -> usize {
+        self.scroll_offset
+    }
+
+    /// Screen position of the cursor after the last render, if it fit in the area.
+    pub fn cursor(&self) -> Option<(u16, u16)> {
+        self.cursor
+    }
+
+    /// The area of the last render, which mouse clicks are checked against.
+    pub fn area(&self) -> Rect {
+        self.area
+    }
 }
 
 impl TextArea {
     pub fn new(label: &str, helper_text: Option<&str>, visible_lines: usize) -> Self {
         TextArea {
-            text: vec![String::new()], // Start with one empty line
-            cursor_x: 0,
-            cursor_y: 0,
-            scroll_offset: 0,
+            buffer: TextBuffer::new(),
             label: label.to_string(),
-            helper: helper_text.map(|text| Helper::new(text)),
+            helper: helper_text.map(Helper::new),
             visible_lines,
+            state: TextAreaState::default(),
+            keymap: KeyMap::default_for(Component::TextArea),
+            keys: KeyBuffer::new(),
+            theme: Theme::default(),
+            focused: true,
         }
     }
 
-    pub fn insert_char(&mut self, c: char) {
-        if c == '\n' {
-            self.insert_new_line();
-        } else {
-            if self.cursor_x >= self.text[self.cursor_y].len() {
-                self.text[self.cursor_y].push(c);
-            } else {
-                self.text[self.cursor_y].insert(self.cursor_x, c);
-            }
-            self.cursor_x += 1;
+    pub fn with_keymap(mut self, keymap: KeyMap) -> Self {
+        self.set_keymap(keymap);
+        self
+    }
+
+    pub fn set_keymap(&mut self, keymap: KeyMap) {
+        self.keymap = keymap;
+        self.keys.clear();
+    }
+
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.set_theme(theme);
+        self
+    }
+
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+        if let Some(helper) = self.helper.as_mut() {
+            helper.set_theme(theme);
         }
-        self.ensure_cursor_within_bounds();
     }
 
-    pub fn insert_new_line(&mut self) {
-        let current_line = self.text[self.cursor_y].split_off(self.cursor_x);
-        self.text.insert(self.cursor_y + 1, current_line);
-        self.cursor_y += 1;
-        self.cursor_x = 0;
-        self.adjust_scroll();
+    pub fn value(&self) -> String {
+        self.buffer.text()
+    }
+
+    pub fn label(&self) -> &str {
+        &self.label
     }
 
-    // Make sure cursor bounds are always respected
-    pub fn ensure_cursor_within_bounds(&mut self) {
-        if self.cursor_y >= self.text.len() {
-            self.cursor_y = self.text.len() - 1;
+    /// The text being edited and the cursor within it.
+    pub fn buffer(&self) -> &TextBuffer {
+        &self.buffer
+    }
+
+    /// Read the text as plain lines of `input`, for when there is no
+    /// terminal. An empty line or the end of input finishes the text; input
+    /// that ends before any line cancels.
+    pub(crate) fn prompt_plain(
+        &mut self,
+        input: &mut impl BufRead,
+        output: &mut impl Write,
+    ) -> Result<PromptResult<String>> {
+        writeln!(output, "{} (finish with an empty line)", self.label)?;
+        let mut lines = Vec::new();
+        loop {
+            match fallback::read_line(input, output, "")? {
+                None if lines.is_empty() => return Ok(PromptResult::Cancelled),
+                Some(line) if !line.is_empty() => lines.push(line),
+                _ => break,
+            }
         }
-        if self.cursor_x > self.text[self.cursor_y].len() {
-            self.cursor_x = self.text[self.cursor_y].len();
+        self.buffer = TextBuffer::from_text(&lines.join("\n"));
+        Ok(PromptResult::Submitted(self.value()))
+    }
+
+    pub fn state(&self) -> &TextAreaState {
+        &self.state
+    }
+
+    // The wheel moves the cursor a line at a time, and a click on the text
+    // places the cursor there
+    fn handle_mouse(&mut self, mouse: &MouseEvent) -> EventOutcome {
+        match mouse.kind {
+            MouseEventKind::ScrollUp => self.move_cursor_up(),
+            MouseEventKind::ScrollDown => self.move_cursor_down(),
+            _ => match mouse::left_click(mouse, self.state.area) {
+                Some((column, row)) if (2..2 + self.visible_lines as u16).contains(&row) => {
+                    let line = self.state.scroll_offset + (row - 2) as usize;
+                    let width = (column as usize).saturating_sub(GUTTER_WIDTH);
+                    let column = self.buffer.column_at_width(line, width);
+                    self.buffer.set_cursor(line, column);
+                }
+                _ => return EventOutcome::Ignored,
+            },
         }
+        EventOutcome::Consumed
+    }
+
+    pub fn insert_char(&mut self, c: char) {
+        self.buffer.insert_char(c);
+        self.adjust_scroll();
+    }
+
+    /// Insert `text` at the cursor in one edit, keeping its line breaks.
+    pub fn insert_str(&mut self, text: &str) {
+        self.buffer.insert_str(text);
+        self.adjust_scroll();
+    }
+
+    pub fn insert_new_line(&mut self) {
+        self.buffer.insert_newline();
+        self.adjust_scroll();
     }
 
     // Adjust the scroll if cursor moves outside the visible area
     pub fn adjust_scroll(&mut self) {
-        if self.cursor_y < self.scroll_offset {
-            self.scroll_offset = self.cursor_y;
-        } else if self.cursor_y >= self.scroll_offset + self.visible_lines {
-            self.scroll_offset = self.cursor_y - self.visible_lines + 1;
+        let (row, _) = self.buffer.cursor();
+        if row < self.state.scroll_offset {
+            self.state.scroll_offset = row;
+        } else if row >= self.state.scroll_offset + self.visible_lines {
+            self.state.scroll_offset = row + 1 - self.visible_lines;
         }
     }
 
-    fn update_cursor_position(&self, x: u16, y: u16) {
-        let current_line_y = y + 2 + (self.cursor_y - self.scroll_offset) as u16;
-        let cursor_pos_x = x + 5 + self.cursor_x as u16; // Account for line number width
-        let mut stdout = stdout();
-        execute!(stdout, MoveTo(cursor_pos_x, current_line_y)).unwrap();
-    }
-
     pub fn move_cursor_left(&mut self) {
-        if self.cursor_x > 0 {
-            self.cursor_x -= 1;
-        } else if self.cursor_y > 0 {
-            self.cursor_y -= 1;
-            self.cursor_x = self.text[self.cursor_y].len(); // Move to the end of the previous line
-        }
+        self.buffer.move_left(); // Moves to the end of the previous line at the start
+        self.adjust_scroll();
     }
 
     pub fn move_cursor_right(&mut self) {
-        if self.cursor_x < self.text[self.cursor_y].len() {
-            self.cursor_x += 1;
-        } else if self.cursor_y < self.text.len() - 1 {
-            self.cursor_y += 1;
-            self.cursor_x = 0; // Move to the start of the next line
-        }
+        self.buffer.move_right(); // Moves to the start of the next line at the end
+        self.adjust_scroll();
     }
 
     pub fn move_cursor_down(&mut self) {
-        if self.cursor_y < self.text.len() - 1 {
-            self.cursor_y += 1; // Move cursor down within the text boundaries
-
-            // Adjust scroll offset if the cursor moves below the visible area
-            if self.cursor_y >= self.scroll_offset + self.visible_lines {
-                self.scroll_offset += 1;
-            }
-        }
+        self.buffer.move_down();
+        self.adjust_scroll();
     }
 
     pub fn move_cursor_up(&mut self) {
-        if self.cursor_y > 0 {
-            self.cursor_y -= 1;
-            // Ensure that scroll_offset is adjusted if necessary
-            if self.cursor_y < self.scroll_offset {
-                self.scroll_offset -= 1;
-            }
-        }
+        self.buffer.move_up();
+        self.adjust_scroll();
+    }
+
+    pub fn move_cursor_line_start(&mut self) {
+        self.buffer.move_line_start();
+    }
+
+    pub fn move_cursor_line_end(&mut self) {
+        self.buffer.move_line_end();
+    }
+
+    /// Delete the character under the cursor, joining the next line at the end
+    /// of a line.
+    pub fn delete_char_forward(&mut self) {
+        self.buffer.delete_forward();
     }
 
     pub fn delete_char(&mut self) {
-        if self.cursor_x > 0 {
-            self.text[self.cursor_y].remove(self.cursor_x - 1);
-            self.cursor_x -= 1;
-        } else if self.cursor_y > 0 {
-            // Store the current line in a temporary variable before removing it
-            let current_line = self.text.remove(self.cursor_y);
-            self.cursor_y -= 1;
-            self.cursor_x = self.text[self.cursor_y].len();
-            self.text[self.cursor_y].push_str(&current_line);
-            if self.cursor_y < self.scroll_offset {
-                if self.scroll_offset > 0 {
-                    self.scroll_offset -= 1;
-                }
-            }
+        self.buffer.delete_backward();
+        self.adjust_scroll();
+    }
+
+    /// Number of rows the text area needs, including the helper text.
+    pub fn height(&self) -> u16 {
+        let helper_rows = if self.helper.is_some() { 2 } else { 0 };
+        (2 + self.visible_lines + helper_rows) as u16
+    }
+
+    pub fn render(&mut self, x: u16, y: u16) -> Result<()> {
+        let area = tui::area_at(x, y, self.height())?;
+        let mut state = self.state.clone();
+        tui::draw_stateful_widget(&*self, area, &mut state)?;
+        self.state = state;
+
+        // Update the cursor position to reflect the latest changes
+        if let Some((cursor_x, cursor_y)) = self.state.cursor {
+            execute!(stdout(), MoveTo(cursor_x, cursor_y))?;
         }
+        Ok(())
     }
 
-    pub fn render(&self, x: u16, y: u16) {
-        let mut stdout = stdout();
+    /// Draw the component into `area`, for use inside a [`Program`](crate::program::Program).
+    pub fn view(&mut self, frame: &mut Frame, area: Rect) {
+        let mut state = self.state.clone();
+        frame.render_stateful_widget(&*self, area, &mut state);
+        self.state = state;
 
-        // Extend clearing to ensure all lines including potential overflow are cleared
-        let total_lines_to_clear = self.visible_lines + 3; // +3 to include label and two lines for helper just in case
-        for i in 0..total_lines_to_clear {
-            execute!(
-                stdout,
-                MoveTo(x, y + i as u16),
-                Clear(ClearType::CurrentLine)
-            )
-            .unwrap();
+        if let Some((cursor_x, cursor_y)) = self.state.cursor {
+            frame.set_cursor(cursor_x, cursor_y);
         }
+    }
+}
 
-        // Render the label at the designated position
-        execute!(stdout, MoveTo(x, y), Print(&self.label)).unwrap();
+impl StatefulWidget for &TextArea {
+    type State = TextAreaState;
 
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut TextAreaState) {
+        let mut lines = vec![Line::from(self.label.as_str()), Line::default()];
         // Render each line of text with its line number
         for i in 0..self.visible_lines {
-            let line_idx = i + self.scroll_offset;
-            execute!(
-                stdout,
-                MoveTo(x, y + 2 + i as u16), // +2 to offset from label
-                Print(format!("|{:3} ", line_idx + 1)),
-                Print(&self.text.get(line_idx).unwrap_or(&String::new()))
-            )
-            .unwrap();
+            let line_idx = i + state.scroll_offset;
+            let text = self.buffer.line(line_idx);
+            lines.push(Line::from(format!("|{:3} {}", line_idx + 1, text)));
         }
-
         // Render the helper text below the last visible line
-        if let Some(helper_text) = &self.helper {
-            helper_text.render(x, y + 2 + self.visible_lines as u16 + 1)
+        if let Some(ref helper) = self.helper {
+            lines.push(Line::default());
+            lines.push(Line::from(helper.span()));
+        }
+        let mut paragraph = Paragraph::new(lines);
+        if !self.focused {
+            paragraph = paragraph.style(Style::default().fg(self.theme.muted.resolve()));
         }
+        paragraph.render(area, buf);
 
-        // Update the cursor position to reflect the latest changes
-        self.update_cursor_position(x, y);
+        // Account for the label and the line number gutter
+        let (row, _) = self.buffer.cursor();
+        let cursor_x = area.x + (GUTTER_WIDTH + self.buffer.cursor_width()) as u16;
+        let visible = row >= state.scroll_offset;
+        let cursor_y = area.y + 2 + row.saturating_sub(state.scroll_offset) as u16;
+        state.cursor =
+            (self.focused && visible && cursor_x < area.right() && cursor_y < area.bottom())
+                .then_some((cursor_x, cursor_y));
+        state.area = area;
     }
+}
 
-    // Methods to handle input, scroll, etc., go here
+impl Widget for &TextArea {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let mut state = self.state.clone();
+        StatefulWidget::render(self, area, buf, &mut state);
+    }
 }
 
-pub fn handle_text_area(text_area: &mut TextArea, x: u16, y: u16) -> Option<String> {
-    text_area.render(x, y);
-    loop {
-        match read().unwrap() {
-            Event::Key(KeyEvent {
-                code: KeyCode::Char(c),
-                modifiers,
-                ..
-            }) => {
-                if modifiers.contains(KeyModifiers::CONTROL) && c == 'c' {
-                    return None; // Break the loop if Ctrl+C is pressed
-                }
-                text_area.insert_char(c);
-                text_area.render(x, y);
-            }
-            Event::Key(KeyEvent {
-                code: KeyCode::Backspace,
-                ..
-            }) => {
-                text_area.delete_char();
-                text_area.render(x, y);
-            }
-            Event::Key(KeyEvent {
-                code: KeyCode::Left,
-                ..
-            }) => {
-                text_area.move_cursor_left();
-                text_area.render(x, y);
-            }
-            Event::Key(KeyEvent {
-                code: KeyCode::Right,
-                ..
-            }) => {
-                text_area.move_cursor_right();
-                text_area.render(x, y);
+impl HandleEvent for TextArea {
+    fn handle_event(&mut self, event: &Event) -> EventOutcome {
+        let key = match event {
+            Event::Key(key) => key,
+            Event::Mouse(mouse) => return self.handle_mouse(mouse),
+            Event::Paste(text) => {
+                self.insert_str(text);
+                return EventOutcome::Consumed;
             }
-            Event::Key(KeyEvent {
-                code: KeyCode::Down,
-                ..
-            }) => {
-                text_area.move_cursor_down();
-                text_area.render(x, y);
-            }
-            Event::Key(KeyEvent {
-                code: KeyCode::Up, ..
-            }) => {
-                text_area.move_cursor_up();
-                text_area.render(x, y);
-            }
-            Event::Key(KeyEvent {
-                code: KeyCode::Enter,
-                ..
-            }) => {
-                text_area.insert_new_line();
-            }
-            Event::Key(KeyEvent {
-                code: KeyCode::Tab, ..
-            }) => {
-                if !text_area.text.is_empty() {
-                    return Some(text_area.text.join("\n"));
+            // Redraw at the new width
+            Event::Resize(..) => return EventOutcome::Consumed,
+            _ => return EventOutcome::Ignored,
+        };
+        // Sequences such as emacs' `ctrl+x ctrl+s` wait for their second key
+        match self.keys.resolve(&self.keymap, key) {
+            Resolved::Action { action, .. } => match action {
+                Action::Interrupt => return EventOutcome::Interrupted,
+                Action::Submit => return EventOutcome::Submitted,
+                Action::Cancel => return EventOutcome::Cancelled,
+                Action::Backspace => self.delete_char(),
+                Action::Delete => self.delete_char_forward(),
+                Action::Left => self.move_cursor_left(),
+                Action::Right => self.move_cursor_right(),
+                Action::Down => self.move_cursor_down(),
+                Action::Up => self.move_cursor_up(),
+                Action::LineStart => self.move_cursor_line_start(),
+                Action::LineEnd => self.move_cursor_line_end(),
+                Action::NewLine => self.insert_new_line(),
+                _ => return EventOutcome::Ignored,
+            },
+            Resolved::Pending => {}
+            Resolved::Unbound => match key.code {
+                KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.insert_char(c)
                 }
-            }
-            Event::Key(KeyEvent {
-                code: KeyCode::Esc, ..
-            }) => return None,
+                _ => return EventOutcome::Ignored,
+            },
+        }
+        EventOutcome::Consumed
+    }
+}
+
+impl Focusable for TextArea {
+    fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        TextArea::view(self, frame, area);
+    }
+}
+
+impl InlinePrompt for TextArea {
+    type Value = String;
+
+    fn inline_height(&self) -> u16 {
+        self.height()
+    }
 
-            _ => {}
+    fn submitted_value(&self) -> Option<String> {
+        Some(self.value())
+    }
+
+    fn fallback_value(
+        &mut self,
+        input: &mut impl BufRead,
+        output: &mut impl Write,
+    ) -> Result<PromptResult<String>> {
+        self.prompt_plain(input, output)
+    }
+
+    fn summary(&self, value: &String) -> String {
+        // Only the first line fits on the summary line
+        match value.split_once('\n') {
+            Some((first, _)) => format!("{}…", first),
+            None => value.clone(),
         }
-        // Re-render text area after each input
-        text_area.render(x, y);
+    }
+
+    fn theme(&self) -> Theme {
+        self.theme
     }
 }
 
+impl<M> Model<M> for TextArea {
+    fn update(&mut self, msg: Msg<M>) -> Cmd<M> {
+        if let Some(event) = msg.to_event() {
+            self.handle_event(&event);
+        }
+        Cmd::none()
+    }
+
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        TextArea::view(self, frame, area);
+    }
+}
+
+pub fn handle_text_area(text_area: &mut TextArea, x: u16, y: u16) -> Result<PromptResult<String>> {
+    if !fallback::is_interactive() {
+        return text_area.prompt_plain(&mut io::stdin().lock(), &mut io::stdout());
+    }
+    // Mouse capture lets clicks place the cursor and the wheel scroll, and
+    // bracketed paste delivers pasted text as one event
+    let options = tui::SessionOptions::new()
+        .mouse_capture(true)
+        .bracketed_paste(true);
+    let _session = tui::TerminalSession::with_options(options)?;
+    text_area.render(x, y)?;
+    loop {
+        let event = read()?;
+        if let Event::Resize(..) = event {
+            tui::clear_below(y)?;
+        }
+        match text_area.handle_event(&event) {
+            EventOutcome::Submitted => return Ok(PromptResult::Submitted(text_area.value())),
+            EventOutcome::Cancelled => return Ok(PromptResult::Cancelled),
+            EventOutcome::Interrupted => return Ok(PromptResult::Interrupted),
+            // Keys typed or pasted faster than we draw are applied before the next render
+            EventOutcome::Consumed if !poll(Duration::ZERO)? => text_area.render(x, y)?,
+            EventOutcome::Consumed => {}
+            EventOutcome::Ignored => {}
+        }
+    }
+}
 
 #[cfg(test)]
 mod tests {
     use super::*; // Import everything from the parent module.
+    use crossterm::event::KeyEvent;
 
     #[test]
     fn test_insert_char() {
         let mut text_area = TextArea::new("Example", None, 3);
         text_area.insert_char('a');
-        assert_eq!(text_area.text[0], "a", "Character should be inserted");
+        assert_eq!(
+            text_area.buffer.line(0),
+            "a",
+            "Character should be inserted"
+        );
     }
 
     #[test]
@@ -267,8 +450,12 @@ mod tests {
         let mut text_area = TextArea::new("Example", None, 3);
         text_area.insert_char('a');
         text_area.insert_char('\n');
-        assert_eq!(text_area.text.len(), 2, "New line should be added");
-        assert_eq!(text_area.cursor_y, 1, "Cursor should move to the new line");
+        assert_eq!(text_area.buffer.line_count(), 2, "New line should be added");
+        assert_eq!(
+            text_area.buffer.cursor().0,
+            1,
+            "Cursor should move to the new line"
+        );
     }
 
     #[test]
@@ -277,14 +464,14 @@ mod tests {
         text_area.insert_char('a');
         text_area.insert_char('b');
         text_area.move_cursor_left();
-        assert_eq!(text_area.cursor_x, 1, "Cursor should move left");
+        assert_eq!(text_area.buffer.cursor().1, 1, "Cursor should move left");
         text_area.move_cursor_right();
-        assert_eq!(text_area.cursor_x, 2, "Cursor should move right");
+        assert_eq!(text_area.buffer.cursor().1, 2, "Cursor should move right");
         text_area.insert_char('\n');
         text_area.move_cursor_up();
-        assert_eq!(text_area.cursor_y, 0, "Cursor should move up");
+        assert_eq!(text_area.buffer.cursor().0, 0, "Cursor should move up");
         text_area.move_cursor_down();
-        assert_eq!(text_area.cursor_y, 1, "Cursor should move down");
+        assert_eq!(text_area.buffer.cursor().0, 1, "Cursor should move down");
     }
 
     #[test]
@@ -293,13 +480,40 @@ mod tests {
         text_area.insert_char('a');
         text_area.insert_char('b');
         text_area.delete_char();
-        assert_eq!(text_area.text[0], "a", "Last character should be deleted");
+        assert_eq!(
+            text_area.buffer.line(0),
+            "a",
+            "Last character should be deleted"
+        );
         text_area.insert_char('\n');
 
         text_area.move_cursor_up();
 
         text_area.delete_char(); // Deleting the new line
-        assert_eq!(text_area.text[0].len(), 1, "Lines should merge");
+        assert_eq!(text_area.buffer.line(0).len(), 1, "Lines should merge");
+    }
+
+    #[test]
+    fn test_handle_event_outcomes() {
+        let mut text_area = TextArea::new("Example", None, 3);
+        let key = |code| Event::Key(KeyEvent::new(code, KeyModifiers::NONE));
+        assert_eq!(
+            text_area.handle_event(&key(KeyCode::Char('a'))),
+            EventOutcome::Consumed
+        );
+        assert_eq!(
+            text_area.handle_event(&key(KeyCode::Enter)),
+            EventOutcome::Consumed
+        );
+        assert_eq!(
+            text_area.handle_event(&key(KeyCode::Tab)),
+            EventOutcome::Submitted
+        );
+        assert_eq!(
+            text_area.handle_event(&key(KeyCode::Esc)),
+            EventOutcome::Cancelled
+        );
+        assert_eq!(text_area.value(), "a\n");
     }
 
     #[test]
@@ -313,6 +527,39 @@ mod tests {
         text_area.move_cursor_down();
         text_area.move_cursor_down();
         text_area.move_cursor_down(); // Move cursor to make scrolling necessary
-        assert_eq!(text_area.scroll_offset, 3, "Should scroll down when cursor moves beyond visible lines");
+        assert_eq!(
+            text_area.state.scroll_offset, 3,
+            "Should scroll down when cursor moves beyond visible lines"
+        );
+    }
+
+    #[test]
+    fn test_wide_characters() {
+        let mut text_area = TextArea::new("Example", None, 3);
+        for c in "日本\nab👍".chars() {
+            text_area.insert_char(c);
+        }
+        text_area.move_cursor_up();
+        assert_eq!(text_area.buffer.cursor(), (0, 2));
+        text_area.delete_char();
+        assert_eq!(text_area.value(), "日\nab👍");
+
+        let area = Rect::new(0, 0, 20, text_area.height());
+        let mut buf = Buffer::empty(area);
+        let mut state = text_area.state().clone();
+        StatefulWidget::render(&text_area, area, &mut buf, &mut state);
+        assert_eq!(state.cursor(), Some((7, 2)));
+    }
+
+    #[test]
+    fn test_paste_keeps_newlines() {
+        let mut text_area = TextArea::new("Example", None, 2);
+        text_area.insert_str("ab");
+        text_area.move_cursor_left();
+        let outcome = text_area.handle_event(&Event::Paste("1\r2\r3".to_string()));
+        assert_eq!(outcome, EventOutcome::Consumed);
+        assert_eq!(text_area.value(), "a1\n2\n3b");
+        assert_eq!(text_area.buffer.cursor(), (2, 1));
+        assert_eq!(text_area.state.scroll_offset, 1);
     }
 }
@@ -0,0 +1,259 @@
+use std::{fs, path::Path};
+
+use ratatui::style::Color;
+use serde::Deserialize;
+
+use crate::{colors::AdaptiveColor, Error, Result};
+
+/// The colors components draw with, by role rather than by component.
+///
+/// Every component starts with [`Theme::default`] and takes another one
+/// through its `with_theme` method. Each role is an [`AdaptiveColor`], so a
+/// theme can pick different colors for light and dark terminals.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Theme {
+    /// Titles and the highlighted item of a list.
+    pub primary: AdaptiveColor,
+    /// Small highlights such as an unfinished key sequence.
+    pub accent: AdaptiveColor,
+    /// Regular text such as item titles and input prefixes.
+    pub text: AdaptiveColor,
+    /// Secondary text such as subtitles and typed values.
+    pub secondary: AdaptiveColor,
+    /// Hints such as the help bar and helper text, and components that
+    /// don't have focus.
+    pub muted: AdaptiveColor,
+    /// Background of the selected table row.
+    pub selection: AdaptiveColor,
+    /// Confirmations such as the check mark of an inline summary.
+    pub success: AdaptiveColor,
+    /// Validation and other error messages.
+    pub error: AdaptiveColor,
+    /// Box drawing around tables and viewports.
+    pub border: AdaptiveColor,
+    /// The unfilled part of a progress bar, and the selected row of a table
+    /// that doesn't have focus.
+    pub track: AdaptiveColor,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        // Light terminals get darker colors from the 256 color palette,
+        // which themes remap less often than the first 16 entries
+        Self {
+            primary: AdaptiveColor::new(Color::Magenta, Color::LightMagenta),
+            accent: AdaptiveColor::new(Color::Magenta, Color::LightMagenta),
+            text: AdaptiveColor::new(Color::Black, Color::White),
+            secondary: AdaptiveColor::new(Color::Indexed(238), Color::Gray),
+            muted: AdaptiveColor::new(Color::Indexed(242), Color::DarkGray),
+            selection: AdaptiveColor::new(Color::Rgb(196, 191, 245), Color::Rgb(122, 114, 229)),
+            success: Color::Green.into(),
+            error: Color::Red.into(),
+            border: Color::Reset.into(),
+            track: AdaptiveColor::new(Color::Indexed(252), Color::DarkGray),
+        }
+    }
+}
+
+/// A color in a theme file: a name such as `"light-magenta"`, `"#ff5f87"`
+/// or a 256 color index such as `"212"`, or a table with one color for
+/// light and one for dark backgrounds.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawColor {
+    Fixed(String),
+    Adaptive { light: String, dark: String },
+}
+
+impl RawColor {
+    fn parse(self) -> Result<AdaptiveColor> {
+        let parse = |color: String| {
+            color
+                .parse::<Color>()
+                .map_err(|_| Error::Config(format!("invalid color `{}`", color)))
+        };
+        match self {
+            RawColor::Fixed(color) => parse(color).map(AdaptiveColor::from),
+            RawColor::Adaptive { light, dark } => {
+                Ok(AdaptiveColor::new(parse(light)?, parse(dark)?))
+            }
+        }
+    }
+}
+
+/// What a theme file may set.
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawTheme {
+    base: Option<String>,
+    primary: Option<RawColor>,
+    accent: Option<RawColor>,
+    text: Option<RawColor>,
+    secondary: Option<RawColor>,
+    muted: Option<RawColor>,
+    selection: Option<RawColor>,
+    success: Option<RawColor>,
+    error: Option<RawColor>,
+    border: Option<RawColor>,
+    track: Option<RawColor>,
+}
+
+impl Theme {
+    /// Names accepted by [`Theme::builtin`] and by `base` in theme files.
+    pub const BUILTIN: [&'static str; 5] = ["default", "dracula", "nord", "gruvbox", "monochrome"];
+
+    /// One of the themes that ship with rustubble, see [`Theme::BUILTIN`].
+    pub fn builtin(name: &str) -> Option<Self> {
+        let theme = match name {
+            "default" => Self::default(),
+            "dracula" => Self {
+                primary: Color::Rgb(255, 121, 198).into(),
+                accent: Color::Rgb(139, 233, 253).into(),
+                text: Color::Rgb(248, 248, 242).into(),
+                secondary: Color::Rgb(189, 147, 249).into(),
+                muted: Color::Rgb(98, 114, 164).into(),
+                selection: Color::Rgb(68, 71, 90).into(),
+                success: Color::Rgb(80, 250, 123).into(),
+                error: Color::Rgb(255, 85, 85).into(),
+                border: Color::Rgb(98, 114, 164).into(),
+                track: Color::Rgb(68, 71, 90).into(),
+            },
+            "nord" => Self {
+                primary: Color::Rgb(136, 192, 208).into(),
+                accent: Color::Rgb(235, 203, 139).into(),
+                text: Color::Rgb(236, 239, 244).into(),
+                secondary: Color::Rgb(216, 222, 233).into(),
+                muted: Color::Rgb(76, 86, 106).into(),
+                selection: Color::Rgb(67, 76, 94).into(),
+                success: Color::Rgb(163, 190, 140).into(),
+                error: Color::Rgb(191, 97, 106).into(),
+                border: Color::Rgb(76, 86, 106).into(),
+                track: Color::Rgb(59, 66, 82).into(),
+            },
+            "gruvbox" => Self {
+                primary: Color::Rgb(254, 128, 25).into(),
+                accent: Color::Rgb(250, 189, 47).into(),
+                text: Color::Rgb(235, 219, 178).into(),
+                secondary: Color::Rgb(213, 196, 161).into(),
+                muted: Color::Rgb(146, 131, 116).into(),
+                selection: Color::Rgb(80, 73, 69).into(),
+                success: Color::Rgb(184, 187, 38).into(),
+                error: Color::Rgb(251, 73, 52).into(),
+                border: Color::Rgb(102, 92, 84).into(),
+                track: Color::Rgb(60, 56, 54).into(),
+            },
+            // Leaves colors to the terminal, for users who turned them off
+            "monochrome" => Self {
+                primary: Color::Reset.into(),
+                accent: Color::Reset.into(),
+                text: Color::Reset.into(),
+                secondary: Color::Reset.into(),
+                muted: Color::Reset.into(),
+                selection: AdaptiveColor::new(Color::Gray, Color::DarkGray),
+                success: Color::Reset.into(),
+                error: Color::Reset.into(),
+                border: Color::Reset.into(),
+                track: Color::DarkGray.into(),
+            },
+            _ => return None,
+        };
+        Some(theme)
+    }
+
+    /// Load a theme file, as JSON if the file ends in `.json` and as TOML
+    /// otherwise. A file can start from a built-in theme with `base` and
+    /// override some of its colors:
+    ///
+    /// ```toml
+    /// base = "nord"
+    /// primary = "#ff5f87"
+    /// muted = { light = "244", dark = "dark-gray" }
+    /// ```
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path)?;
+        if path.extension().is_some_and(|ext| ext == "json") {
+            Self::from_json(&source)
+        } else {
+            Self::from_toml(&source)
+        }
+    }
+
+    pub fn from_toml(source: &str) -> Result<Self> {
+        let raw = toml::from_str(source).map_err(|err| Error::Config(err.to_string()))?;
+        Self::from_raw(raw)
+    }
+
+    pub fn from_json(source: &str) -> Result<Self> {
+        let raw = serde_json::from_str(source).map_err(|err| Error::Config(err.to_string()))?;
+        Self::from_raw(raw)
+    }
+
+    fn from_raw(raw: RawTheme) -> Result<Self> {
+        let mut theme = match raw.base {
+            Some(base) => Self::builtin(&base)
+                .ok_or_else(|| Error::Config(format!("unknown theme `{}`", base)))?,
+            None => Self::default(),
+        };
+        for (slot, color) in [
+            (&mut theme.primary, raw.primary),
+            (&mut theme.accent, raw.accent),
+            (&mut theme.text, raw.text),
+            (&mut theme.secondary, raw.secondary),
+            (&mut theme.muted, raw.muted),
+            (&mut theme.selection, raw.selection),
+            (&mut theme.success, raw.success),
+            (&mut theme.error, raw.error),
+            (&mut theme.border, raw.border),
+            (&mut theme.track, raw.track),
+        ] {
+            if let Some(color) = color {
+                *slot = color.parse()?;
+            }
+        }
+        Ok(theme)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_themes_exist() {
+        for name in Theme::BUILTIN {
+            assert!(Theme::builtin(name).is_some(), "{}", name);
+        }
+        assert_eq!(Theme::builtin("solarized"), None);
+    }
+
+    #[test]
+    fn loads_toml_and_json() {
+        let toml = Theme::from_toml("base = \"nord\"\nprimary = \"#ff5f87\"").unwrap();
+        let json = Theme::from_json(r##"{"base": "nord", "primary": "#ff5f87"}"##).unwrap();
+        assert_eq!(toml, json);
+        assert_eq!(toml.primary, Color::Rgb(255, 95, 135).into());
+        assert_eq!(toml.muted, Theme::builtin("nord").unwrap().muted);
+
+        let indexed = Theme::from_toml("muted = \"242\"").unwrap();
+        assert_eq!(indexed.muted, Color::Indexed(242).into());
+        assert_eq!(indexed.primary, Theme::default().primary);
+    }
+
+    #[test]
+    fn loads_adaptive_colors() {
+        let theme = Theme::from_toml("muted = { light = \"244\", dark = \"dark-gray\" }").unwrap();
+        assert_eq!(
+            theme.muted,
+            AdaptiveColor::new(Color::Indexed(244), Color::DarkGray)
+        );
+        assert!(Theme::from_toml("muted = { light = \"244\" }").is_err());
+    }
+
+    #[test]
+    fn rejects_bad_themes() {
+        assert!(Theme::from_toml("primary = \"blurple\"").is_err());
+        assert!(Theme::from_toml("base = \"solarized\"").is_err());
+        assert!(Theme::from_json(r#"{"primry": "red"}"#).is_err());
+    }
+}
@@ -1,18 +1,29 @@
-use crossterm::event::{poll, read, Event, KeyCode, KeyModifiers};
+use crossterm::event::{poll, read, Event};
 use ratatui::{
     backend::Backend,
     layout::{Alignment, Rect},
     prelude::{CrosstermBackend, Terminal},
     widgets::Paragraph,
+    Frame,
 };
 use std::{
-    io::stdout,
+    io::{stdout, Write},
+    thread,
     time::{Duration, Instant},
 };
 
+use crate::{
+    event::{EventOutcome, HandleEvent, PromptResult},
+    fallback,
+    keymap::{Action, Component, KeyMap},
+    program::{Cmd, Model, Msg},
+    tui, Result,
+};
+
 pub struct Timer {
     start_time: Instant,
     duration: Duration,
+    keymap: KeyMap,
 }
 
 impl Timer {
@@ -20,9 +31,19 @@ impl Timer {
         Self {
             start_time: Instant::now(),
             duration,
+            keymap: KeyMap::default_for(Component::Timer),
         }
     }
 
+    pub fn with_keymap(mut self, keymap: KeyMap) -> Self {
+        self.keymap = keymap;
+        self
+    }
+
+    pub fn set_keymap(&mut self, keymap: KeyMap) {
+        self.keymap = keymap;
+    }
+
     pub fn time_remaining(&self) -> Duration {
         let elapsed = self.start_time.elapsed();
         if elapsed >= self.duration {
@@ -40,7 +61,7 @@ impl Timer {
         let minutes = remaining.as_secs() / 60;
         let hours = remaining.as_secs() / 3600;
 
-        let timer_text = match remaining.as_secs() {
+        match remaining.as_secs() {
             0 => format!("Exiting in {:03}ms", millis), // Only milliseconds
             1..=59 => format!("Exiting in {}.{:03}s", secs, millis), // Seconds and milliseconds
             60..=3599 => format!("Exiting in {}:{:02}.{:03}m", minutes, secs, millis), // Minutes and seconds
@@ -48,56 +69,94 @@ impl Timer {
                 "Exiting in {}:{:02}:{:02}.{:03}h",
                 hours, minutes, secs, millis
             ), // Hours and minutes
-        };
+        }
+    }
+
+    fn render<B: Backend>(&self, terminal: &mut Terminal<B>, x: u16, y: u16) -> Result<()> {
+        terminal.draw(|f| {
+            let size = f.size();
+            let area = Rect::new(x, y, size.width.saturating_sub(x), 1).intersection(size);
+
+            let timer_text = self.format_duration();
 
-        timer_text
+            let paragraph = Paragraph::new(timer_text).alignment(Alignment::Left);
+            f.render_widget(paragraph, area);
+        })?;
+        Ok(())
     }
+}
 
-    fn render<B: Backend>(&self, terminal: &mut Terminal<B>, x: u16, y: u16) {
-        terminal
-            .draw(|f| {
-                let size = f.size();
-                let area = Rect::new(x, y, size.width - x, 1);
+impl Timer {
+    /// Draw the component into `area`, for use inside a [`Program`](crate::program::Program).
+    pub fn view(&mut self, frame: &mut Frame, area: Rect) {
+        let paragraph = Paragraph::new(self.format_duration()).alignment(Alignment::Left);
+        frame.render_widget(paragraph, area);
+    }
+}
+
+impl HandleEvent for Timer {
+    fn handle_event(&mut self, event: &Event) -> EventOutcome {
+        let Event::Key(key) = event else {
+            return EventOutcome::Ignored;
+        };
+        match self.keymap.action(key) {
+            Some(Action::Interrupt) => EventOutcome::Interrupted,
+            Some(Action::Cancel) => EventOutcome::Cancelled,
+            _ => EventOutcome::Ignored,
+        }
+    }
+}
 
-                let timer_text = self.format_duration();
+impl<M> Model<M> for Timer {
+    fn update(&mut self, _msg: Msg<M>) -> Cmd<M> {
+        // The display is derived from the clock, so ticks only trigger a redraw
+        Cmd::none()
+    }
 
-                let paragraph = Paragraph::new(timer_text).alignment(Alignment::Left);
-                f.render_widget(paragraph, area);
-            })
-            .unwrap();
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        Timer::view(self, frame, area);
     }
 }
 
-pub fn handle_timer(timer: &mut Timer, x: u16, y: u16) {
+/// Count down until the timer runs out, which counts as a submission.
+pub fn handle_timer(timer: &mut Timer, x: u16, y: u16) -> Result<PromptResult<()>> {
+    // Without a terminal, print the time left every few seconds instead
+    if !fallback::is_interactive() {
+        let mut output = stdout();
+        while timer.time_remaining() > Duration::default() {
+            writeln!(output, "{}", timer.format_duration())?;
+            thread::sleep(timer.time_remaining().min(fallback::STATUS_INTERVAL));
+        }
+        return Ok(PromptResult::Submitted(()));
+    }
+    let _session = tui::TerminalSession::new()?;
     let mut stdout = stdout();
     let backend = CrosstermBackend::new(&mut stdout);
-    let mut terminal = Terminal::new(backend).unwrap();
+    let mut terminal = Terminal::new(backend)?;
 
-    terminal.clear().unwrap();
+    terminal.clear()?;
 
+    let mut result = PromptResult::Submitted(());
     while timer.time_remaining() > Duration::default() {
-        timer.render(&mut terminal, x, y);
-
-        if poll(Duration::from_millis(50)).unwrap() {
-            if let Ok(Event::Key(key)) = read() {
-                if key.code == KeyCode::Esc
-                    || (key.code == KeyCode::Char('c')
-                        && key.modifiers.contains(KeyModifiers::CONTROL))
-                    || key.code == KeyCode::Char('q')
-                {
-                    break;
-                }
+        timer.render(&mut terminal, x, y)?;
+
+        if poll(Duration::from_millis(50))? {
+            match timer.handle_event(&read()?) {
+                EventOutcome::Cancelled => result = PromptResult::Cancelled,
+                EventOutcome::Interrupted => result = PromptResult::Interrupted,
+                _ => continue,
             }
+            break;
         }
     }
 
-    terminal
-        .draw(|f| {
-            let size = f.size();
-            let area = Rect::new(x, y, size.width - x, 1);
+    terminal.draw(|f| {
+        let size = f.size();
+        let area = Rect::new(x, y, size.width.saturating_sub(x), 1).intersection(size);
 
-            let paragraph = Paragraph::new("All done").alignment(Alignment::Center);
-            f.render_widget(paragraph, area);
-        })
-        .unwrap();
+        let paragraph = Paragraph::new("All done").alignment(Alignment::Center);
+        f.render_widget(paragraph, area);
+    })?;
+
+    Ok(result)
 }
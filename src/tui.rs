@@ -1,21 +1,322 @@
-use std::io::{self, stdout, Stdout};
+use std::{
+    io::{self, stdout, Stdout},
+    mem, panic,
+    sync::{Mutex, MutexGuard, Once},
+};
 
-use crossterm::{execute, terminal::*};
-use ratatui::prelude::*;
+use crossterm::{
+    cursor,
+    event::{DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture},
+    execute,
+    terminal::*,
+};
+use ratatui::{
+    buffer::Buffer,
+    prelude::*,
+    widgets::{StatefulWidget, Widget},
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::{colors, Result};
 
 /// A type alias for the terminal type used in this application
 pub type Tui = Terminal<CrosstermBackend<Stdout>>;
 
 /// Initialize the terminal
-pub fn init() -> io::Result<Tui> {
+///
+/// Prefer a [`TerminalSession`], which also restores the terminal when the
+/// program panics or returns early.
+pub fn init() -> Result<Tui> {
     execute!(stdout(), EnterAlternateScreen)?;
     enable_raw_mode()?;
-    Terminal::new(CrosstermBackend::new(stdout()))
+    Ok(Terminal::new(CrosstermBackend::new(stdout()))?)
 }
 
 /// Restore the terminal to its original state
-pub fn restore() -> io::Result<()> {
+pub fn restore() -> Result<()> {
     execute!(stdout(), LeaveAlternateScreen)?;
     disable_raw_mode()?;
     Ok(())
 }
+
+/// Terminal modes a [`TerminalSession`] turns on in addition to raw mode.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SessionOptions {
+    pub alternate_screen: bool,
+    pub mouse_capture: bool,
+    pub bracketed_paste: bool,
+}
+
+impl SessionOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn alternate_screen(mut self, enabled: bool) -> Self {
+        self.alternate_screen = enabled;
+        self
+    }
+
+    pub fn mouse_capture(mut self, enabled: bool) -> Self {
+        self.mouse_capture = enabled;
+        self
+    }
+
+    pub fn bracketed_paste(mut self, enabled: bool) -> Self {
+        self.bracketed_paste = enabled;
+        self
+    }
+}
+
+// The terminal modes that are switched on, either by a session or by the
+// caller before the session started.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct Modes {
+    raw_mode: bool,
+    alternate_screen: bool,
+    mouse_capture: bool,
+    bracketed_paste: bool,
+}
+
+impl Modes {
+    const NONE: Modes = Modes {
+        raw_mode: false,
+        alternate_screen: false,
+        mouse_capture: false,
+        bracketed_paste: false,
+    };
+
+    fn intersection(self, other: Modes) -> Modes {
+        Modes {
+            raw_mode: self.raw_mode && other.raw_mode,
+            alternate_screen: self.alternate_screen && other.alternate_screen,
+            mouse_capture: self.mouse_capture && other.mouse_capture,
+            bracketed_paste: self.bracketed_paste && other.bracketed_paste,
+        }
+    }
+
+    fn without(self, other: Modes) -> Modes {
+        Modes {
+            raw_mode: self.raw_mode && !other.raw_mode,
+            alternate_screen: self.alternate_screen && !other.alternate_screen,
+            mouse_capture: self.mouse_capture && !other.mouse_capture,
+            bracketed_paste: self.bracketed_paste && !other.bracketed_paste,
+        }
+    }
+}
+
+// Modes switched on by live sessions, so the panic hook knows what to undo
+static ACTIVE_MODES: Mutex<Modes> = Mutex::new(Modes::NONE);
+static PANIC_HOOK: Once = Once::new();
+
+fn active_modes() -> MutexGuard<'static, Modes> {
+    // A panic while the lock is held must not stop the terminal from being restored
+    ACTIVE_MODES
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn install_panic_hook() {
+    PANIC_HOOK.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let modes = mem::take(&mut *active_modes());
+            let _ = disable_modes(modes);
+            previous(info);
+        }));
+    });
+}
+
+// Every mode is attempted even if an earlier one fails; the first error wins.
+fn disable_modes(modes: Modes) -> io::Result<()> {
+    let mut stdout = stdout();
+    let mut result = Ok(());
+    if modes.bracketed_paste {
+        result = result.and(execute!(stdout, DisableBracketedPaste));
+    }
+    if modes.mouse_capture {
+        result = result.and(execute!(stdout, DisableMouseCapture));
+    }
+    if modes.alternate_screen {
+        result = result.and(execute!(stdout, LeaveAlternateScreen));
+    }
+    if modes.raw_mode {
+        result = result.and(disable_raw_mode());
+    }
+    result
+}
+
+/// Puts the terminal into raw mode, plus any [`SessionOptions`], and restores
+/// it when dropped.
+///
+/// The first session installs a panic hook that restores the terminal before
+/// the previous hook prints the panic message. Modes that were already on when
+/// the session started, e.g. raw mode enabled by an enclosing session or by the
+/// caller, are left on when it ends.
+pub struct TerminalSession {
+    enabled: Modes,
+}
+
+impl TerminalSession {
+    /// Start a session that only enables raw mode.
+    pub fn new() -> Result<Self> {
+        Self::with_options(SessionOptions::default())
+    }
+
+    pub fn with_options(options: SessionOptions) -> Result<Self> {
+        install_panic_hook();
+        // Ask for the background before raw mode starts delivering keys, so
+        // the terminal's reply is not mixed into them
+        colors::detect_background();
+
+        let requested = Modes {
+            raw_mode: !is_raw_mode_enabled()?,
+            alternate_screen: options.alternate_screen,
+            mouse_capture: options.mouse_capture,
+            bracketed_paste: options.bracketed_paste,
+        };
+        let modes = requested.without(*active_modes());
+
+        // Dropping the session on error undoes the modes enabled so far
+        let mut session = TerminalSession {
+            enabled: Modes::NONE,
+        };
+        let mut stdout = stdout();
+        if modes.raw_mode {
+            enable_raw_mode()?;
+            session.mark(|modes| modes.raw_mode = true);
+        }
+        if modes.alternate_screen {
+            execute!(stdout, EnterAlternateScreen)?;
+            session.mark(|modes| modes.alternate_screen = true);
+        }
+        if modes.mouse_capture {
+            execute!(stdout, EnableMouseCapture)?;
+            session.mark(|modes| modes.mouse_capture = true);
+        }
+        if modes.bracketed_paste {
+            execute!(stdout, EnableBracketedPaste)?;
+            session.mark(|modes| modes.bracketed_paste = true);
+        }
+        Ok(session)
+    }
+
+    // Record a mode this session switched on
+    fn mark(&mut self, set: fn(&mut Modes)) {
+        set(&mut self.enabled);
+        set(&mut active_modes());
+    }
+
+    /// End the session now, reporting any error that dropping it would ignore.
+    pub fn restore(mut self) -> Result<()> {
+        Ok(self.release()?)
+    }
+
+    fn release(&mut self) -> io::Result<()> {
+        let mut active = active_modes();
+        // The panic hook may already have restored some of the modes
+        let modes = mem::take(&mut self.enabled).intersection(*active);
+        *active = active.without(modes);
+        disable_modes(modes)
+    }
+}
+
+impl Drop for TerminalSession {
+    fn drop(&mut self) {
+        let _ = self.release();
+    }
+}
+
+/// The area of `height` rows starting at `(x, y)` and extending to the right
+/// edge of the terminal, clipped to the terminal size.
+pub fn area_at(x: u16, y: u16, height: u16) -> Result<Rect> {
+    let (width, rows) = size()?;
+    let screen = Rect::new(0, 0, width, rows);
+    Ok(Rect::new(x, y, width.saturating_sub(x), height).intersection(screen))
+}
+
+/// Clear the screen from row `y` down. After a resize the terminal may have
+/// rewrapped what was drawn there, so prompts clear it before redrawing.
+pub fn clear_below(y: u16) -> Result<()> {
+    execute!(
+        stdout(),
+        cursor::MoveTo(0, y),
+        Clear(ClearType::FromCursorDown)
+    )?;
+    Ok(())
+}
+
+/// Render a widget straight to stdout inside `area`.
+///
+/// Unlike [`Terminal::draw`] every cell of the area is written, so whatever
+/// was printed there before is overwritten without clearing the screen.
+pub fn draw_widget<W: Widget>(widget: W, area: Rect) -> Result<()> {
+    let mut buffer = Buffer::empty(area);
+    widget.render(area, &mut buffer);
+    flush_buffer(&mut buffer)
+}
+
+/// Render a stateful widget straight to stdout inside `area`, see [`draw_widget`].
+pub fn draw_stateful_widget<W: StatefulWidget>(
+    widget: W,
+    area: Rect,
+    state: &mut W::State,
+) -> Result<()> {
+    let mut buffer = Buffer::empty(area);
+    widget.render(area, &mut buffer, state);
+    flush_buffer(&mut buffer)
+}
+
+/// Map the colors drawn into `frame` to the terminal's
+/// [`ColorProfile`](crate::colors::ColorProfile). Call it last inside
+/// [`Terminal::draw`] when drawing components into your own terminal.
+pub fn adapt_colors(frame: &mut Frame) {
+    colors::color_profile().adapt_buffer(frame.buffer_mut());
+}
+
+fn flush_buffer(buffer: &mut Buffer) -> Result<()> {
+    colors::color_profile().adapt_buffer(buffer);
+    let mut backend = CrosstermBackend::new(stdout());
+    // Wide characters cover the following cells, which must not be printed
+    let mut hidden = 0;
+    let cells = buffer.content.iter().enumerate().filter_map(|(i, cell)| {
+        if hidden > 0 {
+            hidden -= 1;
+            return None;
+        }
+        hidden = cell.symbol().width().saturating_sub(1);
+        let (x, y) = buffer.pos_of(i);
+        Some((x, y, cell))
+    });
+    backend.draw(cells)?;
+    Ok(Backend::flush(&mut backend)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn session_only_undoes_its_own_modes() {
+        let requested = Modes {
+            raw_mode: true,
+            alternate_screen: true,
+            mouse_capture: true,
+            bracketed_paste: false,
+        };
+        let already_on = Modes {
+            raw_mode: true,
+            ..Modes::NONE
+        };
+        let enabled = requested.without(already_on);
+        assert!(!enabled.raw_mode);
+        assert!(enabled.alternate_screen && enabled.mouse_capture);
+
+        // After a panic hook restored the alternate screen, only mouse capture is left
+        let still_active = Modes {
+            mouse_capture: true,
+            ..Modes::NONE
+        };
+        assert_eq!(enabled.intersection(still_active), still_active);
+    }
+}
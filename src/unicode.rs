@@ -0,0 +1,73 @@
+use std::borrow::Cow;
+
+use ratatui::layout::Alignment;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Number of terminal columns `text` takes up. East Asian wide characters
+/// and most emoji take two, combining marks none.
+///
+/// Components lay text out with this rather than `str::len`, which counts
+/// bytes, or `chars().count()`, which counts code points.
+pub fn width(text: &str) -> usize {
+    text.width()
+}
+
+/// `text` cut to at most `width` columns, ending in `…` if anything was
+/// cut. Characters are never split, so the result may be a column short
+/// when a wide character doesn't fit.
+pub fn truncate(text: &str, width: usize) -> Cow<'_, str> {
+    if self::width(text) <= width {
+        return Cow::Borrowed(text);
+    }
+    if width == 0 {
+        return Cow::Borrowed("");
+    }
+    let mut truncated = String::new();
+    let mut used = 0;
+    for grapheme in text.graphemes(true) {
+        used += grapheme.width();
+        if used > width - 1 {
+            break;
+        }
+        truncated.push_str(grapheme);
+    }
+    truncated.push('…');
+    Cow::Owned(truncated)
+}
+
+/// `text` padded with spaces to `width` columns, like the `{:<}`, `{:^}` and
+/// `{:>}` format specifiers but counting display columns. Text that is
+/// already wider is returned unchanged.
+pub fn pad(text: &str, width: usize, alignment: Alignment) -> String {
+    let fill = width.saturating_sub(self::width(text));
+    let left = match alignment {
+        Alignment::Left => 0,
+        Alignment::Center => fill / 2,
+        Alignment::Right => fill,
+    };
+    format!("{}{}{}", " ".repeat(left), text, " ".repeat(fill - left))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measures_truncates_and_pads() {
+        assert_eq!(width("abc"), 3);
+        assert_eq!(width("日本"), 4);
+        assert_eq!(width("e\u{301}"), 1);
+
+        assert_eq!(truncate("Charlie", 7), "Charlie");
+        assert_eq!(truncate("Charlie", 5), "Char…");
+        assert_eq!(truncate("日本語", 4), "日…");
+        assert_eq!(truncate("日本語", 1), "…");
+        assert_eq!(truncate("abc", 0), "");
+
+        assert_eq!(pad("日本", 7, Alignment::Left), "日本   ");
+        assert_eq!(pad("日本", 7, Alignment::Center), " 日本  ");
+        assert_eq!(pad("日本", 7, Alignment::Right), "   日本");
+        assert_eq!(pad("日本", 3, Alignment::Center), "日本");
+    }
+}
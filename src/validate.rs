@@ -0,0 +1,176 @@
+use regex_lite::Regex;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{Error, Result};
+
+/// A check run on the text of a [`TextInput`](crate::input::TextInput),
+/// returning the message to show when it fails.
+///
+/// Any `Fn(&str) -> Result<(), String>` closure works as a validator; the
+/// functions in this module build the common ones. All but [`required`]
+/// accept empty text, so combine them with it for fields that must be filled.
+pub type Validator = Box<dyn Fn(&str) -> std::result::Result<(), String>>;
+
+/// When a [`TextInput`](crate::input::TextInput) runs its validators.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ValidateOn {
+    /// After every edit, so the message follows the text as it is typed.
+    Change,
+    /// Only when Enter is pressed.
+    #[default]
+    Submit,
+}
+
+/// Refuse empty or blank text.
+pub fn required() -> Validator {
+    Box::new(|text| match text.trim().is_empty() {
+        true => Err("This field is required".to_string()),
+        false => Ok(()),
+    })
+}
+
+/// Refuse text shorter than `min` characters.
+pub fn min_length(min: usize) -> Validator {
+    Box::new(move |text| {
+        let length = length(text);
+        match length > 0 && length < min {
+            true => Err(format!("Must be at least {} characters", min)),
+            false => Ok(()),
+        }
+    })
+}
+
+/// Refuse text longer than `max` characters.
+pub fn max_length(max: usize) -> Validator {
+    Box::new(move |text| match length(text) > max {
+        true => Err(format!("Must be at most {} characters", max)),
+        false => Ok(()),
+    })
+}
+
+/// Refuse text that doesn't match the regular expression `pattern`, showing
+/// `message`. The pattern matches anywhere in the text unless it is anchored
+/// with `^` and `$`.
+///
+/// Fails with [`Error::Config`] if `pattern` is not a valid expression.
+pub fn pattern(pattern: &str, message: &str) -> Result<Validator> {
+    let regex = Regex::new(pattern)
+        .map_err(|err| Error::Config(format!("invalid pattern `{}`: {}", pattern, err)))?;
+    let message = message.to_string();
+    Ok(Box::new(move |text| {
+        match text.is_empty() || regex.is_match(text) {
+            true => Ok(()),
+            false => Err(message.clone()),
+        }
+    }))
+}
+
+/// Refuse text that isn't a number from `min` to `max`, inclusive.
+pub fn number_between(min: f64, max: f64) -> Validator {
+    Box::new(move |text| {
+        if text.is_empty() {
+            return Ok(());
+        }
+        match text.trim().parse::<f64>() {
+            Ok(number) if (min..=max).contains(&number) => Ok(()),
+            Ok(number) if number.is_finite() => Err(format!("Must be between {} and {}", min, max)),
+            _ => Err("Must be a number".to_string()),
+        }
+    })
+}
+
+/// Refuse text that isn't shaped like an email address: a name, `@` and a
+/// domain with a dot, without spaces. Whether the address exists is not
+/// checked.
+pub fn email() -> Validator {
+    Box::new(|text| {
+        let valid = match text.split_once('@') {
+            Some((name, domain)) => {
+                !name.is_empty()
+                    && !text.contains(char::is_whitespace)
+                    && !domain.contains('@')
+                    && domain.split('.').count() > 1
+                    && domain.split('.').all(|part| !part.is_empty())
+            }
+            None => false,
+        };
+        match text.is_empty() || valid {
+            true => Ok(()),
+            false => Err("Must be an email address".to_string()),
+        }
+    })
+}
+
+/// Refuse text that isn't shaped like an `http` or `https` URL with a host.
+pub fn url() -> Validator {
+    Box::new(|text| {
+        let rest = text
+            .strip_prefix("https://")
+            .or_else(|| text.strip_prefix("http://"));
+        let valid = match rest {
+            Some(rest) => {
+                let host = rest.split(['/', '?', '#']).next().unwrap_or_default();
+                !host.is_empty() && !text.contains(char::is_whitespace)
+            }
+            None => false,
+        };
+        match text.is_empty() || valid {
+            true => Ok(()),
+            false => Err("Must be a URL starting with http:// or https://".to_string()),
+        }
+    })
+}
+
+// Characters as the user sees them
+fn length(text: &str) -> usize {
+    text.graphemes(true).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checks_lengths_and_numbers() {
+        assert!(required()("  ").is_err());
+        assert!(required()("a").is_ok());
+        assert!(min_length(3)("ab").is_err());
+        assert!(min_length(3)("").is_ok());
+        assert!(min_length(3)("日本語").is_ok());
+        assert_eq!(
+            max_length(2)("abc"),
+            Err("Must be at most 2 characters".to_string())
+        );
+
+        let port = number_between(1.0, 65535.0);
+        assert!(port("8080").is_ok());
+        assert_eq!(port("0"), Err("Must be between 1 and 65535".to_string()));
+        assert_eq!(port("http"), Err("Must be a number".to_string()));
+        assert_eq!(port("NaN"), Err("Must be a number".to_string()));
+    }
+
+    #[test]
+    fn checks_shapes_and_patterns() {
+        let email = email();
+        assert!(email("ada@example.com").is_ok());
+        for text in [
+            "ada",
+            "@example.com",
+            "ada@example",
+            "ada@ex..com",
+            "a da@x.io",
+        ] {
+            assert!(email(text).is_err(), "{} should be refused", text);
+        }
+
+        let url = url();
+        assert!(url("https://example.com/path?q=1").is_ok());
+        assert!(url("example.com").is_err());
+        assert!(url("http:///path").is_err());
+
+        let slug = pattern("^[a-z0-9-]+$", "Use lowercase letters, digits and dashes").unwrap();
+        assert!(slug("my-project").is_ok());
+        assert!(slug("My Project").is_err());
+        assert!(matches!(pattern("(", ""), Err(Error::Config(_))));
+    }
+}
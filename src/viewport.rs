@@ -1,220 +1,420 @@
 use crossterm::{
-    cursor::MoveTo, event::{self, Event, KeyCode, KeyEvent, KeyModifiers, MouseEvent, MouseEventKind}, execute, style::Print, terminal::{Clear, ClearType}
+    event::{self, Event, MouseEventKind},
+    execute,
+    terminal::{Clear, ClearType},
 };
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Style,
+    text::{Line, Span},
+    widgets::{Paragraph, StatefulWidget, Widget},
+    Frame,
+};
+use std::io::{self, stdout, BufRead, Write};
 use textwrap::{wrap, Options};
-use std::io::{stdout, Write};
+
+use crate::{
+    event::{EventOutcome, HandleEvent, PromptResult},
+    fallback,
+    inline::InlinePrompt,
+    keymap::{Action, Component, KeyBuffer, KeyMap, Resolved},
+    program::{Cmd, Model, Msg},
+    theme::Theme,
+    tui, unicode, Result,
+};
 
 pub struct Viewport {
     header: String,
     content: String, // Each string represents a line of the content
     padding: usize,
+    // Visible size of the content area asked for, which shrinks to fit the terminal
+    height: usize,
+    width: usize,
+    state: ViewportState,
+    keymap: KeyMap,
+    keys: KeyBuffer,
+    theme: Theme,
+}
+
+/// Scroll state of a [`Viewport`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ViewportState {
     scroll_offset: usize,
-    height: usize, // Visible height of the content area
-    width: usize,  // Visible width of the content area
+    // The size the content was last laid out at, zero until it is drawn
+    width: usize,
+    height: usize,
+}
+
+impl ViewportState {
+    pub fn scroll_offset(&self) -> usize {
+        self.scroll_offset
+    }
 }
 
 impl Viewport {
-    pub fn new(header: String, content: String,  height: usize,width: usize , padding: usize) -> Self {
+    pub fn new(
+        header: String,
+        content: String,
+        height: usize,
+        width: usize,
+        padding: usize,
+    ) -> Self {
         Self {
             header,
             content,
-            scroll_offset: 0,
             padding,
             height,
-            width
+            width,
+            state: ViewportState::default(),
+            keymap: KeyMap::default_for(Component::Viewport),
+            keys: KeyBuffer::new().counts(true),
+            theme: Theme::default(),
         }
     }
 
-    pub fn render(&self, x: u16, y: u16) {
-        let mut stdout = stdout();
+    pub fn with_keymap(mut self, keymap: KeyMap) -> Self {
+        self.set_keymap(keymap);
+        self
+    }
 
-        // Clear the terminal
-        execute!(stdout, Clear(ClearType::All)).unwrap();
-
-        // Render the header
-        // println!("{}", self.header);
-        self.render_header_box(x, y);
-
-        // Render the content with vertical scrolling
-        let content_options = Options::new(self.width - (2* self.padding));
-        let wrapped_lines: Vec<String> = wrap(&self.content, content_options).into_iter().map(|cow| cow.into_owned()).collect();
-        let visible_content = wrapped_lines.iter().skip(self.scroll_offset).take(self.height);
-
-        let mut line_number = y + 4;
-        for line in visible_content {
-            execute!(
-                stdout,
-                MoveTo(x, line_number),
-                Clear(ClearType::CurrentLine),
-                Print(format!("{:<width$}", line, width=self.width)) // Fill line with spaces to width
-            )
-            .unwrap();
-            line_number += 1;
-        }
+    pub fn set_keymap(&mut self, keymap: KeyMap) {
+        self.keymap = keymap;
+        self.keys.clear();
+    }
 
-        // Fill the rest of the viewport with blank lines if content is less than viewport height
-        while line_number < y + 3 + self.height as u16 {
-            execute!(
-                stdout,
-                MoveTo(x, line_number),
-                Clear(ClearType::CurrentLine),
-                Print(format!("{:width$}", " ", width=self.width)) // Fill line with spaces to width
-            )
-            .unwrap();
-            line_number += 1;
-        }
-        // Update and render the footer
-        let progress = (self.scroll_offset as f32 / (wrapped_lines.len() - self.height) as f32 * 100.0).min(100.0);
-        let horizontal_line = "─".repeat(self.width - 2);
-        let footer_with_progress = format!("{} {:.2}%",horizontal_line, progress);
-        // println!("{}", footer_with_progress);
-
-        execute!(
-            std::io::stdout(),
-            MoveTo(x, y + 5 + self.height as u16),
-            Clear(ClearType::CurrentLine),
-            Print(&footer_with_progress),
-        )
-        .unwrap();
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.set_theme(theme);
+        self
+    }
 
-        self.hide_cursor();
-        stdout.flush().unwrap();
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
     }
 
-    fn render_header_box(&self, x: u16, y: u16) {
+    pub fn state(&self) -> &ViewportState {
+        &self.state
+    }
+
+    /// Print the header and content on plain lines, for when there is no
+    /// terminal to page through them in.
+    pub(crate) fn prompt_plain(&self, output: &mut impl Write) -> Result<PromptResult<()>> {
+        writeln!(output, "{}\n\n{}", self.header, self.content)?;
+        Ok(PromptResult::Submitted(()))
+    }
+
+    /// Number of rows the viewport needs: header box, content and footer.
+    pub fn total_height(&self) -> u16 {
+        (6 + self.height) as u16
+    }
+
+    pub fn render(&mut self, x: u16, y: u16) -> Result<()> {
         let mut stdout = stdout();
-        // get self.header length
-        let header_length = self.header.len();
-        let top_border = format!(
-            "┌{}┐",
-            "─".repeat(header_length + 1)
-        );
 
-        let bottom_border = format!(
-            "└{}┘",
-            "─".repeat(header_length + 1)
-        );
+        // Clear the terminal
+        execute!(stdout, Clear(ClearType::All))?;
 
-        let text_content = format!(
-            "│ {}│",
-            self.header
-        );
+        let area = tui::area_at(x, y, self.total_height())?;
+        self.fit(area);
+        tui::draw_widget(&*self, area)?;
 
-        execute!(
-            stdout,
-            MoveTo(x, y),
-            Clear(ClearType::CurrentLine),
-            Print(&top_border)
-        )
-        .unwrap();
+        self.hide_cursor()?;
+        stdout.flush()?;
+        Ok(())
+    }
 
-        execute!(
-            stdout,
-            MoveTo(x, y + 1),
-            Clear(ClearType::CurrentLine),
-            Print(text_content)
-        )
-        .unwrap();
-        
-        execute!(
-            stdout,
-            MoveTo(x, y + 2),
-            Clear(ClearType::CurrentLine),
-            Print(&bottom_border)
-        )
-        .unwrap();
+    /// Draw the component into `area`, for use inside a [`Program`](crate::program::Program).
+    pub fn view(&mut self, frame: &mut Frame, area: Rect) {
+        self.fit(area);
+        frame.render_stateful_widget(&*self, area, &mut self.state.clone());
+    }
 
-       
+    /// Lay the content out for `area`, which may be smaller than the size
+    /// the viewport was created with. When the width changes the text is
+    /// rewrapped and the line that was at the top stays in view.
+    pub fn fit(&mut self, area: Rect) {
+        let width = self.width.min(area.width as usize);
+        let height = self.height.min(area.height.saturating_sub(6) as usize);
+        if (width, height) == self.size() {
+            return;
+        }
+        // Whitespace at the wrap points is dropped, so count the other
+        // characters above the top line to find it again after rewrapping
+        let above: usize = self
+            .wrapped_lines()
+            .iter()
+            .take(self.state.scroll_offset)
+            .map(|line| visible_chars(line))
+            .sum();
+        self.state.width = width;
+        self.state.height = height;
+
+        let mut seen = 0;
+        let lines = self.wrapped_lines();
+        let top = lines
+            .iter()
+            .position(|line| {
+                seen += visible_chars(line);
+                seen > above
+            })
+            .unwrap_or(lines.len());
+        self.state.scroll_offset = top.min(lines.len().saturating_sub(height));
+    }
+
+    // The size the content is currently laid out at
+    fn size(&self) -> (usize, usize) {
+        if self.state.width == 0 {
+            (self.width, self.height)
+        } else {
+            (self.state.width, self.state.height)
+        }
+    }
 
+    fn visible_height(&self) -> usize {
+        self.size().1
     }
 
+    fn wrapped_lines(&self) -> Vec<String> {
+        let width = self.size().0.saturating_sub(2 * self.padding).max(1);
+        wrap(&self.content, Options::new(width))
+            .into_iter()
+            .map(|cow| cow.into_owned())
+            .collect()
+    }
+
+    fn scroll_percent(&self, scroll_offset: usize, total_lines: usize) -> f32 {
+        let max_offset = total_lines.saturating_sub(self.visible_height());
+        if max_offset == 0 {
+            return 100.0;
+        }
+        (scroll_offset as f32 / max_offset as f32 * 100.0).min(100.0)
+    }
 
     fn scroll_up(&mut self) {
-        if self.scroll_offset > 0 {
-            self.scroll_offset -= 1;
+        if self.state.scroll_offset > 0 {
+            self.state.scroll_offset -= 1;
         }
     }
+
     fn scroll_down(&mut self) {
         // Calculate the total number of lines that content can be wrapped into
-        let content_options = Options::new(self.width - (2 * self.padding));
-        let wrapped_lines: Vec<String> = wrap(&self.content, content_options).into_iter().map(|cow| cow.into_owned()).collect();
-        
+        let wrapped_lines = self.wrapped_lines();
+
         // Check if scrolling down is possible by comparing scroll_offset with the number of lines minus the viewport height
-        if self.scroll_offset < wrapped_lines.len().saturating_sub(self.height) {
-            self.scroll_offset += 1;
+        if self.state.scroll_offset < wrapped_lines.len().saturating_sub(self.visible_height()) {
+            self.state.scroll_offset += 1;
         }
     }
 
+    /// Scroll so that line `n`, counting from 1, is at the top, or to the end.
+    fn scroll_to(&mut self, n: Option<usize>) {
+        let max_offset = self
+            .wrapped_lines()
+            .len()
+            .saturating_sub(self.visible_height());
+        self.state.scroll_offset = n.map_or(max_offset, |n| n.saturating_sub(1).min(max_offset));
+    }
+
     fn page_down(&mut self) {
-        let content_options = Options::new(self.width - (2 * self.padding));
-        let wrapped_lines: Vec<String> = wrap(&self.content, content_options).into_iter().map(|cow| cow.into_owned()).collect();
-        let max_offset = wrapped_lines.len().saturating_sub(self.height);
-        
-        if self.scroll_offset < max_offset {
-            self.scroll_offset = usize::min(self.scroll_offset + self.height, max_offset);
+        let wrapped_lines = self.wrapped_lines();
+        let max_offset = wrapped_lines.len().saturating_sub(self.visible_height());
+
+        if self.state.scroll_offset < max_offset {
+            self.state.scroll_offset =
+                usize::min(self.state.scroll_offset + self.visible_height(), max_offset);
         }
     }
 
     fn page_up(&mut self) {
         // Scroll up by the height of the viewport
-        self.scroll_offset = self.scroll_offset.saturating_sub(self.height);
+        self.state.scroll_offset = self
+            .state
+            .scroll_offset
+            .saturating_sub(self.visible_height());
     }
 
-    fn hide_cursor(&self) {
-        print!("\x1B[?25l"); // Hide the cursor
-        std::io::stdout().flush().unwrap(); // Ensure the command is applied immediately
+    fn hide_cursor(&self) -> Result<()> {
+        write!(stdout(), "\x1B[?25l")?; // Hide the cursor
+        stdout().flush()?; // Ensure the command is applied immediately
+        Ok(())
     }
 
-    pub fn show_cursor(&self) {
-        print!("\x1B[?25h"); // Show the cursor
-        std::io::stdout().flush().unwrap(); // Ensure the command is applied immediately
+    pub fn show_cursor(&self) -> Result<()> {
+        write!(stdout(), "\x1B[?25h")?; // Show the cursor
+        stdout().flush()?; // Ensure the command is applied immediately
+        Ok(())
     }
+}
 
-    // Methods to handle user input and scrolling go here...
+impl StatefulWidget for &Viewport {
+    type State = ViewportState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut ViewportState) {
+        // Render the header box
+        let header_border = "─".repeat(unicode::width(&self.header) + 1);
+        let border_style = Style::default().fg(self.theme.border.resolve());
+        let mut lines = vec![
+            Line::styled(format!("┌{}┐", header_border), border_style),
+            Line::from(vec![
+                Span::styled("│ ", border_style),
+                Span::styled(
+                    self.header.as_str(),
+                    Style::default().fg(self.theme.primary.resolve()),
+                ),
+                Span::styled("│", border_style),
+            ]),
+            Line::styled(format!("└{}┘", header_border), border_style),
+            Line::default(),
+        ];
+
+        // Render the content with vertical scrolling, padded to the viewport height
+        let (width, height) = self.size();
+        let wrapped_lines = self.wrapped_lines();
+        lines.extend(
+            wrapped_lines
+                .iter()
+                .skip(state.scroll_offset)
+                .take(height)
+                .map(|line| Line::from(line.as_str())),
+        );
+        lines.resize(4 + height + 1, Line::default());
+
+        // Render the footer with the scroll progress
+        let progress = self.scroll_percent(state.scroll_offset, wrapped_lines.len());
+        // An unfinished key sequence such as `5 g` replaces the start of the rule
+        let mut footer = Vec::new();
+        let mut rule_width = width.saturating_sub(2);
+        if let Some(pending) = self.keys.pending() {
+            let label = format!("{}… ", pending);
+            rule_width = rule_width.saturating_sub(unicode::width(&label));
+            footer.push(Span::styled(
+                label,
+                Style::default().fg(self.theme.accent.resolve()),
+            ));
+        }
+        footer.push(Span::styled("─".repeat(rule_width), border_style));
+        footer.push(Span::styled(
+            format!(" {:.2}%", progress),
+            Style::default().fg(self.theme.muted.resolve()),
+        ));
+        lines.push(Line::from(footer));
+
+        Paragraph::new(lines).render(area, buf);
+    }
 }
 
-pub fn handle_viewport(viewport: &mut Viewport, x: u16, y: u16) {
-    viewport.render(x,y);
-    loop {
-        if let Ok(true) = event::poll(std::time::Duration::from_millis(100)) {
-            if let Ok(event) = event::read() {
-                match event {
-                   
-                    Event::Key(KeyEvent {
-                        code, modifiers, ..
-                    }) => match code {
-                        KeyCode::Up | KeyCode::Char('k') => viewport.scroll_up(),
-                        KeyCode::Down | KeyCode::Char('j') => viewport.scroll_down(),
-                        KeyCode::Esc | KeyCode::Char('q') => {
-                            break
-                        }
-                        KeyCode::Char('c') if modifiers == KeyModifiers::CONTROL => {
-                            break
-                        }
-                        KeyCode::PageUp => viewport.page_up(),
-                        KeyCode::PageDown => viewport.page_down(),
-                        _ => {}
-                    },
-                    Event::Mouse(MouseEvent {
-                        kind: MouseEventKind::ScrollUp,
-                        ..
-                    }) => viewport.scroll_up(),
-                    Event::Mouse(MouseEvent {
-                        kind: MouseEventKind::ScrollDown,
-                        ..
-                    }) => viewport.scroll_down(),
-                    _ => {}
+impl Widget for &Viewport {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        StatefulWidget::render(self, area, buf, &mut self.state.clone());
+    }
+}
+
+impl HandleEvent for Viewport {
+    fn handle_event(&mut self, event: &Event) -> EventOutcome {
+        match event {
+            Event::Key(key) => {
+                let (action, count) = match self.keys.resolve(&self.keymap, key) {
+                    Resolved::Action { action, count } => (action, count),
+                    Resolved::Pending => return EventOutcome::Consumed,
+                    Resolved::Unbound => return EventOutcome::Ignored,
+                };
+                let repeat = count.unwrap_or(1);
+                match action {
+                    Action::Up => (0..repeat).for_each(|_| self.scroll_up()),
+                    Action::Down => (0..repeat).for_each(|_| self.scroll_down()),
+                    Action::PageUp => (0..repeat).for_each(|_| self.page_up()),
+                    Action::PageDown => (0..repeat).for_each(|_| self.page_down()),
+                    Action::Top => self.scroll_to(Some(1)),
+                    Action::Bottom => self.scroll_to(count),
+                    Action::Submit => return EventOutcome::Submitted,
+                    Action::Cancel => return EventOutcome::Cancelled,
+                    Action::Interrupt => return EventOutcome::Interrupted,
+                    _ => return EventOutcome::Ignored,
                 }
             }
-            viewport.render(x,y);
+            Event::Mouse(mouse) => match mouse.kind {
+                MouseEventKind::ScrollUp => self.scroll_up(),
+                MouseEventKind::ScrollDown => self.scroll_down(),
+                _ => return EventOutcome::Ignored,
+            },
+            // The next render fits the content to the new size
+            Event::Resize(..) => {}
+            _ => return EventOutcome::Ignored,
+        }
+        EventOutcome::Consumed
+    }
+}
+
+impl InlinePrompt for Viewport {
+    type Value = ();
+
+    fn inline_height(&self) -> u16 {
+        self.total_height()
+    }
+
+    fn submitted_value(&self) -> Option<()> {
+        Some(())
+    }
+
+    fn fallback_value(
+        &mut self,
+        _input: &mut impl BufRead,
+        output: &mut impl Write,
+    ) -> Result<PromptResult<()>> {
+        self.prompt_plain(output)
+    }
 
+    fn summary(&self, _value: &()) -> String {
+        self.header.clone()
+    }
+
+    fn theme(&self) -> Theme {
+        self.theme
+    }
+}
+
+impl<M> Model<M> for Viewport {
+    fn update(&mut self, msg: Msg<M>) -> Cmd<M> {
+        if let Some(event) = msg.to_event() {
+            self.handle_event(&event);
+        }
+        Cmd::none()
+    }
+
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        Viewport::view(self, frame, area);
+    }
+}
+
+/// Page through the content until the reader confirms with Enter or leaves.
+pub fn handle_viewport(viewport: &mut Viewport, x: u16, y: u16) -> Result<PromptResult<()>> {
+    // Without a terminal there is nothing to page through, so print it all
+    if !fallback::is_interactive() {
+        return viewport.prompt_plain(&mut io::stdout());
+    }
+    // Mouse capture lets the wheel scroll the content
+    let _session =
+        tui::TerminalSession::with_options(tui::SessionOptions::new().mouse_capture(true))?;
+    viewport.render(x, y)?;
+    loop {
+        match viewport.handle_event(&event::read()?) {
+            EventOutcome::Submitted => return Ok(PromptResult::Submitted(())),
+            EventOutcome::Cancelled => return Ok(PromptResult::Cancelled),
+            EventOutcome::Interrupted => return Ok(PromptResult::Interrupted),
+            EventOutcome::Consumed => viewport.render(x, y)?,
+            EventOutcome::Ignored => {}
         }
     }
 }
 
+fn visible_chars(line: &str) -> usize {
+    line.chars().filter(|c| !c.is_whitespace()).count()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*; // Import everything from the outer module.
+    use crossterm::event::{KeyCode, KeyModifiers};
 
     // Helper function to create a viewport for testing
     fn setup_viewport(content: &str, height: usize, width: usize) -> Viewport {
@@ -223,7 +423,7 @@ mod tests {
             content.to_string(),
             height,
             width,
-            2
+            2,
         )
     }
 
@@ -232,7 +432,10 @@ mod tests {
         let content = "Short content";
         let mut viewport = setup_viewport(content, 5, 50); // Height is more than needed for the content
         viewport.scroll_down();
-        assert_eq!(viewport.scroll_offset, 0, "Scroll offset should not increase when all content fits within the viewport");
+        assert_eq!(
+            viewport.state.scroll_offset, 0,
+            "Scroll offset should not increase when all content fits within the viewport"
+        );
     }
 
     #[test]
@@ -240,14 +443,20 @@ mod tests {
         let content = "Line 1\nLine 2\nLine 3\nLine 4\nLine 5\nLine 6\nLine 7";
         let mut viewport = setup_viewport(content, 3, 50); // Only part of the content fits at a time
         viewport.scroll_down();
-        assert_eq!(viewport.scroll_offset, 1, "Scroll offset should increase by 1");
+        assert_eq!(
+            viewport.state.scroll_offset, 1,
+            "Scroll offset should increase by 1"
+        );
 
         // Scroll to the bottom
         viewport.scroll_down();
         viewport.scroll_down();
         viewport.scroll_down();
         viewport.scroll_down(); // Try to scroll past the end
-        assert_eq!(viewport.scroll_offset, 4, "Scroll offset should stop at the maximum scrollable content");
+        assert_eq!(
+            viewport.state.scroll_offset, 4,
+            "Scroll offset should stop at the maximum scrollable content"
+        );
     }
 
     #[test]
@@ -257,26 +466,96 @@ mod tests {
         viewport.scroll_down(); // Set the offset to 1
         viewport.scroll_down(); // Set the offset to 2
         viewport.scroll_up();
-        assert_eq!(viewport.scroll_offset, 1, "Scroll offset should decrease by 1");
+        assert_eq!(
+            viewport.state.scroll_offset, 1,
+            "Scroll offset should decrease by 1"
+        );
 
         // Try scrolling past the beginning
         viewport.scroll_up();
         viewport.scroll_up();
-        assert_eq!(viewport.scroll_offset, 0, "Scroll offset should not go below 0");
+        assert_eq!(
+            viewport.state.scroll_offset, 0,
+            "Scroll offset should not go below 0"
+        );
+    }
+
+    #[test]
+    fn test_handle_event_outcomes() {
+        let content = "Line 1\nLine 2\nLine 3\nLine 4";
+        let mut viewport = setup_viewport(content, 3, 50);
+        let key = |code| Event::Key(crossterm::event::KeyEvent::new(code, KeyModifiers::NONE));
+        assert_eq!(
+            viewport.handle_event(&key(KeyCode::Char('j'))),
+            EventOutcome::Consumed
+        );
+        assert_eq!(viewport.state.scroll_offset, 1);
+        assert_eq!(
+            viewport.handle_event(&key(KeyCode::Tab)),
+            EventOutcome::Ignored
+        );
+        assert_eq!(
+            viewport.handle_event(&key(KeyCode::Enter)),
+            EventOutcome::Submitted
+        );
+        assert_eq!(
+            viewport.handle_event(&key(KeyCode::Char('q'))),
+            EventOutcome::Cancelled
+        );
     }
 
     #[test]
     fn test_page_down_and_page_up() {
-        let content = "Line 1\nLine 2\nLine 3\nLine 4\nLine 5\nLine 6\nLine 7\nLine 8\nLine 9\nLine 10";
+        let content =
+            "Line 1\nLine 2\nLine 3\nLine 4\nLine 5\nLine 6\nLine 7\nLine 8\nLine 9\nLine 10";
         let mut viewport = setup_viewport(content, 3, 50); // Setup viewport with 3 lines visible at a time
         viewport.page_down();
-        assert_eq!(viewport.scroll_offset, 3, "Page down should scroll down by the height of the viewport");
+        assert_eq!(
+            viewport.state.scroll_offset, 3,
+            "Page down should scroll down by the height of the viewport"
+        );
 
         viewport.page_down();
-        assert!(viewport.scroll_offset <= 7, "Second page down should not scroll past the content length minus viewport height");
+        assert!(
+            viewport.state.scroll_offset <= 7,
+            "Second page down should not scroll past the content length minus viewport height"
+        );
 
         // Test page up
         viewport.page_up();
-        assert_eq!(viewport.scroll_offset, 3, "Page up should scroll up by the height of the viewport");
+        assert_eq!(
+            viewport.state.scroll_offset, 3,
+            "Page up should scroll up by the height of the viewport"
+        );
+    }
+
+    #[test]
+    fn test_resize_rewraps_and_keeps_position() {
+        let content = "one two three four five six seven eight nine ten eleven twelve";
+        let mut viewport = setup_viewport(content, 3, 20);
+        viewport.fit(Rect::new(0, 0, 80, 24));
+        assert_eq!(viewport.wrapped_lines().len(), 5);
+        viewport.scroll_down();
+        viewport.scroll_down();
+        assert_eq!(viewport.wrapped_lines()[2], "seven eight");
+
+        // Narrower, the line starting with "seven" moves down but stays on top
+        viewport.fit(Rect::new(0, 0, 12, 24));
+        let lines = viewport.wrapped_lines();
+        assert_eq!(lines.len(), 9);
+        assert!(lines[viewport.state.scroll_offset].starts_with("seven"));
+
+        // Fewer rows than asked for show less of the content at once
+        viewport.fit(Rect::new(0, 0, 12, 7));
+        assert_eq!(viewport.visible_height(), 1);
+        assert!(lines[viewport.state.scroll_offset].starts_with("seven"));
+
+        // Back to full size the text wraps as before
+        viewport.fit(Rect::new(0, 0, 80, 24));
+        assert_eq!(viewport.state.scroll_offset, 2);
+        assert_eq!(
+            viewport.handle_event(&Event::Resize(80, 24)),
+            EventOutcome::Consumed
+        );
     }
 }